@@ -1,7 +1,12 @@
 use crate::{GameEvent, PrioritizedEvent, Priority};
 use anyhow::Result;
-use crossbeam_channel::Receiver;
+use crossbeam_channel::{Receiver, RecvTimeoutError};
 use log::{debug, info};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 /// ゲームループの設定
@@ -9,8 +14,14 @@ use std::time::{Duration, Instant};
 pub struct LoopConfig {
     /// 目標フレームレート（FPS）
     pub target_fps: u32,
-    /// 最大更新回数/秒
+    /// 1フレームで実行する更新の最大回数（長く止まった後に追いつく量を制限する）
     pub max_updates: u32,
+    /// 1フレームで処理するLow優先度イベントの上限
+    pub max_low_events_per_frame: usize,
+    /// イベントが届かないときにフレームを処理する間隔（描画の要否を確認する）
+    pub idle_heartbeat: Duration,
+    /// 描画の要否にかかわらず目標フレームレートで描画し続ける（プロファイル用）
+    pub force_continuous: bool,
 }
 
 impl Default for LoopConfig {
@@ -18,6 +29,136 @@ impl Default for LoopConfig {
         LoopConfig {
             target_fps: 60,
             max_updates: 60,
+            max_low_events_per_frame: 16,
+            idle_heartbeat: Duration::from_millis(100),
+            force_continuous: false,
+        }
+    }
+}
+
+/// 固定時間ステップごとに呼ばれる更新コールバック（引数は経過秒）
+pub type UpdateCallback = Box<dyn FnMut(f32) -> Result<()>>;
+/// フレームごとに呼ばれる描画コールバック
+pub type RenderCallback = Box<dyn FnMut() -> Result<()>>;
+/// フレームごとに描画が必要か判定するコールバック
+pub type RenderGate = Box<dyn FnMut() -> bool>;
+/// 優先度順に取り出したイベントごとに呼ばれるコールバック
+pub type EventCallback = Box<dyn FnMut(&PrioritizedEvent) -> Result<()>>;
+
+/// 優先度キュー内のイベント（優先度が高く、通し番号が小さいものから取り出す）
+struct QueuedEvent(PrioritizedEvent);
+
+impl QueuedEvent {
+    fn key(&self) -> (Priority, u64) {
+        (self.0.priority, self.0.sequence)
+    }
+}
+
+impl PartialEq for QueuedEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for QueuedEvent {}
+
+impl PartialOrd for QueuedEvent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedEvent {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// 1区間（更新・描画など）の処理時間の統計
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseStats {
+    pub average: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl PhaseStats {
+    /// 処理時間の列から統計を求める（空なら全て0）
+    fn from_samples(samples: impl Iterator<Item = Duration> + Clone) -> Self {
+        let count = samples.clone().count() as u32;
+        if count == 0 {
+            return Self::default();
+        }
+        Self {
+            average: samples.clone().sum::<Duration>() / count,
+            min: samples.clone().min().unwrap_or_default(),
+            max: samples.max().unwrap_or_default(),
+        }
+    }
+}
+
+/// 直近のフレームの処理時間の統計
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    pub frames: usize,      // 集計したフレーム数
+    pub update: PhaseStats, // 更新（1フレーム内の全ステップの合計）
+    pub render: PhaseStats, // 描画
+}
+
+impl fmt::Display for FrameStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "frames={}", self.frames)?;
+        for (name, stats) in [("update", &self.update), ("render", &self.render)] {
+            write!(
+                f,
+                "; {}: avg={:?} min={:?} max={:?}",
+                name, stats.average, stats.min, stats.max
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 直近のフレームの処理時間を記録し、移動平均と最小・最大を求める
+#[derive(Debug, Clone)]
+pub struct FrameProfiler {
+    window: usize,
+    samples: VecDeque<(Duration, Duration)>, // (更新, 描画)
+}
+
+/// 統計を求める既定のフレーム数
+const DEFAULT_PROFILER_WINDOW: usize = 60;
+
+impl Default for FrameProfiler {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROFILER_WINDOW)
+    }
+}
+
+impl FrameProfiler {
+    /// 直近window（最小1）フレームを集計するプロファイラを作成
+    pub fn new(window: usize) -> Self {
+        let window = window.max(1);
+        Self {
+            window,
+            samples: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// 1フレーム分の処理時間を記録（古いフレームから捨てる）
+    pub fn record(&mut self, update: Duration, render: Duration) {
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((update, render));
+    }
+
+    /// 記録中のフレームの統計
+    pub fn stats(&self) -> FrameStats {
+        FrameStats {
+            frames: self.samples.len(),
+            update: PhaseStats::from_samples(self.samples.iter().map(|(update, _)| *update)),
+            render: PhaseStats::from_samples(self.samples.iter().map(|(_, render)| *render)),
         }
     }
 }
@@ -26,88 +167,206 @@ impl Default for LoopConfig {
 pub struct GameLoop {
     config: LoopConfig,
     event_receiver: Receiver<PrioritizedEvent>,
+    pending_events: BinaryHeap<Reverse<QueuedEvent>>,
     last_update: Instant,
     accumulated_time: Duration,
     frame_duration: Duration,
+    on_update: Option<UpdateCallback>,
+    on_render: Option<RenderCallback>,
+    render_gate: Option<RenderGate>,
+    on_event: Option<EventCallback>,
+    rendered: bool, // 直前のフレームで描画したか（変更中やアニメーション中は続けて描画する）
+    paused: bool,
+    fps: Arc<AtomicU32>,
+    fps_frames: u32,
+    fps_window_start: Instant,
+    profiler: FrameProfiler,
+    frame_stats: Arc<Mutex<FrameStats>>,
 }
 
+/// FPSを計測する区間の長さ
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+
 impl GameLoop {
     pub fn new(config: LoopConfig, event_receiver: Receiver<PrioritizedEvent>) -> Self {
         let frame_duration = Duration::from_secs_f64(1.0 / config.target_fps as f64);
         GameLoop {
             config,
             event_receiver,
+            pending_events: BinaryHeap::new(),
             last_update: Instant::now(),
             accumulated_time: Duration::ZERO,
             frame_duration,
+            on_update: None,
+            on_render: None,
+            render_gate: None,
+            on_event: None,
+            rendered: false,
+            paused: false,
+            fps: Arc::new(AtomicU32::new(0)),
+            fps_frames: 0,
+            fps_window_start: Instant::now(),
+            profiler: FrameProfiler::default(),
+            frame_stats: Arc::new(Mutex::new(FrameStats::default())),
         }
     }
 
+    /// 一時停止中かどうか
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// 計測したFPSを共有するカウンタを取得
+    pub fn fps_counter(&self) -> Arc<AtomicU32> {
+        self.fps.clone()
+    }
+
+    /// 計測したFPSの書き込み先を差し替える
+    pub fn set_fps_counter(&mut self, counter: Arc<AtomicU32>) {
+        self.fps = counter;
+    }
+
+    /// 直近のフレームの処理時間の統計を共有する値を取得
+    pub fn frame_stats(&self) -> Arc<Mutex<FrameStats>> {
+        self.frame_stats.clone()
+    }
+
+    /// フレームの処理時間の統計の書き込み先を差し替える
+    pub fn set_frame_stats(&mut self, stats: Arc<Mutex<FrameStats>>) {
+        self.frame_stats = stats;
+    }
+
+    /// 直近のフレームの処理時間の統計
+    pub fn last_frame_stats(&self) -> FrameStats {
+        self.profiler.stats()
+    }
+
+    /// 更新コールバックを登録
+    pub fn set_on_update(&mut self, callback: impl FnMut(f32) -> Result<()> + 'static) {
+        self.on_update = Some(Box::new(callback));
+    }
+
+    /// 描画コールバックを登録
+    pub fn set_on_render(&mut self, callback: impl FnMut() -> Result<()> + 'static) {
+        self.on_render = Some(Box::new(callback));
+    }
+
+    /// 描画が必要か判定するコールバックを登録（未登録なら毎フレーム描画する）
+    ///
+    /// MapGUI::take_dirty()やアニメーション中かどうかを返す。trueを返した間は
+    /// 次のフレームを1フレーム分の間隔で処理する。force_continuousが有効な間は呼ばれない。
+    pub fn set_render_gate(&mut self, gate: impl FnMut() -> bool + 'static) {
+        self.render_gate = Some(Box::new(gate));
+    }
+
+    /// イベント処理コールバックを登録（優先度順に取り出したイベントを受け取る）
+    pub fn set_on_event(
+        &mut self,
+        callback: impl FnMut(&PrioritizedEvent) -> Result<()> + 'static,
+    ) {
+        self.on_event = Some(Box::new(callback));
+    }
+
     /// ゲームループの実行
+    ///
+    /// 受信済みのイベントを優先度キューに集め、優先度の高いものから処理する。
+    /// 高優先度のStopイベントを処理した時点で、残りのイベントを処理せずに終了する。
+    /// キューが空なら次のイベントが届くか次のフレームの時刻まで待ってからフレームを
+    /// 処理する。更新コールバックがある間（一時停止中を除く）、直前のフレームで描画した間
+    /// とforce_continuous時は前のフレームから1フレーム分、それ以外（何も変わらない間）は
+    /// idle_heartbeatの間隔でフレームを進めるため、イベントが届かなくても固定時間ステップの
+    /// 更新とアニメーションの描画は実時間どおりに進む。
     pub fn run(&mut self) -> Result<()> {
         info!("Starting game loop");
 
-        while let Ok(event) = self.event_receiver.recv() {
-            match event.event {
-                GameEvent::Stop if event.priority == Priority::High => {
-                    info!("Stopping game loop (high priority)");
-                    break;
-                }
-                _ => {
-                    debug!("Processing event with priority: {:?}", event.priority);
-                    self.process_frame()?
+        loop {
+            // キューが空なら次のイベントが届くか次のフレームの時刻まで待つ
+            if self.pending_events.is_empty() {
+                match self.event_receiver.recv_timeout(self.frame_wait()) {
+                    Ok(event) => self.pending_events.push(Reverse(QueuedEvent(event))),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
                 }
             }
+            self.collect_events();
+
+            if self.dispatch_events()? {
+                info!("Stopping game loop (high priority)");
+                break;
+            }
+            self.process_frame()?;
         }
 
         Ok(())
     }
 
-    /// 1フレームの処理
-    fn process_frame(&mut self) -> Result<()> {
-        let current_time = Instant::now();
-        let frame_time = current_time.duration_since(self.last_update);
-        self.last_update = current_time;
-
-        // 時間の蓄積（最大値を制限して極端な更新を防ぐ）
-        self.accumulated_time += frame_time.min(Duration::from_secs(1) / self.config.max_updates);
+    /// 次のフレームの時刻までの残り時間
+    fn frame_wait(&self) -> Duration {
+        (self.last_update + self.frame_interval()).saturating_duration_since(Instant::now())
+    }
 
-        // 固定時間ステップでの更新
-        while self.accumulated_time >= self.frame_duration {
-            self.update()?;
-            self.accumulated_time -= self.frame_duration;
+    /// 前のフレームから次のフレームまでの間隔
+    ///
+    /// 更新コールバックがある間（一時停止中を除く）、直前のフレームで描画した間と
+    /// force_continuous時は1フレーム分、それ以外はidle_heartbeat。
+    fn frame_interval(&self) -> Duration {
+        let updating = self.on_update.is_some() && !self.paused;
+        if self.config.force_continuous || updating || self.rendered {
+            self.frame_duration
+        } else {
+            self.config.idle_heartbeat
         }
-
-        // レンダリング
-        self.render()?;
-
-        Ok(())
     }
 
-    /// ゲーム状態の更新
-    fn update(&mut self) -> Result<()> {
-        // イベントキューから非ブロッキングで処理
+    /// 受信済みのイベントをすべて優先度キューへ移す
+    fn collect_events(&mut self) {
         while let Ok(event) = self.event_receiver.try_recv() {
+            self.pending_events.push(Reverse(QueuedEvent(event)));
+        }
+    }
+
+    /// 優先度キューのイベントを優先度順に処理する
+    ///
+    /// Low優先度のイベントは1フレームあたりmax_low_events_per_frame件までとし、
+    /// 残りは次のフレームに持ち越す。高優先度のStopを処理した場合はtrueを返す。
+    fn dispatch_events(&mut self) -> Result<bool> {
+        let mut low_events = 0;
+        while let Some(Reverse(QueuedEvent(event))) = self.pending_events.pop() {
+            if event.priority == Priority::Low {
+                if low_events >= self.config.max_low_events_per_frame {
+                    self.pending_events.push(Reverse(QueuedEvent(event)));
+                    break;
+                }
+                low_events += 1;
+            }
+
+            if let Some(on_event) = self.on_event.as_mut() {
+                on_event(&event)?;
+            }
             match event.event {
+                GameEvent::Stop if event.priority == Priority::High => return Ok(true),
+                GameEvent::Pause => {
+                    info!("Pausing game loop");
+                    self.paused = true;
+                }
+                GameEvent::Resume => {
+                    info!("Resuming game loop");
+                    self.paused = false;
+                }
                 GameEvent::Update { delta } => {
-                    // 更新処理
                     debug!(
                         "Update frame with delta: {:.3}ms (priority: {:?})",
                         delta * 1000.0,
                         event.priority
                     );
                 }
-                GameEvent::Stop if event.priority == Priority::High => {
-                    info!("Received high priority stop event - shutting down game loop");
-                    return Ok(());
-                }
                 GameEvent::Log { ref message, level } => {
                     debug!(
                         "Log event [{}] with priority {:?}: {}",
                         level, event.priority, message
                     );
                 }
-                event_type => {
+                ref event_type => {
                     debug!(
                         "Received event: {:?} with priority {:?}",
                         event_type, event.priority
@@ -115,12 +374,95 @@ impl GameLoop {
                 }
             }
         }
+        Ok(false)
+    }
+
+    /// 1フレームの処理
+    fn process_frame(&mut self) -> Result<()> {
+        self.process_frame_at(Instant::now())
+    }
+
+    /// 指定した時刻のフレームとして処理する（前のフレームの時刻からの経過で更新する）
+    fn process_frame_at(&mut self, current_time: Instant) -> Result<()> {
+        let frame_time = current_time.duration_since(self.last_update);
+        self.last_update = current_time;
+
+        self.advance(frame_time)?;
+        let updated_at = Instant::now();
+
+        // レンダリング
+        self.render()?;
+
+        self.profiler.record(
+            updated_at.duration_since(current_time),
+            updated_at.elapsed(),
+        );
+        if let Ok(mut stats) = self.frame_stats.lock() {
+            *stats = self.profiler.stats();
+        }
+        self.measure_fps(current_time);
+        Ok(())
+    }
+
+    /// 計測区間ごとのフレーム数からFPSを更新
+    fn measure_fps(&mut self, now: Instant) {
+        self.fps_frames += 1;
+        let elapsed = now.duration_since(self.fps_window_start);
+        if elapsed >= FPS_WINDOW {
+            let fps = (self.fps_frames as f64 / elapsed.as_secs_f64()).round() as u32;
+            self.fps.store(fps, AtomicOrdering::Relaxed);
+            self.fps_frames = 0;
+            self.fps_window_start = now;
+        }
+    }
+
+    /// 経過時間を蓄積し、固定時間ステップで更新を実行（一時停止中は何もしない）
+    ///
+    /// 1フレームの更新はmax_updates回までとし、それでも追いつけない分は捨てる。
+    fn advance(&mut self, frame_time: Duration) -> Result<()> {
+        if self.paused {
+            return Ok(());
+        }
+
+        self.accumulated_time += frame_time;
+
+        // 固定時間ステップでの更新
+        let mut updates = 0;
+        while self.accumulated_time >= self.frame_duration {
+            if updates == self.config.max_updates {
+                debug!(
+                    "Dropping {:.3}ms of update time",
+                    self.accumulated_time.as_secs_f64() * 1000.0
+                );
+                self.accumulated_time = Duration::ZERO;
+                break;
+            }
+            self.update()?;
+            self.accumulated_time -= self.frame_duration;
+            updates += 1;
+        }
+
         Ok(())
     }
 
-    /// レンダリング
-    fn render(&self) -> Result<()> {
-        // TODO: 実際のレンダリング処理
+    /// ゲーム状態の更新
+    fn update(&mut self) -> Result<()> {
+        if let Some(on_update) = self.on_update.as_mut() {
+            on_update(self.frame_duration.as_secs_f32())?;
+        }
+        Ok(())
+    }
+
+    /// レンダリング（描画が不要なフレームでは何もしない）
+    fn render(&mut self) -> Result<()> {
+        self.rendered =
+            self.config.force_continuous || self.render_gate.as_mut().is_none_or(|gate| gate());
+        if !self.rendered {
+            return Ok(());
+        }
+        if let Some(on_render) = self.on_render.as_mut() {
+            on_render()?;
+        }
         Ok(())
     }
 }
@@ -129,6 +471,8 @@ impl GameLoop {
 mod tests {
     use super::*;
     use crossbeam_channel::bounded;
+    use std::cell::Cell;
+    use std::rc::Rc;
     use std::{thread, time::Duration};
 
     #[test]
@@ -145,10 +489,7 @@ mod tests {
         let _loop = GameLoop::new(config, receiver);
         // GameLoopが正しく作成されることを確認
         sender
-            .send(PrioritizedEvent {
-                priority: Priority::High,
-                event: GameEvent::Stop,
-            })
+            .send(PrioritizedEvent::new(Priority::High, GameEvent::Stop))
             .unwrap();
     }
 
@@ -163,10 +504,7 @@ mod tests {
         thread::spawn(move || {
             thread::sleep(Duration::from_millis(100));
             sender_clone
-                .send(PrioritizedEvent {
-                    priority: Priority::High,
-                    event: GameEvent::Stop,
-                })
+                .send(PrioritizedEvent::new(Priority::High, GameEvent::Stop))
                 .unwrap();
         });
 
@@ -186,21 +524,18 @@ mod tests {
             // 3回のUpdateイベントを送信
             for i in 0..3 {
                 sender_clone
-                    .send(PrioritizedEvent {
-                        priority: Priority::Normal,
-                        event: GameEvent::Update {
+                    .send(PrioritizedEvent::new(
+                        Priority::Normal,
+                        GameEvent::Update {
                             delta: 0.016 * (i + 1) as f32,
                         },
-                    })
+                    ))
                     .unwrap();
                 thread::sleep(Duration::from_millis(50));
             }
             // 最後にStopイベントを送信
             sender_clone
-                .send(PrioritizedEvent {
-                    priority: Priority::High,
-                    event: GameEvent::Stop,
-                })
+                .send(PrioritizedEvent::new(Priority::High, GameEvent::Stop))
                 .unwrap();
         });
 
@@ -216,28 +551,22 @@ mod tests {
 
         // イベントを順番に送信（優先度付き）
         sender
-            .send(PrioritizedEvent {
-                priority: Priority::High,
-                event: GameEvent::Start,
-            })
+            .send(PrioritizedEvent::new(Priority::High, GameEvent::Start))
             .unwrap();
         sender
-            .send(PrioritizedEvent {
-                priority: Priority::Normal,
-                event: GameEvent::Update { delta: 0.016 },
-            })
+            .send(PrioritizedEvent::new(
+                Priority::Normal,
+                GameEvent::Update { delta: 0.016 },
+            ))
             .unwrap();
         sender
-            .send(PrioritizedEvent {
-                priority: Priority::Normal,
-                event: GameEvent::Update { delta: 0.016 },
-            })
+            .send(PrioritizedEvent::new(
+                Priority::Normal,
+                GameEvent::Update { delta: 0.016 },
+            ))
             .unwrap();
         sender
-            .send(PrioritizedEvent {
-                priority: Priority::High,
-                event: GameEvent::Stop,
-            })
+            .send(PrioritizedEvent::new(Priority::High, GameEvent::Stop))
             .unwrap();
 
         // ゲームループを実行（すべてのイベントが処理されるはず）
@@ -252,22 +581,424 @@ mod tests {
 
         // 異なる優先度のイベントを送信
         sender
-            .send(PrioritizedEvent {
-                priority: Priority::Low,
-                event: GameEvent::Log {
+            .send(PrioritizedEvent::new(
+                Priority::Low,
+                GameEvent::Log {
                     message: "Low priority log".to_string(),
                     level: crate::LogLevel::Info,
                 },
-            })
+            ))
             .unwrap();
         sender
-            .send(PrioritizedEvent {
-                priority: Priority::High,
-                event: GameEvent::Stop,
-            })
+            .send(PrioritizedEvent::new(Priority::High, GameEvent::Stop))
             .unwrap();
 
         // ゲームループを実行（高優先度のStopイベントが即座に処理されるはず）
         assert!(game_loop.run().is_ok());
     }
+
+    #[test]
+    fn test_game_loop_update_callback_fixed_timestep() {
+        let config = LoopConfig {
+            target_fps: 10,
+            max_updates: 5,
+            ..LoopConfig::default()
+        };
+        let (_sender, receiver) = bounded(100);
+        let mut game_loop = GameLoop::new(config, receiver);
+
+        let update_count = Rc::new(Cell::new(0));
+        let total_delta = Rc::new(Cell::new(0.0f32));
+        {
+            let update_count = update_count.clone();
+            let total_delta = total_delta.clone();
+            game_loop.set_on_update(move |delta| {
+                update_count.set(update_count.get() + 1);
+                total_delta.set(total_delta.get() + delta);
+                Ok(())
+            });
+        }
+
+        // 100msステップで350ms経過 → 3回更新（50ms持ち越し）
+        game_loop.advance(Duration::from_millis(350)).unwrap();
+        assert_eq!(update_count.get(), 3);
+
+        // 持ち越し分と合わせて110ms → さらに1回更新
+        game_loop.advance(Duration::from_millis(60)).unwrap();
+        assert_eq!(update_count.get(), 4);
+        assert!((total_delta.get() - 0.4).abs() < 1e-5);
+
+        // 長く止まった後は1フレームにmax_updates回まで更新し、残りは捨てる
+        game_loop.advance(Duration::from_secs(3)).unwrap();
+        assert_eq!(update_count.get(), 9);
+        game_loop.advance(Duration::from_millis(100)).unwrap();
+        assert_eq!(update_count.get(), 10);
+    }
+
+    #[test]
+    fn test_updates_advance_every_frame_without_events() {
+        let (_sender, receiver) = bounded(100);
+        let config = LoopConfig::default();
+        let idle_heartbeat = config.idle_heartbeat;
+        let mut game_loop = GameLoop::new(config, receiver);
+
+        let update_count = Rc::new(Cell::new(0));
+        {
+            let update_count = update_count.clone();
+            game_loop.set_on_update(move |_| {
+                update_count.set(update_count.get() + 1);
+                Ok(())
+            });
+        }
+
+        // 更新コールバックがある間はイベントを待たずに1フレームごとに処理する
+        let frame_duration = game_loop.frame_duration;
+        assert_eq!(game_loop.frame_interval(), frame_duration);
+        game_loop.paused = true;
+        assert_eq!(game_loop.frame_interval(), idle_heartbeat);
+        game_loop.paused = false;
+
+        // 0.5秒分のフレームを刻むと60Hzの固定時間ステップで30回更新される
+        let start = game_loop.last_update;
+        for frame in 1..=30 {
+            game_loop
+                .process_frame_at(start + frame_duration * frame)
+                .unwrap();
+        }
+        assert_eq!(update_count.get(), 30);
+    }
+
+    #[test]
+    fn test_game_loop_render_callback() {
+        let config = LoopConfig::default();
+        let (sender, receiver) = bounded(100);
+        let mut game_loop = GameLoop::new(config, receiver);
+
+        let render_count = Rc::new(Cell::new(0));
+        {
+            let render_count = render_count.clone();
+            game_loop.set_on_render(move || {
+                render_count.set(render_count.get() + 1);
+                Ok(())
+            });
+        }
+
+        for _ in 0..3 {
+            sender
+                .send(PrioritizedEvent::new(
+                    Priority::Normal,
+                    GameEvent::Update { delta: 0.016 },
+                ))
+                .unwrap();
+        }
+        // Stopは優先して処理されるため、フレームが処理された後に送信する
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            sender
+                .send(PrioritizedEvent::new(Priority::High, GameEvent::Stop))
+                .unwrap();
+        });
+
+        assert!(game_loop.run().is_ok());
+        // 最初のイベント受信時にフレームが処理される
+        assert!(render_count.get() >= 1);
+    }
+
+    #[test]
+    fn test_render_gate_and_idle_heartbeat() {
+        let config = LoopConfig {
+            idle_heartbeat: Duration::from_millis(5),
+            ..LoopConfig::default()
+        };
+        let (sender, receiver) = bounded(100);
+        let mut game_loop = GameLoop::new(config, receiver);
+
+        let render_count = Rc::new(Cell::new(0));
+        let gate_checks = Rc::new(Cell::new(0));
+        let dirty = Rc::new(Cell::new(true));
+        {
+            let render_count = render_count.clone();
+            game_loop.set_on_render(move || {
+                render_count.set(render_count.get() + 1);
+                Ok(())
+            });
+            let gate_checks = gate_checks.clone();
+            let dirty = dirty.clone();
+            game_loop.set_render_gate(move || {
+                gate_checks.set(gate_checks.get() + 1);
+                dirty.take()
+            });
+        }
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(60));
+            sender
+                .send(PrioritizedEvent::new(Priority::High, GameEvent::Stop))
+                .unwrap();
+        });
+
+        // イベントがなくてもハートビートでフレームが進むが、描画は変更があったときだけ
+        assert!(game_loop.run().is_ok());
+        assert!(gate_checks.get() >= 3, "{}", gate_checks.get());
+        assert_eq!(render_count.get(), 1);
+
+        // force_continuousなら判定によらず描画する
+        game_loop.config.force_continuous = true;
+        game_loop.render().unwrap();
+        assert_eq!(render_count.get(), 2);
+    }
+
+    #[test]
+    fn test_render_gate_shortens_wait_while_animating() {
+        let (_sender, receiver) = bounded(100);
+        let config = LoopConfig::default();
+        let idle_heartbeat = config.idle_heartbeat;
+        let mut game_loop = GameLoop::new(config, receiver);
+
+        let animating = Rc::new(Cell::new(true));
+        let render_count = Rc::new(Cell::new(0));
+        {
+            let render_count = render_count.clone();
+            game_loop.set_on_render(move || {
+                render_count.set(render_count.get() + 1);
+                Ok(())
+            });
+            let animating = animating.clone();
+            game_loop.set_render_gate(move || animating.get());
+        }
+
+        // 更新も描画もない間はハートビートの間隔で待つ
+        let frame_duration = game_loop.frame_duration;
+        assert_eq!(game_loop.frame_interval(), idle_heartbeat);
+
+        // アニメーション中は描画するたびに次のフレームを1フレーム後に処理する
+        let start = game_loop.last_update;
+        for frame in 1..=18 {
+            game_loop
+                .process_frame_at(start + frame_duration * frame)
+                .unwrap();
+            assert_eq!(game_loop.frame_interval(), frame_duration);
+        }
+        assert_eq!(render_count.get(), 18);
+
+        // アニメーションが終わると描画せず、ハートビートの間隔に戻る
+        animating.set(false);
+        game_loop
+            .process_frame_at(start + frame_duration * 19)
+            .unwrap();
+        assert_eq!(render_count.get(), 18);
+        assert_eq!(game_loop.frame_interval(), idle_heartbeat);
+    }
+
+    fn low_log(index: usize) -> PrioritizedEvent {
+        PrioritizedEvent::new(
+            Priority::Low,
+            GameEvent::Log {
+                message: format!("log {}", index),
+                level: crate::LogLevel::Info,
+            },
+        )
+    }
+
+    #[test]
+    fn test_high_priority_stop_preempts_low_events() {
+        let config = LoopConfig::default();
+        let (sender, receiver) = bounded(100);
+        let mut game_loop = GameLoop::new(config, receiver);
+
+        let low_processed = Rc::new(Cell::new(0));
+        {
+            let low_processed = low_processed.clone();
+            game_loop.set_on_event(move |event| {
+                if event.priority == Priority::Low {
+                    low_processed.set(low_processed.get() + 1);
+                }
+                Ok(())
+            });
+        }
+
+        for i in 0..50 {
+            sender.send(low_log(i)).unwrap();
+        }
+        sender
+            .send(PrioritizedEvent::new(Priority::High, GameEvent::Stop))
+            .unwrap();
+
+        assert!(game_loop.run().is_ok());
+        // 後から届いたStopが先に処理され、Lowイベントは処理されない
+        assert_eq!(low_processed.get(), 0);
+    }
+
+    #[test]
+    fn test_low_events_are_capped_per_frame() {
+        let config = LoopConfig {
+            max_low_events_per_frame: 10,
+            ..LoopConfig::default()
+        };
+        let (sender, receiver) = bounded(100);
+        let mut game_loop = GameLoop::new(config, receiver);
+
+        let processed = Rc::new(std::cell::RefCell::new(Vec::new()));
+        {
+            let processed = processed.clone();
+            game_loop.set_on_event(move |event| {
+                processed
+                    .borrow_mut()
+                    .push((event.priority, event.sequence));
+                Ok(())
+            });
+        }
+
+        for i in 0..25 {
+            sender.send(low_log(i)).unwrap();
+        }
+        sender
+            .send(PrioritizedEvent::new(
+                Priority::Normal,
+                GameEvent::Update { delta: 0.016 },
+            ))
+            .unwrap();
+
+        game_loop.collect_events();
+        assert!(!game_loop.dispatch_events().unwrap());
+        {
+            let processed = processed.borrow();
+            // Normalが先に処理され、Lowは上限の10件まで
+            assert_eq!(processed.len(), 11);
+            assert_eq!(processed[0].0, Priority::Normal);
+            // 同じ優先度内では発行順
+            assert!(processed[1..].windows(2).all(|pair| pair[0].1 < pair[1].1));
+        }
+
+        // 残りは次のフレーム以降に処理される
+        assert!(!game_loop.dispatch_events().unwrap());
+        assert!(!game_loop.dispatch_events().unwrap());
+        assert_eq!(processed.borrow().len(), 26);
+        assert!(game_loop.pending_events.is_empty());
+    }
+
+    #[test]
+    fn test_pause_stops_updates() {
+        let config = LoopConfig {
+            target_fps: 10,
+            max_updates: 1,
+            ..LoopConfig::default()
+        };
+        let (sender, receiver) = bounded(100);
+        let mut game_loop = GameLoop::new(config, receiver);
+
+        let update_count = Rc::new(Cell::new(0));
+        {
+            let update_count = update_count.clone();
+            game_loop.set_on_update(move |_| {
+                update_count.set(update_count.get() + 1);
+                Ok(())
+            });
+        }
+        game_loop.advance(Duration::from_millis(100)).unwrap();
+        assert_eq!(update_count.get(), 1);
+
+        // 一時停止中はUpdateイベントが届いても更新されない
+        sender
+            .send(PrioritizedEvent::new(Priority::High, GameEvent::Pause))
+            .unwrap();
+        for _ in 0..3 {
+            sender
+                .send(PrioritizedEvent::new(
+                    Priority::Normal,
+                    GameEvent::Update { delta: 0.1 },
+                ))
+                .unwrap();
+        }
+        game_loop.collect_events();
+        assert!(!game_loop.dispatch_events().unwrap());
+        assert!(game_loop.is_paused());
+        for _ in 0..5 {
+            game_loop.advance(Duration::from_millis(100)).unwrap();
+        }
+        assert_eq!(update_count.get(), 1);
+
+        // 再開すると更新が再び呼ばれる
+        sender
+            .send(PrioritizedEvent::new(Priority::High, GameEvent::Resume))
+            .unwrap();
+        game_loop.collect_events();
+        assert!(!game_loop.dispatch_events().unwrap());
+        assert!(!game_loop.is_paused());
+        game_loop.advance(Duration::from_millis(100)).unwrap();
+        assert_eq!(update_count.get(), 2);
+    }
+
+    #[test]
+    fn test_fps_measurement() {
+        let (_sender, receiver) = bounded(100);
+        let mut game_loop = GameLoop::new(LoopConfig::default(), receiver);
+        let fps = game_loop.fps_counter();
+
+        let start = game_loop.fps_window_start;
+        for i in 1..=30 {
+            game_loop.measure_fps(start + Duration::from_millis(i * 1000 / 30));
+        }
+        assert_eq!(fps.load(AtomicOrdering::Relaxed), 30);
+    }
+
+    #[test]
+    fn test_frame_profiler_rolling_stats() {
+        let ms = Duration::from_millis;
+        let mut profiler = FrameProfiler::new(3);
+        assert_eq!(profiler.stats(), FrameStats::default());
+
+        profiler.record(ms(2), ms(10));
+        profiler.record(ms(4), ms(20));
+        profiler.record(ms(6), ms(30));
+        let stats = profiler.stats();
+        assert_eq!(stats.frames, 3);
+        assert_eq!(
+            stats.update,
+            PhaseStats {
+                average: ms(4),
+                min: ms(2),
+                max: ms(6),
+            }
+        );
+        assert_eq!(stats.render.average, ms(20));
+
+        // 窓を超えた古いフレームは集計から外れる
+        profiler.record(ms(8), ms(1));
+        let stats = profiler.stats();
+        assert_eq!(stats.frames, 3);
+        assert_eq!(stats.update.average, ms(6));
+        assert_eq!(stats.update.min, ms(4));
+        assert_eq!(stats.render.min, ms(1));
+        assert_eq!(stats.render.max, ms(30));
+    }
+
+    #[test]
+    fn test_frame_stats_shared_after_run() {
+        let (sender, receiver) = bounded(100);
+        let mut game_loop = GameLoop::new(LoopConfig::default(), receiver);
+        let shared = game_loop.frame_stats();
+        game_loop.set_on_render(|| {
+            thread::sleep(Duration::from_millis(2));
+            Ok(())
+        });
+
+        sender
+            .send(PrioritizedEvent::new(
+                Priority::Normal,
+                GameEvent::Update { delta: 0.016 },
+            ))
+            .unwrap();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            sender
+                .send(PrioritizedEvent::new(Priority::High, GameEvent::Stop))
+                .unwrap();
+        });
+        game_loop.run().unwrap();
+
+        let stats = *shared.lock().unwrap();
+        assert!(stats.frames >= 1);
+        assert_eq!(stats, game_loop.last_frame_stats());
+        assert!(stats.render.min >= Duration::from_millis(2));
+    }
 }
@@ -1,10 +1,16 @@
-use crossbeam_channel::{bounded, Receiver, Sender};
+use crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError};
+use log::{debug, warn};
 use model::MapPosition;
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::thread::JoinHandle;
+use std::time::Duration;
 
 /// イベントの優先度を表現する列挙型
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Priority {
     High,
     #[default]
@@ -14,8 +20,8 @@ pub enum Priority {
 
 use std::fmt;
 
-/// ログレベルを表現する列挙型
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// ログレベルを表現する列挙型（Info < Warning < Errorの順に重大）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum LogLevel {
     Info,
     Warning,
@@ -33,7 +39,7 @@ impl fmt::Display for LogLevel {
 }
 
 /// ゲーム内で発生する様々なイベントを表現する列挙型
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum GameEvent {
     // システムイベント（High Priority）
     Start,
@@ -42,21 +48,118 @@ pub enum GameEvent {
     Resume,
 
     // ゲーム状態イベント（Normal Priority）
-    Update { delta: f32 },
-    TurnStart { faction_id: u32 },
-    TurnEnd { faction_id: u32 },
-    UnitMove { unit_id: u32, position: MapPosition },
+    Update {
+        delta: f32,
+    },
+    TurnStart {
+        faction_id: u32,
+    },
+    TurnEnd {
+        faction_id: u32,
+    },
+    UnitMove {
+        unit_id: u32,
+        position: MapPosition,
+    },
+    MoveRequested {
+        unit_id: u32,
+        to: MapPosition,
+    },
+    UnitSelected {
+        unit_id: u32,
+    },
+    PositionSelected {
+        position: MapPosition,
+    },
+    UnitMoved {
+        unit_id: u32,
+        from: MapPosition,
+        to: MapPosition,
+    },
+    UnitAttacked {
+        attacker: u32,
+        defender: u32,
+        damage: u32,
+    },
+    TurnEnded {
+        faction_id: u32,
+    },
+    UnitLevelUp {
+        unit_id: u32,
+        new_level: u32,
+    },
+    MapUpdated,
+    CellCaptured {
+        position: MapPosition,
+        faction_id: u32,
+        previous_owner: Option<u32>,
+    },
+    Query {
+        id: u64,
+        payload: Box<GameEvent>,
+    },
+    Reply {
+        id: u64,
+        payload: Box<GameEvent>,
+    },
 
     // 情報イベント（Low Priority）
-    Log { message: String, level: LogLevel },
-    Stats { metric: String, value: f64 },
+    Log {
+        message: String,
+        level: LogLevel,
+    },
+    Stats {
+        metric: String,
+        value: f64,
+    },
+}
+
+/// GameEventのバリアント種別（購読時のフィルタ指定に使用）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    Start,
+    Stop,
+    Pause,
+    Resume,
+    Update,
+    TurnStart,
+    TurnEnd,
+    UnitMove,
+    MoveRequested,
+    UnitSelected,
+    PositionSelected,
+    UnitMoved,
+    UnitAttacked,
+    TurnEnded,
+    UnitLevelUp,
+    MapUpdated,
+    CellCaptured,
+    Query,
+    Reply,
+    Log,
+    Stats,
 }
 
 /// イベントとその優先度をカプセル化する構造体
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PrioritizedEvent {
     pub priority: Priority,
     pub event: GameEvent,
+    pub sequence: u64, // 発行順の通し番号（同じ優先度内の順序付けに使う）
+}
+
+/// 次に割り当てる通し番号
+static NEXT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+impl PrioritizedEvent {
+    /// 通し番号を割り当てて優先度付きイベントを作成
+    pub fn new(priority: Priority, event: GameEvent) -> Self {
+        Self {
+            priority,
+            event,
+            sequence: NEXT_SEQUENCE.fetch_add(1, Ordering::Relaxed),
+        }
+    }
 }
 
 impl GameEvent {
@@ -70,17 +173,334 @@ impl GameEvent {
             GameEvent::Update { .. }
             | GameEvent::TurnStart { .. }
             | GameEvent::TurnEnd { .. }
-            | GameEvent::UnitMove { .. } => Priority::Normal,
+            | GameEvent::UnitMove { .. }
+            | GameEvent::MoveRequested { .. }
+            | GameEvent::UnitSelected { .. }
+            | GameEvent::PositionSelected { .. }
+            | GameEvent::UnitMoved { .. }
+            | GameEvent::UnitAttacked { .. }
+            | GameEvent::TurnEnded { .. }
+            | GameEvent::UnitLevelUp { .. }
+            | GameEvent::MapUpdated
+            | GameEvent::CellCaptured { .. }
+            | GameEvent::Query { .. }
+            | GameEvent::Reply { .. } => Priority::Normal,
 
             GameEvent::Log { .. } | GameEvent::Stats { .. } => Priority::Low,
         }
     }
+
+    /// イベントのバリアント種別を返す
+    pub fn kind(&self) -> EventKind {
+        match self {
+            GameEvent::Start => EventKind::Start,
+            GameEvent::Stop => EventKind::Stop,
+            GameEvent::Pause => EventKind::Pause,
+            GameEvent::Resume => EventKind::Resume,
+            GameEvent::Update { .. } => EventKind::Update,
+            GameEvent::TurnStart { .. } => EventKind::TurnStart,
+            GameEvent::TurnEnd { .. } => EventKind::TurnEnd,
+            GameEvent::UnitMove { .. } => EventKind::UnitMove,
+            GameEvent::MoveRequested { .. } => EventKind::MoveRequested,
+            GameEvent::UnitSelected { .. } => EventKind::UnitSelected,
+            GameEvent::PositionSelected { .. } => EventKind::PositionSelected,
+            GameEvent::UnitMoved { .. } => EventKind::UnitMoved,
+            GameEvent::UnitAttacked { .. } => EventKind::UnitAttacked,
+            GameEvent::TurnEnded { .. } => EventKind::TurnEnded,
+            GameEvent::UnitLevelUp { .. } => EventKind::UnitLevelUp,
+            GameEvent::MapUpdated => EventKind::MapUpdated,
+            GameEvent::CellCaptured { .. } => EventKind::CellCaptured,
+            GameEvent::Query { .. } => EventKind::Query,
+            GameEvent::Reply { .. } => EventKind::Reply,
+            GameEvent::Log { .. } => EventKind::Log,
+            GameEvent::Stats { .. } => EventKind::Stats,
+        }
+    }
+}
+
+/// request()の失敗理由
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestError {
+    /// 指定時間内に応答がなかった
+    Timeout { topic: String, id: u64 },
+    /// 問い合わせを発行できなかった
+    Publish { topic: String, message: String },
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::Timeout { topic, id } => {
+                write!(
+                    f,
+                    "問い合わせがタイムアウトしました: トピック {} (ID {})",
+                    topic, id
+                )
+            }
+            RequestError::Publish { topic, message } => {
+                write!(
+                    f,
+                    "問い合わせを発行できません: トピック {} ({})",
+                    topic, message
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+/// 応答待ちの問い合わせ（問い合わせID → 応答の送信先）
+type PendingReplies = Arc<Mutex<HashMap<u64, Sender<GameEvent>>>>;
+
+/// 次に割り当てる問い合わせID
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 応答待ちの問い合わせへ応答を届ける（該当する問い合わせがなければfalse）
+fn deliver_reply(pending: &PendingReplies, id: u64, payload: GameEvent) -> bool {
+    let Some(sender) = pending.lock().unwrap().remove(&id) else {
+        return false;
+    };
+    sender
+        .send(GameEvent::Reply {
+            id,
+            payload: Box::new(payload),
+        })
+        .is_ok()
+}
+
+/// 購読時のイベントフィルタ
+type EventFilter = Box<dyn Fn(&GameEvent) -> bool + Send>;
+
+/// 購読の識別子（購読の解除に使う）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// 次に割り当てる購読ID
+static NEXT_SUBSCRIPTION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 購読者のキューの既定の容量
+pub const DEFAULT_QUEUE_CAPACITY: usize = 100;
+
+/// 購読者のキューが満杯のときの扱い
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BackpressurePolicy {
+    /// キューに空きができるまで発行側を待たせる
+    Block,
+    /// 新しいイベントを破棄し、発行はエラーを返す（暗黙に作られるトピックの既定）
+    #[default]
+    DropNewest,
+    /// 最も古いイベントを破棄して新しいイベントを入れる
+    DropOldest,
+    /// キュー内の同じバリアントのイベントを取り除いてから入れる（満杯ならDropOldest）
+    Coalesce,
+}
+
+/// トピックごとのキューの設定
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TopicConfig {
+    pub capacity: usize, // 購読者ごとのキューの容量
+    pub policy: BackpressurePolicy,
+}
+
+impl Default for TopicConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_QUEUE_CAPACITY,
+            policy: BackpressurePolicy::default(),
+        }
+    }
+}
+
+impl TopicConfig {
+    /// バス側でもキューから取り出す必要があるか
+    fn needs_queue_access(&self) -> bool {
+        matches!(
+            self.policy,
+            BackpressurePolicy::DropOldest | BackpressurePolicy::Coalesce
+        )
+    }
+}
+
+/// 購読の受信側
+///
+/// crossbeam_channel::Receiverとして使える。破棄すると、バスが受信側を持つ
+/// DropOldest・Coalesceのトピックでも次の発行時に購読が解除される。
+pub struct Subscription {
+    receiver: Receiver<PrioritizedEvent>,
+    _alive: Arc<()>, // 破棄されたことをバスに知らせる
+}
+
+impl Deref for Subscription {
+    type Target = Receiver<PrioritizedEvent>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+/// 購読者（送信先チャネルと任意のフィルタ）
+struct Subscriber {
+    id: SubscriptionId,
+    sender: Sender<PrioritizedEvent>,
+    queue: Option<Receiver<PrioritizedEvent>>, // 古いイベントを取り除くための受信側（DropOldest・Coalesceのみ）
+    pending: VecDeque<EventKind>, // キューに残っているイベントの種別（送信順、Coalesceのみ）
+    alive: Weak<()>,
+    filter: Option<EventFilter>,
+}
+
+impl Subscriber {
+    /// イベントがこの購読者に配信対象かどうか
+    fn accepts(&self, event: &GameEvent) -> bool {
+        self.filter.as_ref().is_none_or(|filter| filter(event))
+    }
+
+    /// 受信側（Subscription）が破棄されていないか
+    fn is_alive(&self) -> bool {
+        self.alive.strong_count() > 0
+    }
+
+    /// 同じバリアントのイベントをキューから取り除いてから入れる
+    ///
+    /// 送信するのはバスだけなので、キューに残っているのは直近に送った件数分の
+    /// イベントになる。その種別を覚えておき、同じバリアントが残っているときだけ
+    /// キューを入れ直す。(置き換えた件数, 満杯で破棄した件数)を返す。
+    /// 受信側が破棄されていればNone。
+    fn send_coalescing(&mut self, event: PrioritizedEvent) -> Option<(u64, u64)> {
+        let kind = event.event.kind();
+        let queued = self.sender.len();
+        while self.pending.len() > queued {
+            self.pending.pop_front();
+        }
+        let coalesced = if self.pending.contains(&kind) {
+            self.remove_queued(kind)
+        } else {
+            0
+        };
+        let dropped = self.send_dropping_oldest(event)?;
+        self.pending.push_back(kind);
+        Some((coalesced, dropped))
+    }
+
+    /// キューから指定したバリアントのイベントを取り除き、取り除いた件数を返す
+    fn remove_queued(&mut self, kind: EventKind) -> u64 {
+        let Some(queue) = &self.queue else {
+            return 0;
+        };
+        let queued: Vec<_> = queue.try_iter().collect();
+        self.pending.clear();
+        let mut removed = 0;
+        for event in queued {
+            if event.event.kind() == kind {
+                removed += 1;
+            } else {
+                // 取り出した分の空きがあるので必ず入る
+                self.pending.push_back(event.event.kind());
+                self.sender.try_send(event).ok();
+            }
+        }
+        removed
+    }
+
+    /// キューに入れる（満杯なら最も古いイベントを破棄する）
+    ///
+    /// 破棄した件数を返す。受信側が破棄されていればNone。
+    fn send_dropping_oldest(&mut self, mut event: PrioritizedEvent) -> Option<u64> {
+        let mut dropped = 0;
+        loop {
+            match self.sender.try_send(event) {
+                Ok(()) => return Some(dropped),
+                Err(TrySendError::Full(rejected)) => {
+                    event = rejected;
+                    // 受信側が先に取り出して空いた場合は取り除かずにもう一度送る
+                    if let Some(queue) = &self.queue {
+                        if queue.try_recv().is_ok() {
+                            self.pending.pop_front();
+                            dropped += 1;
+                        }
+                    }
+                }
+                Err(TrySendError::Disconnected(_)) => return None,
+            }
+        }
+    }
+}
+
+/// トピックごとの購読者と配信統計
+#[derive(Default)]
+struct Topic {
+    config: TopicConfig,
+    subscribers: Vec<Subscriber>,
+    published: u64, // 発行されたイベント数
+    delivered: u64, // 購読者のキューに入ったイベント数
+    dropped: u64,   // キューが満杯で破棄されたイベント数
+    coalesced: u64, // Coalesceで新しいイベントに置き換えられたイベント数
+}
+
+/// トピックごとの統計のスナップショット
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TopicStats {
+    pub published: u64, // 発行されたイベント数
+    pub delivered: u64, // 購読者のキューに入ったイベント数（購読者ごとに数える）
+    pub queued: usize,  // 現在キューに残っているイベント数（概算）
+    pub dropped: u64,   // キューが満杯で破棄されたイベント数
+    pub coalesced: u64, // Coalesceで新しいイベントに置き換えられたイベント数
+}
+
+/// イベントバス全体の統計のスナップショット
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BusStats {
+    pub topics: HashMap<String, TopicStats>,
+}
+
+impl BusStats {
+    /// 指定トピックの統計（未使用のトピックはすべて0）
+    pub fn topic(&self, topic: &str) -> TopicStats {
+        self.topics.get(topic).copied().unwrap_or_default()
+    }
+
+    /// 全トピックで破棄されたイベントの合計
+    pub fn total_dropped(&self) -> u64 {
+        self.topics.values().map(|stats| stats.dropped).sum()
+    }
+
+    /// 全トピックでキューに残っているイベントの合計
+    pub fn total_queued(&self) -> usize {
+        self.topics.values().map(|stats| stats.queued).sum()
+    }
+}
+
+impl fmt::Display for BusStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<_> = self.topics.keys().collect();
+        names.sort();
+        for (i, name) in names.into_iter().enumerate() {
+            let stats = &self.topics[name];
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(
+                f,
+                "{}: published={} delivered={} queued={} dropped={}",
+                name, stats.published, stats.delivered, stats.queued, stats.dropped
+            )?;
+            if stats.coalesced > 0 {
+                write!(f, " coalesced={}", stats.coalesced)?;
+            }
+        }
+        Ok(())
+    }
 }
 
+/// 発行されたすべてのイベントを観測するフック（トピック名とイベントを受け取る）
+pub type PublishObserver = Arc<dyn Fn(&str, &PrioritizedEvent) + Send + Sync>;
+
 /// イベントバスの実装
 #[derive(Clone)]
 pub struct EventBus {
-    senders: Arc<Mutex<HashMap<String, Vec<Sender<PrioritizedEvent>>>>>,
+    topics: Arc<Mutex<HashMap<String, Topic>>>,
+    pending_replies: PendingReplies,
+    observers: Arc<Mutex<Vec<PublishObserver>>>,
 }
 
 impl EventBus {
@@ -89,42 +509,353 @@ impl EventBus {
     }
 
     /// 特定のイベントタイプの購読を登録
-    pub fn subscribe(&self, event_type: &str) -> anyhow::Result<Receiver<PrioritizedEvent>> {
-        let (sender, receiver) = bounded(100);
-        let mut senders = self.senders.lock().unwrap();
-        senders
-            .entry(event_type.to_string())
-            .or_default()
-            .push(sender);
-        Ok(receiver)
-    }
-
-    /// イベントを発行（デフォルトの優先度を使用）
-    pub fn publish(&self, event_type: &str, event: GameEvent) -> anyhow::Result<()> {
+    pub fn subscribe(&self, event_type: &str) -> anyhow::Result<Subscription> {
+        self.subscribe_with_id(event_type)
+            .map(|(_, receiver)| receiver)
+    }
+
+    /// 購読を登録し、解除用の購読IDと受信側を返す
+    pub fn subscribe_with_id(
+        &self,
+        event_type: &str,
+    ) -> anyhow::Result<(SubscriptionId, Subscription)> {
+        self.register(event_type, None)
+    }
+
+    /// 購読を解除する（該当する購読がなければfalse）
+    pub fn unsubscribe(&self, event_type: &str, id: SubscriptionId) -> bool {
+        let mut topics = self.topics.lock().unwrap();
+        let Some(topic) = topics.get_mut(event_type) else {
+            return false;
+        };
+        let before = topic.subscribers.len();
+        topic.subscribers.retain(|subscriber| subscriber.id != id);
+        topic.subscribers.len() != before
+    }
+
+    /// フィルタ条件に一致するイベントのみを受け取る購読を登録
+    ///
+    /// フィルタはチャネルへの送信前に評価されるため、対象外のイベントで
+    /// 購読者のキューが埋まることはない。
+    pub fn subscribe_filtered(
+        &self,
+        event_type: &str,
+        filter: impl Fn(&GameEvent) -> bool + Send + 'static,
+    ) -> anyhow::Result<Subscription> {
+        self.register(event_type, Some(Box::new(filter)))
+            .map(|(_, receiver)| receiver)
+    }
+
+    /// 指定したバリアントのイベントのみを受け取る購読を登録
+    pub fn subscribe_to(&self, event_type: &str, kind: EventKind) -> anyhow::Result<Subscription> {
+        self.subscribe_filtered(event_type, move |event| event.kind() == kind)
+    }
+
+    /// 購読者を登録して購読IDと受信側を返す
+    fn register(
+        &self,
+        event_type: &str,
+        filter: Option<EventFilter>,
+    ) -> anyhow::Result<(SubscriptionId, Subscription)> {
+        let id = SubscriptionId(NEXT_SUBSCRIPTION_ID.fetch_add(1, Ordering::Relaxed));
+        let mut topics = self.topics.lock().unwrap();
+        let topic = topics.entry(event_type.to_string()).or_default();
+        let (sender, receiver) = bounded(topic.config.capacity);
+        let queue = topic.config.needs_queue_access().then(|| receiver.clone());
+        let alive = Arc::new(());
+        topic.subscribers.push(Subscriber {
+            id,
+            sender,
+            queue,
+            pending: VecDeque::new(),
+            alive: Arc::downgrade(&alive),
+            filter,
+        });
+        let subscription = Subscription {
+            receiver,
+            _alive: alive,
+        };
+        Ok((id, subscription))
+    }
+
+    /// トピックのキューの容量と満杯時の扱いを設定する
+    ///
+    /// 容量は購読者のキューを作るときに決まるため、購読者がいるトピックの設定は
+    /// 変更できない（同じ設定ならOk）。設定しないトピックはTopicConfig::default()になる。
+    /// LOG_TOPICとMAP_UPDATED_TOPICはバスの作成時に設定済み。
+    pub fn create_topic(&self, name: &str, config: TopicConfig) -> anyhow::Result<()> {
+        let mut topics = self.topics.lock().unwrap();
+        let topic = topics.entry(name.to_string()).or_default();
+        if config.capacity == 0 && config.needs_queue_access() {
+            return Err(anyhow::anyhow!(
+                "{:?}のトピックには1以上の容量が必要です: トピック {}",
+                config.policy,
+                name
+            ));
+        }
+        if topic.config != config && !topic.subscribers.is_empty() {
+            return Err(anyhow::anyhow!(
+                "購読者のいるトピックの設定は変更できません: トピック {}",
+                name
+            ));
+        }
+        topic.config = config;
+        Ok(())
+    }
+
+    /// 全トピックに発行されるイベントを観測するフックを登録
+    ///
+    /// フックは購読者への配信前に発行元のスレッドで呼ばれる。記録やデバッグ用。
+    /// 呼び出し中はバスのロックを保持しないので、フックの中からログを出したり
+    /// イベントを発行したりしてもよい。
+    pub fn observe(&self, observer: impl Fn(&str, &PrioritizedEvent) + Send + Sync + 'static) {
+        self.observers.lock().unwrap().push(Arc::new(observer));
+    }
+
+    /// イベントを発行（デフォルトの優先度を使用）し、配信できた購読者数を返す
+    pub fn publish(&self, event_type: &str, event: GameEvent) -> anyhow::Result<usize> {
         self.publish_with_priority(event_type, event, None)
     }
 
     /// イベントを指定した優先度で発行
+    ///
+    /// 配信できた購読者数を返す。キューが満杯のときの扱いはトピックの設定に従う。
+    /// 既定（DropNewest）では送信はブロックせず、キューが満杯の購読者には配信せず
+    /// 破棄数として記録し、他の購読者への配信を終えた後でエラーを返す。
+    /// DropOldest・Coalesceで古いイベントを破棄した場合はエラーにしない。
+    /// 受信側が破棄された購読者は購読を解除する。
     pub fn publish_with_priority(
         &self,
         event_type: &str,
         event: GameEvent,
         priority: Option<Priority>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<usize> {
         let priority = priority.unwrap_or_else(|| event.default_priority());
-        let prioritized_event = PrioritizedEvent { priority, event };
+        let prioritized_event = PrioritizedEvent::new(priority, event);
+        // フックがこのバスへ発行しても自己デッドロックしないよう、複製してからロックを外して呼ぶ
+        let observers = self.observers.lock().unwrap().clone();
+        for observer in &observers {
+            observer(event_type, &prioritized_event);
+        }
+
+        let mut topics = self.topics.lock().unwrap();
+        let topic = topics.entry(event_type.to_string()).or_default();
+        topic.published += 1;
+        let policy = topic.config.policy;
+        if policy == BackpressurePolicy::Block {
+            return self.publish_blocking(topics, event_type, prioritized_event);
+        }
 
-        let senders = self.senders.lock().unwrap();
-        if let Some(event_senders) = senders.get(event_type) {
-            for sender in event_senders {
-                sender.send(prioritized_event.clone())?;
+        let mut received = 0;
+        let mut dropped = 0; // DropOldest・Coalesceで破棄した古いイベント
+        let mut rejected = 0; // DropNewestで破棄した新しいイベント
+        let mut coalesced = 0;
+        topic.subscribers.retain_mut(|subscriber| {
+            if !subscriber.is_alive() {
+                debug!(
+                    "破棄された購読者を解除します: トピック {} ({:?})",
+                    event_type, subscriber.id
+                );
+                return false;
             }
+            if !subscriber.accepts(&prioritized_event.event) {
+                return true;
+            }
+            let sent = match policy {
+                BackpressurePolicy::Block | BackpressurePolicy::DropNewest => {
+                    match subscriber.sender.try_send(prioritized_event.clone()) {
+                        Ok(()) => Some(0),
+                        Err(TrySendError::Full(_)) => {
+                            rejected += 1;
+                            return true;
+                        }
+                        Err(TrySendError::Disconnected(_)) => None,
+                    }
+                }
+                BackpressurePolicy::DropOldest => {
+                    subscriber.send_dropping_oldest(prioritized_event.clone())
+                }
+                BackpressurePolicy::Coalesce => subscriber
+                    .send_coalescing(prioritized_event.clone())
+                    .map(|(replaced, oldest)| {
+                        coalesced += replaced;
+                        oldest
+                    }),
+            };
+            match sent {
+                Some(oldest) => {
+                    dropped += oldest;
+                    received += 1;
+                    true
+                }
+                None => {
+                    debug!(
+                        "切断された購読者を解除します: トピック {} ({:?})",
+                        event_type, subscriber.id
+                    );
+                    false
+                }
+            }
+        });
+        topic.delivered += received as u64;
+        topic.dropped += dropped + rejected;
+        topic.coalesced += coalesced;
+        if rejected > 0 {
+            return Err(anyhow::anyhow!(
+                "キューが満杯のため{}件の配信を破棄しました: トピック {}",
+                rejected,
+                event_type
+            ));
         }
-        Ok(())
+        Ok(received)
+    }
+
+    /// Blockのトピックへ発行する（ロックを外してからキューの空きを待つ）
+    fn publish_blocking(
+        &self,
+        topics: MutexGuard<'_, HashMap<String, Topic>>,
+        event_type: &str,
+        prioritized_event: PrioritizedEvent,
+    ) -> anyhow::Result<usize> {
+        let senders: Vec<(SubscriptionId, Sender<PrioritizedEvent>)> = topics
+            .get(event_type)
+            .map(|topic| {
+                topic
+                    .subscribers
+                    .iter()
+                    .filter(|subscriber| subscriber.accepts(&prioritized_event.event))
+                    .map(|subscriber| (subscriber.id, subscriber.sender.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        drop(topics);
+
+        let mut received = 0;
+        let mut disconnected = Vec::new();
+        for (id, sender) in senders {
+            match sender.send(prioritized_event.clone()) {
+                Ok(()) => received += 1,
+                Err(_) => disconnected.push(id),
+            }
+        }
+
+        let mut topics = self.topics.lock().unwrap();
+        if let Some(topic) = topics.get_mut(event_type) {
+            topic.delivered += received as u64;
+            topic
+                .subscribers
+                .retain(|subscriber| !disconnected.contains(&subscriber.id));
+        }
+        Ok(received)
+    }
+
+    /// 通知イベントを発行し、配信できなかった場合は警告をログに出して続行する
+    ///
+    /// 状態を変更した後に送る通知向け。満杯の購読者がいても呼び出し側の処理は
+    /// 中断させない。破棄された件数はstats()で確認できる。
+    pub fn notify(&self, event_type: &str, event: GameEvent) {
+        if let Err(e) = self.publish(event_type, event) {
+            warn!("通知を配信できませんでした: {}", e);
+        }
+    }
+
+    /// イベントを発行し、配信できなかった場合は黙って破棄する
+    ///
+    /// ログや統計など、取りこぼしても問題のない低優先度のイベント向け。
+    /// 破棄された件数はstats()で確認できる。
+    pub fn publish_or_drop(&self, event_type: &str, event: GameEvent) {
+        self.publish(event_type, event).ok();
+    }
+
+    /// トピックごとの配信統計のスナップショットを取得
+    pub fn stats(&self) -> BusStats {
+        let topics = self.topics.lock().unwrap();
+        let topics = topics
+            .iter()
+            .map(|(name, topic)| {
+                let stats = TopicStats {
+                    published: topic.published,
+                    delivered: topic.delivered,
+                    queued: topic.subscribers.iter().map(|s| s.sender.len()).sum(),
+                    dropped: topic.dropped,
+                    coalesced: topic.coalesced,
+                };
+                (name.clone(), stats)
+            })
+            .collect();
+        BusStats { topics }
+    }
+
+    /// トピックに問い合わせを発行し、応答を待つ
+    ///
+    /// 問い合わせはQueryイベントとして発行され、respond()で登録したハンドラ
+    /// （またはreply()を呼ぶ購読者）の応答ペイロードを返す。timeout内に応答が
+    /// なければRequestError::Timeoutを返す。
+    pub fn request(
+        &self,
+        event_type: &str,
+        payload: GameEvent,
+        timeout: Duration,
+    ) -> anyhow::Result<GameEvent> {
+        let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = bounded(1);
+        self.pending_replies.lock().unwrap().insert(id, sender);
+
+        let query = GameEvent::Query {
+            id,
+            payload: Box::new(payload),
+        };
+        if let Err(e) = self.publish(event_type, query) {
+            self.pending_replies.lock().unwrap().remove(&id);
+            return Err(RequestError::Publish {
+                topic: event_type.to_string(),
+                message: e.to_string(),
+            }
+            .into());
+        }
+
+        let result = receiver.recv_timeout(timeout);
+        self.pending_replies.lock().unwrap().remove(&id);
+        match result {
+            Ok(GameEvent::Reply { payload, .. }) => Ok(*payload),
+            Ok(other) => Ok(other),
+            Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => {
+                Err(RequestError::Timeout {
+                    topic: event_type.to_string(),
+                    id,
+                }
+                .into())
+            }
+        }
+    }
+
+    /// 問い合わせIDに応答する（応答待ちの問い合わせがなければfalse）
+    pub fn reply(&self, id: u64, payload: GameEvent) -> bool {
+        deliver_reply(&self.pending_replies, id, payload)
+    }
+
+    /// トピックへの問い合わせに応答するハンドラを登録
+    ///
+    /// ハンドラは専用のワーカースレッドで実行され、問い合わせのペイロードを受け取って
+    /// 応答のペイロードを返す。ワーカーはイベントバスがすべて破棄されると終了する。
+    pub fn respond(
+        &self,
+        event_type: &str,
+        handler: impl Fn(&GameEvent) -> GameEvent + Send + 'static,
+    ) -> anyhow::Result<JoinHandle<()>> {
+        let receiver = self.subscribe_to(event_type, EventKind::Query)?;
+        let pending = self.pending_replies.clone();
+        Ok(std::thread::spawn(move || {
+            while let Ok(prioritized_event) = receiver.recv() {
+                if let GameEvent::Query { id, payload } = prioritized_event.event {
+                    deliver_reply(&pending, id, handler(&payload));
+                }
+            }
+        }))
     }
 
     /// エラーイベントを発行（常にHigh優先度）
-    pub fn publish_error(&self, message: String) -> anyhow::Result<()> {
+    pub fn publish_error(&self, message: String) -> anyhow::Result<usize> {
         self.publish_with_priority(
             "error",
             GameEvent::Log {
@@ -136,10 +867,129 @@ impl EventBus {
     }
 }
 
+/// logクレートの出力を転送するトピック（満杯なら古いログから捨てる）
+pub const LOG_TOPIC: &str = "log";
+
+/// MapGUIのマップ更新通知のトピック（未受信の通知は1件にまとめる）
+pub const MAP_UPDATED_TOPIC: &str = "map_updated";
+
+/// バスの作成時に設定しておくトピック
+fn builtin_topics() -> HashMap<String, Topic> {
+    let configs = [
+        (
+            LOG_TOPIC,
+            TopicConfig {
+                capacity: 1000,
+                policy: BackpressurePolicy::DropOldest,
+            },
+        ),
+        (
+            MAP_UPDATED_TOPIC,
+            TopicConfig {
+                capacity: 16,
+                policy: BackpressurePolicy::Coalesce,
+            },
+        ),
+    ];
+    configs
+        .into_iter()
+        .map(|(name, config)| {
+            let topic = Topic {
+                config,
+                ..Topic::default()
+            };
+            (name.to_string(), topic)
+        })
+        .collect()
+}
+
+thread_local! {
+    /// このスレッドでログをEventBusへ転送している最中か
+    static FORWARDING_LOG: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// FORWARDING_LOGを立てている間を表し、破棄時（パニックによる巻き戻しを含む）に下ろす
+struct ForwardingGuard;
+
+impl ForwardingGuard {
+    /// このスレッドで転送中でなければ印を立てる（転送中ならNone）
+    fn enter() -> Option<Self> {
+        (!FORWARDING_LOG.with(|forwarding| forwarding.replace(true))).then_some(Self)
+    }
+}
+
+impl Drop for ForwardingGuard {
+    fn drop(&mut self) {
+        FORWARDING_LOG.with(|forwarding| forwarding.set(false));
+    }
+}
+
+/// logクレートの出力をLogイベントとしてEventBusへ転送するロガー
+struct EventBusLogger {
+    event_bus: EventBus,
+    min_level: LogLevel,
+}
+
+impl EventBusLogger {
+    /// logクレートのレベルを対応するLogLevelに変換する（debug以下は転送しない）
+    fn level_of(level: log::Level) -> Option<LogLevel> {
+        match level {
+            log::Level::Error => Some(LogLevel::Error),
+            log::Level::Warn => Some(LogLevel::Warning),
+            log::Level::Info => Some(LogLevel::Info),
+            log::Level::Debug | log::Level::Trace => None,
+        }
+    }
+}
+
+impl log::Log for EventBusLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        Self::level_of(metadata.level()).is_some_and(|level| level >= self.min_level)
+    }
+
+    fn log(&self, record: &log::Record) {
+        let Some(level) = Self::level_of(record.level()) else {
+            return;
+        };
+        if level < self.min_level {
+            return;
+        }
+        // 転送中に観測フックなどが出したログは、再び転送すると際限なく繰り返しうるので捨てる
+        let Some(_guard) = ForwardingGuard::enter() else {
+            return;
+        };
+        self.event_bus.publish_or_drop(
+            LOG_TOPIC,
+            GameEvent::Log {
+                message: record.args().to_string(),
+                level,
+            },
+        );
+    }
+
+    fn flush(&self) {}
+}
+
+/// logクレートの出力のうちmin_level以上のものを、LOG_TOPICへLogイベントとして発行する
+///
+/// プロセス全体のロガーとして登録するため、他のロガーが登録済みの場合はエラーを返す。
+pub fn forward_logs(event_bus: &EventBus, min_level: LogLevel) -> anyhow::Result<()> {
+    let logger = EventBusLogger {
+        event_bus: event_bus.clone(),
+        min_level,
+    };
+    log::set_logger(Box::leak(Box::new(logger)))
+        .map_err(|e| anyhow::anyhow!("ロガーを登録できません: {}", e))?;
+    log::set_max_level(log::LevelFilter::Info);
+    Ok(())
+}
+
 impl Default for EventBus {
     fn default() -> Self {
         EventBus {
-            senders: Arc::new(Mutex::new(HashMap::new())),
+            topics: Arc::new(Mutex::new(builtin_topics())),
+            pending_replies: Arc::new(Mutex::new(HashMap::new())),
+            observers: Arc::new(Mutex::new(Vec::new())),
         }
     }
 }
@@ -215,4 +1065,415 @@ mod tests {
             panic!("No event received");
         }
     }
+
+    #[test]
+    fn test_subscribe_to_variant() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        let stop_receiver = event_bus.subscribe_to("test", EventKind::Stop)?;
+        let all_receiver = event_bus.subscribe("test")?;
+
+        event_bus.publish("test", GameEvent::Update { delta: 0.016 })?;
+        event_bus.publish(
+            "test",
+            GameEvent::Log {
+                message: "log".to_string(),
+                level: LogLevel::Info,
+            },
+        )?;
+        event_bus.publish("test", GameEvent::Stop)?;
+
+        // Stopイベントのみ受信する
+        let received: Vec<_> = stop_receiver.try_iter().collect();
+        assert_eq!(received.len(), 1);
+        assert!(matches!(received[0].event, GameEvent::Stop));
+
+        // フィルタなしの購読者はすべて受信する
+        assert_eq!(all_receiver.try_iter().count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_does_not_fill_queue() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        let receiver = event_bus.subscribe_filtered(
+            "test",
+            |event| matches!(event, GameEvent::Update { delta } if *delta > 1.0),
+        )?;
+
+        // チャネル容量を超える数の対象外イベントを発行してもエラーにならない
+        for _ in 0..200 {
+            event_bus.publish("test", GameEvent::Update { delta: 0.016 })?;
+        }
+        event_bus.publish("test", GameEvent::Update { delta: 2.0 })?;
+
+        let received: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(received.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats_count_dropped_events() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        let full_receiver = event_bus.subscribe("test")?;
+        let _log_receiver = event_bus.subscribe_to("test", EventKind::Log)?;
+
+        // 購読者のキューを満杯にする
+        for _ in 0..100 {
+            event_bus.publish("test", GameEvent::Update { delta: 0.016 })?;
+        }
+        let stats = event_bus.stats().topic("test");
+        assert_eq!(stats.published, 100);
+        assert_eq!(stats.delivered, 100);
+        assert_eq!(stats.queued, 100);
+        assert_eq!(stats.dropped, 0);
+
+        // 満杯の購読者への送信はブロックせず、破棄数が増える
+        assert!(event_bus
+            .publish("test", GameEvent::Update { delta: 0.016 })
+            .is_err());
+        event_bus.publish_or_drop(
+            "test",
+            GameEvent::Log {
+                message: "log".to_string(),
+                level: LogLevel::Info,
+            },
+        );
+        let stats = event_bus.stats();
+        assert_eq!(stats.topic("test").published, 102);
+        assert_eq!(stats.topic("test").dropped, 2);
+        // Logはフィルタ付きの購読者には届いている
+        assert_eq!(stats.topic("test").delivered, 101);
+        assert_eq!(stats.total_dropped(), 2);
+
+        // 受信するとキューの長さが減る
+        full_receiver.try_iter().take(40).count();
+        assert_eq!(event_bus.stats().topic("test").queued, 61);
+        assert_eq!(event_bus.stats().topic("unknown"), TopicStats::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_drop_oldest_keeps_newest_events() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        event_bus.create_topic(
+            "test",
+            TopicConfig {
+                capacity: 5,
+                policy: BackpressurePolicy::DropOldest,
+            },
+        )?;
+        let receiver = event_bus.subscribe("test")?;
+        for i in 0..12 {
+            event_bus.publish("test", GameEvent::Update { delta: i as f32 })?;
+        }
+
+        let deltas: Vec<f32> = receiver
+            .try_iter()
+            .filter_map(|event| match event.event {
+                GameEvent::Update { delta } => Some(delta),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(deltas, vec![7.0, 8.0, 9.0, 10.0, 11.0]);
+        let stats = event_bus.stats().topic("test");
+        assert_eq!((stats.delivered, stats.dropped), (12, 7));
+        Ok(())
+    }
+
+    #[test]
+    fn test_coalesce_keeps_latest_of_each_variant() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        event_bus.create_topic(
+            "test",
+            TopicConfig {
+                capacity: 8,
+                policy: BackpressurePolicy::Coalesce,
+            },
+        )?;
+        let receiver = event_bus.subscribe("test")?;
+        for i in 0..10 {
+            event_bus.publish(
+                "test",
+                GameEvent::Log {
+                    message: format!("log{}", i),
+                    level: LogLevel::Info,
+                },
+            )?;
+            if i % 3 == 0 {
+                event_bus.publish("test", GameEvent::TurnStart { faction_id: i })?;
+            }
+        }
+
+        let events: Vec<GameEvent> = receiver.try_iter().map(|event| event.event).collect();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], GameEvent::Log { message, .. } if message == "log9"));
+        assert!(matches!(events[1], GameEvent::TurnStart { faction_id: 9 }));
+        let stats = event_bus.stats();
+        assert_eq!(stats.topic("test").coalesced, 12);
+        assert_eq!(stats.topic("test").dropped, 0);
+        assert!(stats.to_string().contains("coalesced=12"));
+
+        // 受信済みのイベントはまとめられない
+        event_bus.publish("test", GameEvent::TurnStart { faction_id: 1 })?;
+        assert_eq!(receiver.try_iter().count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_block_waits_for_receiver() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        event_bus.create_topic(
+            "test",
+            TopicConfig {
+                capacity: 1,
+                policy: BackpressurePolicy::Block,
+            },
+        )?;
+        let receiver = event_bus.subscribe("test")?;
+        let publisher = {
+            let event_bus = event_bus.clone();
+            std::thread::spawn(move || -> anyhow::Result<usize> {
+                let mut received = 0;
+                for _ in 0..5 {
+                    received += event_bus.publish("test", GameEvent::MapUpdated)?;
+                }
+                Ok(received)
+            })
+        };
+        // 発行側が待っている間も他のトピックへは発行できる
+        std::thread::sleep(Duration::from_millis(20));
+        event_bus.publish("other", GameEvent::MapUpdated)?;
+        assert_eq!(receiver.iter().take(5).count(), 5);
+        assert_eq!(publisher.join().unwrap()?, 5);
+        assert_eq!(event_bus.stats().topic("test").dropped, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_topic_validation() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        let coalesce = TopicConfig {
+            capacity: 4,
+            policy: BackpressurePolicy::Coalesce,
+        };
+        let _receiver = event_bus.subscribe("test")?;
+        // 購読者がいる暗黙のトピックは変更できない
+        assert!(event_bus.create_topic("test", coalesce).is_err());
+        assert!(event_bus
+            .create_topic("test", TopicConfig::default())
+            .is_ok());
+        assert!(event_bus
+            .create_topic(
+                "empty",
+                TopicConfig {
+                    capacity: 0,
+                    ..coalesce
+                }
+            )
+            .is_err());
+
+        // 組み込みのトピックは設定済み
+        let updates = event_bus.subscribe(MAP_UPDATED_TOPIC)?;
+        for _ in 0..30 {
+            event_bus.publish(MAP_UPDATED_TOPIC, GameEvent::MapUpdated)?;
+        }
+        assert_eq!(updates.try_iter().count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_publish_stamps_increasing_sequence() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        let receiver = event_bus.subscribe("test")?;
+        for _ in 0..5 {
+            event_bus.publish("test", GameEvent::MapUpdated)?;
+        }
+        let sequences: Vec<u64> = receiver.try_iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences.len(), 5);
+        assert!(sequences.windows(2).all(|pair| pair[0] < pair[1]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_disconnected_subscriber_is_pruned() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        let _first = event_bus.subscribe("test")?;
+        let second = event_bus.subscribe("test")?;
+        let (id, third) = event_bus.subscribe_with_id("test")?;
+        assert_eq!(event_bus.publish("test", GameEvent::MapUpdated)?, 3);
+
+        // 受信側を破棄した購読者はエラーにならず解除される
+        drop(second);
+        assert_eq!(event_bus.publish("test", GameEvent::MapUpdated)?, 2);
+        assert_eq!(event_bus.publish("test", GameEvent::MapUpdated)?, 2);
+
+        // 明示的な解除
+        assert!(event_bus.unsubscribe("test", id));
+        assert!(!event_bus.unsubscribe("test", id));
+        assert!(!event_bus.unsubscribe("other", id));
+        assert_eq!(event_bus.publish("test", GameEvent::MapUpdated)?, 1);
+        assert_eq!(third.try_iter().count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dropped_queue_access_subscribers_are_pruned() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        event_bus.create_topic(
+            "oldest",
+            TopicConfig {
+                capacity: 4,
+                policy: BackpressurePolicy::DropOldest,
+            },
+        )?;
+        for topic in [MAP_UPDATED_TOPIC, "oldest"] {
+            let kept = event_bus.subscribe(topic)?;
+            let dropped = event_bus.subscribe(topic)?;
+            assert_eq!(event_bus.publish(topic, GameEvent::MapUpdated)?, 2);
+
+            // バスも受信側を持つトピックでも、破棄された購読者は解除される
+            drop(dropped);
+            assert_eq!(event_bus.publish(topic, GameEvent::MapUpdated)?, 1);
+            assert_eq!(event_bus.stats().topic(topic).queued, kept.len());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_coalesce_tracks_pending_kinds() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        let receiver = event_bus.subscribe(MAP_UPDATED_TOPIC)?;
+        event_bus.publish(MAP_UPDATED_TOPIC, GameEvent::MapUpdated)?;
+        event_bus.publish(MAP_UPDATED_TOPIC, GameEvent::TurnStart { faction_id: 1 })?;
+        event_bus.publish(MAP_UPDATED_TOPIC, GameEvent::MapUpdated)?;
+        assert_eq!(event_bus.stats().topic(MAP_UPDATED_TOPIC).coalesced, 1);
+
+        // 受信済みの種別は置き換えずにそのまま入れる
+        assert!(matches!(
+            receiver.try_recv()?.event,
+            GameEvent::TurnStart { .. }
+        ));
+        assert!(matches!(receiver.try_recv()?.event, GameEvent::MapUpdated));
+        event_bus.publish(MAP_UPDATED_TOPIC, GameEvent::MapUpdated)?;
+        event_bus.publish(MAP_UPDATED_TOPIC, GameEvent::TurnStart { faction_id: 2 })?;
+        assert_eq!(event_bus.stats().topic(MAP_UPDATED_TOPIC).coalesced, 1);
+        event_bus.publish(MAP_UPDATED_TOPIC, GameEvent::MapUpdated)?;
+        let events: Vec<GameEvent> = receiver.try_iter().map(|event| event.event).collect();
+        assert!(matches!(
+            events[..],
+            [
+                GameEvent::TurnStart { faction_id: 2 },
+                GameEvent::MapUpdated
+            ]
+        ));
+        assert_eq!(event_bus.stats().topic(MAP_UPDATED_TOPIC).coalesced, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_reply_echo() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        event_bus.respond("query", |payload| payload.clone())?;
+
+        let reply = event_bus.request(
+            "query",
+            GameEvent::UnitSelected { unit_id: 7 },
+            Duration::from_secs(1),
+        )?;
+        assert!(matches!(reply, GameEvent::UnitSelected { unit_id: 7 }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_timeout() {
+        let event_bus = EventBus::new();
+        // 応答しない購読者のみ
+        let _receiver = event_bus.subscribe("query").unwrap();
+
+        let error = event_bus
+            .request("query", GameEvent::MapUpdated, Duration::from_millis(20))
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<RequestError>(),
+            Some(RequestError::Timeout { .. })
+        ));
+        // タイムアウトした問い合わせは応答待ちから取り除かれる
+        assert!(event_bus.pending_replies.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_requests_resolve_to_callers() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        event_bus.respond("query", |payload| match payload {
+            GameEvent::UnitSelected { unit_id } => GameEvent::UnitLevelUp {
+                unit_id: *unit_id,
+                new_level: unit_id * 10,
+            },
+            _ => GameEvent::MapUpdated,
+        })?;
+
+        let handles: Vec<_> = (1..=8)
+            .map(|unit_id| {
+                let event_bus = event_bus.clone();
+                std::thread::spawn(move || {
+                    event_bus
+                        .request(
+                            "query",
+                            GameEvent::UnitSelected { unit_id },
+                            Duration::from_secs(1),
+                        )
+                        .map(|reply| (unit_id, reply))
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            let (unit_id, reply) = handle.join().unwrap()?;
+            match reply {
+                GameEvent::UnitLevelUp {
+                    unit_id: replied_id,
+                    new_level,
+                } => {
+                    assert_eq!(replied_id, unit_id);
+                    assert_eq!(new_level, unit_id * 10);
+                }
+                _ => panic!("Unexpected reply"),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_forwarding_guard_resets_after_panic() {
+        let result = std::panic::catch_unwind(|| {
+            let _guard = ForwardingGuard::enter().unwrap();
+            // 転送中は入れ子の転送を行わない
+            assert!(ForwardingGuard::enter().is_none());
+            panic!("転送中のパニック");
+        });
+        assert!(result.is_err());
+        // パニックで巻き戻った後も印は下りている
+        assert!(ForwardingGuard::enter().is_some());
+    }
+
+    #[test]
+    fn test_panicking_observer_does_not_poison_bus() -> anyhow::Result<()> {
+        let event_bus = EventBus::new();
+        let receiver = event_bus.subscribe("test")?;
+        event_bus.observe(|_, event| {
+            if matches!(event.event, GameEvent::Pause) {
+                panic!("観測フックのパニック");
+            }
+        });
+
+        let bus = event_bus.clone();
+        let result = std::thread::spawn(move || bus.publish("test", GameEvent::Pause)).join();
+        assert!(result.is_err());
+
+        // 以降の発行と観測フックの登録は引き続き使える
+        event_bus.observe(|_, _| {});
+        assert_eq!(event_bus.publish("test", GameEvent::Resume)?, 1);
+        assert!(matches!(receiver.try_recv()?.event, GameEvent::Resume));
+        Ok(())
+    }
 }
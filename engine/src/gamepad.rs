@@ -0,0 +1,305 @@
+//! ゲームパッド入力をマップ操作のアクションへ変換するモジュール
+//!
+//! ボタンとスティックの入力をハードウェアに依存しない形で表し、InputActionへ
+//! 変換する。左スティックでスクロール、右スティックでズーム、十字キーで仮想カーソル、
+//! 南ボタンで決定、東ボタンで選択解除、左右のバンパーで手番勢力のユニットを順に選ぶ。
+//! 実機の読み取りは`gamepad`フィーチャーを有効にしたときのGamepadPollerが行う。
+use crate::input::InputAction;
+use std::time::Duration;
+
+/// スティックの不感帯の既定値（倒した量の割合）
+pub const DEFAULT_DEADZONE: f32 = 0.2;
+
+/// ゲームパッドのボタン（XboxのA/Bではなく位置で呼ぶ）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    South,       // 決定（Xboxの A）
+    East,        // 選択解除（Xboxの B）
+    LeftBumper,  // 前のユニット
+    RightBumper, // 次のユニット
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// ゲームパッドのスティックの軸
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+}
+
+/// ゲームパッドの入力イベント（軸の値は-1.0〜1.0で右・上が正）
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GamepadEvent {
+    ButtonPressed(GamepadButton),
+    AxisMoved(GamepadAxis, f32),
+}
+
+/// スティックの入力に円形の不感帯を適用する
+///
+/// 倒した量が不感帯以下なら(0, 0)。それより大きければ不感帯の端を0、
+/// 倒し切った位置を1として向きを保ったまま割り当て直す。
+pub fn apply_deadzone(x: f32, y: f32, deadzone: f32) -> (f32, f32) {
+    let magnitude = x.hypot(y);
+    if magnitude <= deadzone || magnitude == 0.0 {
+        return (0.0, 0.0);
+    }
+    let scaled = ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0);
+    (x / magnitude * scaled, y / magnitude * scaled)
+}
+
+/// ゲームパッドの状態を保持し、入力をアクションに変換する
+#[derive(Debug, Clone)]
+pub struct GamepadInput {
+    pub deadzone: f32,
+    pub scroll_speed: f32, // 倒し切ったときのスクロール速度（ズーム1でのピクセル/秒）
+    pub zoom_speed: f32,   // 倒し切ったときの1秒あたりのズーム倍率
+    left_stick: (f32, f32),
+    right_stick: (f32, f32),
+    scroll_remainder: (f32, f32), // 1ピクセルに満たないスクロール量の繰り越し
+}
+
+impl Default for GamepadInput {
+    fn default() -> Self {
+        Self {
+            deadzone: DEFAULT_DEADZONE,
+            scroll_speed: 320.0,
+            zoom_speed: 2.0,
+            left_stick: (0.0, 0.0),
+            right_stick: (0.0, 0.0),
+            scroll_remainder: (0.0, 0.0),
+        }
+    }
+}
+
+impl GamepadInput {
+    /// 既定の設定で作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 入力イベントを処理する（ボタンはアクションを返し、スティックは状態だけ更新する）
+    pub fn handle_event(&mut self, event: GamepadEvent) -> Option<InputAction> {
+        let button = match event {
+            GamepadEvent::ButtonPressed(button) => button,
+            GamepadEvent::AxisMoved(axis, value) => {
+                let value = value.clamp(-1.0, 1.0);
+                match axis {
+                    GamepadAxis::LeftStickX => self.left_stick.0 = value,
+                    GamepadAxis::LeftStickY => self.left_stick.1 = value,
+                    GamepadAxis::RightStickX => self.right_stick.0 = value,
+                    GamepadAxis::RightStickY => self.right_stick.1 = value,
+                }
+                return None;
+            }
+        };
+        Some(match button {
+            GamepadButton::South => InputAction::SelectAtCursor,
+            GamepadButton::East => InputAction::ClearSelection,
+            GamepadButton::LeftBumper => InputAction::CycleUnit { reverse: true },
+            GamepadButton::RightBumper => InputAction::CycleUnit { reverse: false },
+            GamepadButton::DPadUp => InputAction::MoveCursor { dx: 0, dy: -1 },
+            GamepadButton::DPadDown => InputAction::MoveCursor { dx: 0, dy: 1 },
+            GamepadButton::DPadLeft => InputAction::MoveCursor { dx: -1, dy: 0 },
+            GamepadButton::DPadRight => InputAction::MoveCursor { dx: 1, dy: 0 },
+        })
+    }
+
+    /// 経過時間分のスティック操作をアクションに変換する（毎フレーム呼ぶ）
+    ///
+    /// スクロール量は現在のズーム率に比例させ、画面上のタイルの流れる速さを
+    /// ズームによらず一定にする。
+    pub fn update(&mut self, elapsed: Duration, zoom: f32) -> Vec<InputAction> {
+        let seconds = elapsed.as_secs_f32();
+        let mut actions = Vec::new();
+
+        let (x, y) = apply_deadzone(self.left_stick.0, self.left_stick.1, self.deadzone);
+        if (x, y) == (0.0, 0.0) {
+            self.scroll_remainder = (0.0, 0.0);
+        } else {
+            let speed = self.scroll_speed * zoom * seconds;
+            // スティックは上が正、スクロールは下が正
+            self.scroll_remainder.0 += x * speed;
+            self.scroll_remainder.1 -= y * speed;
+            let dx = self.scroll_remainder.0.trunc();
+            let dy = self.scroll_remainder.1.trunc();
+            self.scroll_remainder.0 -= dx;
+            self.scroll_remainder.1 -= dy;
+            if dx != 0.0 || dy != 0.0 {
+                actions.push(InputAction::ScrollPixels {
+                    dx: dx as i32,
+                    dy: dy as i32,
+                });
+            }
+        }
+
+        let (_, zoom_axis) = apply_deadzone(self.right_stick.0, self.right_stick.1, self.deadzone);
+        if zoom_axis != 0.0 && seconds > 0.0 {
+            actions.push(InputAction::Zoom(self.zoom_speed.powf(zoom_axis * seconds)));
+        }
+        actions
+    }
+}
+
+/// gilrsで接続中のゲームパッドを読み取る
+#[cfg(feature = "gamepad")]
+pub struct GamepadPoller {
+    gilrs: gilrs::Gilrs,
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadPoller {
+    /// ゲームパッドの読み取りを開始する
+    pub fn new() -> anyhow::Result<Self> {
+        let gilrs = gilrs::Gilrs::new()
+            .map_err(|error| anyhow::anyhow!("ゲームパッドを初期化できません: {}", error))?;
+        Ok(Self { gilrs })
+    }
+
+    /// 溜まったイベントを読み取り、ボタン入力をアクションに変換する
+    ///
+    /// スティックの値はinputに記録されるので、続けてGamepadInput::updateを呼ぶ。
+    pub fn poll(&mut self, input: &mut GamepadInput) -> Vec<InputAction> {
+        let mut actions = Vec::new();
+        while let Some(gilrs::Event { event, .. }) = self.gilrs.next_event() {
+            if let Some(event) = convert_event(event) {
+                actions.extend(input.handle_event(event));
+            }
+        }
+        actions
+    }
+}
+
+/// gilrsのイベントを対応するゲームパッドイベントに変換する（対象外はNone）
+#[cfg(feature = "gamepad")]
+fn convert_event(event: gilrs::EventType) -> Option<GamepadEvent> {
+    use gilrs::{Axis, Button, EventType};
+    match event {
+        EventType::ButtonPressed(button, _) => {
+            let button = match button {
+                Button::South => GamepadButton::South,
+                Button::East => GamepadButton::East,
+                Button::LeftTrigger => GamepadButton::LeftBumper,
+                Button::RightTrigger => GamepadButton::RightBumper,
+                Button::DPadUp => GamepadButton::DPadUp,
+                Button::DPadDown => GamepadButton::DPadDown,
+                Button::DPadLeft => GamepadButton::DPadLeft,
+                Button::DPadRight => GamepadButton::DPadRight,
+                _ => return None,
+            };
+            Some(GamepadEvent::ButtonPressed(button))
+        }
+        EventType::AxisChanged(axis, value, _) => {
+            let axis = match axis {
+                Axis::LeftStickX => GamepadAxis::LeftStickX,
+                Axis::LeftStickY => GamepadAxis::LeftStickY,
+                Axis::RightStickX => GamepadAxis::RightStickX,
+                Axis::RightStickY => GamepadAxis::RightStickY,
+                _ => return None,
+            };
+            Some(GamepadEvent::AxisMoved(axis, value))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventBus;
+    use crate::gui::map_gui::MapGUI;
+    use anyhow::Result;
+    use model::{Map, MapPosition, Unit, UnitType};
+
+    #[test]
+    fn test_apply_deadzone() {
+        assert_eq!(apply_deadzone(0.1, -0.1, 0.2), (0.0, 0.0));
+        assert_eq!(apply_deadzone(0.0, 0.0, 0.0), (0.0, 0.0));
+        // 不感帯の端から倒し切るまでを0〜1に割り当て直す
+        let (x, y) = apply_deadzone(0.6, 0.0, 0.2);
+        assert!((x - 0.5).abs() < 1e-6 && y == 0.0);
+        let (x, y) = apply_deadzone(-1.0, 1.0, 0.2);
+        assert!((x + std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert!((y - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sticks_scroll_and_zoom() {
+        let mut input = GamepadInput::new();
+        let frame = Duration::from_millis(100);
+        assert!(input.update(frame, 1.0).is_empty());
+
+        // 不感帯内の揺れは無視する
+        input.handle_event(GamepadEvent::AxisMoved(GamepadAxis::LeftStickX, 0.15));
+        assert!(input.update(frame, 1.0).is_empty());
+
+        // 倒し切ると0.1秒で32ピクセル、ズーム2なら倍になる（上は負の向き）
+        input.handle_event(GamepadEvent::AxisMoved(GamepadAxis::LeftStickX, 0.0));
+        input.handle_event(GamepadEvent::AxisMoved(GamepadAxis::LeftStickY, 1.0));
+        assert_eq!(
+            input.update(frame, 1.0),
+            vec![InputAction::ScrollPixels { dx: 0, dy: -32 }]
+        );
+        assert_eq!(
+            input.update(frame, 2.0),
+            vec![InputAction::ScrollPixels { dx: 0, dy: -64 }]
+        );
+
+        // 1ピクセルに満たない量は次のフレームへ繰り越す
+        let short = Duration::from_millis(2);
+        assert!(input.update(short, 1.0).is_empty());
+        assert_eq!(
+            input.update(short, 1.0),
+            vec![InputAction::ScrollPixels { dx: 0, dy: -1 }]
+        );
+
+        input.handle_event(GamepadEvent::AxisMoved(GamepadAxis::LeftStickY, 0.0));
+        input.handle_event(GamepadEvent::AxisMoved(GamepadAxis::RightStickY, 1.0));
+        let actions = input.update(Duration::from_secs(1), 1.0);
+        assert_eq!(actions, vec![InputAction::Zoom(2.0)]);
+    }
+
+    #[test]
+    fn test_buttons_drive_cursor_and_unit_cycle() -> Result<()> {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        map_gui.set_map(Map::new(10, 10));
+        for (id, x) in [(4, 3), (2, 6)] {
+            map_gui.add_unit(Unit::new(
+                id,
+                format!("ユニット{}", id),
+                UnitType::Ranged,
+                1,
+                MapPosition::new(x, 0),
+            ))?;
+        }
+        map_gui.set_active_faction(Some(1));
+        let mut input = GamepadInput::new();
+        let mut press = |map_gui: &mut MapGUI, button| {
+            let action = input.handle_event(GamepadEvent::ButtonPressed(button));
+            map_gui.apply_input(action.expect("ボタンはアクションになる"))
+        };
+
+        press(&mut map_gui, GamepadButton::RightBumper)?;
+        assert_eq!(map_gui.get_selected_unit_id(), Some(2));
+        press(&mut map_gui, GamepadButton::RightBumper)?;
+        assert_eq!(map_gui.get_selected_unit_id(), Some(4));
+        press(&mut map_gui, GamepadButton::LeftBumper)?;
+        assert_eq!(map_gui.get_selected_unit_id(), Some(2));
+
+        press(&mut map_gui, GamepadButton::East)?;
+        assert_eq!(map_gui.get_selected_unit_id(), None);
+        assert_eq!(map_gui.get_cursor(), Some(MapPosition::new(6, 0)));
+        for _ in 0..3 {
+            press(&mut map_gui, GamepadButton::DPadLeft)?;
+        }
+        press(&mut map_gui, GamepadButton::DPadUp)?;
+        assert_eq!(map_gui.get_cursor(), Some(MapPosition::new(3, 0)));
+        let outcome = press(&mut map_gui, GamepadButton::South)?;
+        assert_eq!(outcome, Some(crate::ClickOutcome::SelectedUnit(4)));
+        Ok(())
+    }
+}
@@ -1,19 +1,180 @@
 //! マップGUIコンポーネント
-use crate::events::{EventBus, GameEvent};
+mod cursor;
+mod editor;
+mod unit_panel;
+
+use self::editor::EditorState;
+use crate::events::{EventBus, GameEvent, MAP_UPDATED_TOPIC};
 use anyhow::Result;
-use model::{Map, MapPosition, Unit};
+use model::{
+    Cell, CellType, CombatForecast, CombatResult, GridKind, LevelUp, Map, MapPosition, SightRule,
+    Structure, Unit, Visibility, VisibilityMap,
+};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// ANSIエスケープシーケンス
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_RESET_FOREGROUND: &str = "\x1b[22;39m"; // 文字色と輝度のみ戻す（背景・反転は維持）
+const ANSI_INVERSE: &str = "\x1b[7m";
+const ANSI_YELLOW_BACKGROUND: &str = "\x1b[43m";
+const ANSI_CYAN_BACKGROUND: &str = "\x1b[46m";
+const ANSI_RED_BACKGROUND: &str = "\x1b[41m";
+const ANSI_MAGENTA_BACKGROUND: &str = "\x1b[45m";
+const ANSI_BLUE_BACKGROUND: &str = "\x1b[44m";
+
+/// 勢力IDごとのユニットの文字色（1:青, 2:緑, 3:赤, その他:灰）
+fn faction_color(faction_id: u32) -> &'static str {
+    match faction_id {
+        1 => "\x1b[34m",
+        2 => "\x1b[32m",
+        3 => "\x1b[31m",
+        _ => "\x1b[90m",
+    }
+}
+
+/// 所有勢力ごとの背景色（境界のセルは明るい色で強調する）
+fn owner_background(faction_id: u32, is_border: bool) -> &'static str {
+    match (faction_id, is_border) {
+        (1, false) => "\x1b[44m",
+        (1, true) => "\x1b[104m",
+        (2, false) => "\x1b[42m",
+        (2, true) => "\x1b[102m",
+        (3, false) => "\x1b[41m",
+        (3, true) => "\x1b[101m",
+        (_, false) => "\x1b[100m",
+        (_, true) => "\x1b[47m",
+    }
+}
+
+/// ユニット種別ごとの表示文字
+fn unit_symbol(unit_type: model::UnitType) -> &'static str {
+    match unit_type {
+        model::UnitType::Infantry => "I",
+        model::UnitType::Cavalry => "K",
+        model::UnitType::Ranged => "R",
+        model::UnitType::Siege => "S",
+        model::UnitType::Support => "U",
+    }
+}
+
+/// 地形ごとの文字色（暗めの色）
+#[allow(deprecated)] // 旧来の都市・拠点は構造物と同じ表示にする
+fn terrain_color(cell_type: CellType) -> &'static str {
+    match cell_type {
+        CellType::Plain | CellType::Road => "\x1b[2;37m",
+        CellType::Forest => "\x1b[2;32m",
+        CellType::Mountain => "\x1b[2;33m",
+        CellType::Water => "\x1b[2;36m",
+        CellType::City | CellType::Base => "\x1b[2;35m",
+    }
+}
+
+/// 地形ごとの表示文字
+#[allow(deprecated)] // 旧来の都市・拠点は構造物と同じ表示にする
+fn terrain_symbol(cell_type: CellType) -> &'static str {
+    match cell_type {
+        CellType::Plain => ".",
+        CellType::Forest => "T",
+        CellType::Mountain => "^",
+        CellType::Water => "~",
+        CellType::Road => "=",
+        CellType::City => "C",
+        CellType::Base => "B",
+    }
+}
+
+/// 構造物の表示文字
+fn structure_symbol(structure: Structure) -> &'static str {
+    match structure {
+        Structure::City { .. } => "C",
+        Structure::Base => "B",
+        Structure::Ruins => "x",
+    }
+}
+
+/// セルの表示文字と文字色（構造物があれば地形より優先する）
+fn cell_appearance(cell: &Cell) -> (&'static str, &'static str) {
+    match cell.structure {
+        Some(structure) => (structure_symbol(structure), "\x1b[2;35m"),
+        None => (
+            terrain_symbol(cell.cell_type),
+            terrain_color(cell.cell_type),
+        ),
+    }
+}
+
+/// ASCII表示の1マス分の装飾
+#[derive(Debug, Clone, Copy, Default)]
+struct AsciiMarks<'a> {
+    background: Option<&'a str>, // 所有勢力の背景色
+    is_selected: bool,
+    is_cursor: bool,                  // 仮想カーソルの位置
+    highlight: Option<HighlightKind>, // 表示するハイライトの種類
+    is_explored: bool,                // 探索済みで視界外
+    stacked: usize,                   // 表示中のユニットと同じセルにいる他のユニットの数
+}
+
+/// ハイライト表示の種類（種類ごとに独立して設定でき、同時に表示できる）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HighlightKind {
+    Movement,   // 移動範囲
+    Attack,     // 攻撃範囲
+    Danger,     // 敵の攻撃が届く範囲
+    Custom(u8), // 呼び出し側が用途を決める
+}
+
+impl HighlightKind {
+    /// 同じセルに複数の種類が重なったときの表示の優先度（大きいほど優先）
+    fn display_priority(&self) -> u8 {
+        match self {
+            HighlightKind::Attack => 3,
+            HighlightKind::Movement => 2,
+            HighlightKind::Danger => 1,
+            HighlightKind::Custom(_) => 0,
+        }
+    }
+
+    /// ASCII表示の枠の文字と背景色
+    fn ascii_decoration(&self) -> (char, &'static str) {
+        match self {
+            HighlightKind::Movement => ('*', ANSI_YELLOW_BACKGROUND),
+            HighlightKind::Attack => ('!', ANSI_RED_BACKGROUND),
+            HighlightKind::Danger => ('~', ANSI_MAGENTA_BACKGROUND),
+            HighlightKind::Custom(_) => (':', ANSI_BLUE_BACKGROUND),
+        }
+    }
+}
+
+/// ASCII表示の詳細度（1文字にまとめるタイル数）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AsciiLod {
+    /// ズーム率から決める（ズーム1未満で1/zoom四方のタイルを1文字にまとめる）
+    #[default]
+    Auto,
+    /// N×Nタイルを1文字にまとめる（1で従来どおり1タイル1文字）
+    Fixed(u32),
+}
+
+/// ズーム率の範囲
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 2.0;
+
 /// マップGUIの表示オプション
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MapViewOptions {
     pub tile_size: u32,
     pub scroll_x: i32,
     pub scroll_y: i32,
     pub zoom: f32,
     pub show_grid: bool,
-    pub viewport_width: u32,  // ビューポートの幅（タイル単位）
-    pub viewport_height: u32, // ビューポートの高さ（タイル単位）
+    pub viewport_width: u32,   // ビューポートの幅（タイル単位）
+    pub viewport_height: u32,  // ビューポートの高さ（タイル単位）
+    pub use_color: bool,       // ASCII表示でANSIカラーを使うか（端末判定は呼び出し側で行う）
+    pub ascii_lod: AsciiLod,   // ASCII表示の詳細度
+    pub show_ownership: bool,  // 所有勢力を表示するか（カラー時は背景色、それ以外は別の表で表示）
+    pub show_unit_panel: bool, // ASCII表示の右側にユニット一覧を表示するか
 }
 
 impl Default for MapViewOptions {
@@ -26,19 +187,55 @@ impl Default for MapViewOptions {
             show_grid: true,
             viewport_width: 20,  // デフォルトのビューポート幅
             viewport_height: 15, // デフォルトのビューポート高さ
+            use_color: false,
+            ascii_lod: AsciiLod::Auto,
+            show_ownership: false,
+            show_unit_panel: false,
         }
     }
 }
 
+impl MapViewOptions {
+    /// ズーム率を0.25〜2.0の範囲に収める（NaNや無限大は1.0として扱う）
+    pub fn clamp_zoom(&mut self) {
+        self.zoom = if self.zoom.is_finite() {
+            self.zoom.clamp(MIN_ZOOM, MAX_ZOOM)
+        } else {
+            1.0
+        };
+    }
+}
+
+/// クリック操作の結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickOutcome {
+    SelectedUnit(u32),                               // ユニットを選択した
+    SelectedCell(MapPosition),                       // ユニットのいないセルを選択した
+    MoveRequested { unit_id: u32, to: MapPosition }, // 選択中ユニットの移動を要求した
+    Deselected,                                      // 選択を解除した
+    OutOfBounds,                                     // ビューポートまたはマップの範囲外
+    Painted(MapPosition),                            // 編集モードで地形を塗った
+}
+
 /// マップGUIコンポーネント
 pub struct MapGUI {
     event_bus: EventBus,
     map: Option<Map>,
     units: HashMap<u32, Unit>,
+    position_index: HashMap<MapPosition, Vec<u32>>, // 位置ごとのユニットID（配置順）
+    allow_stacking: bool,                           // 同一セルへの複数配置を許可するか
     view_options: MapViewOptions,
     selected_position: Option<MapPosition>,
     selected_unit_id: Option<u32>,
-    highlight_positions: Vec<MapPosition>,
+    highlights: HashMap<HighlightKind, Vec<MapPosition>>, // 種類ごとのハイライト表示位置
+    active_faction: Option<u32>,                          // 手番制限中の勢力ID（Noneなら制限なし）
+    visibility: Option<VisibilityMap>,                    // 表示中の勢力の視界（Noneなら全表示）
+    batch_depth: u32,                                     // batch()のネスト深さ
+    pending_map_update: bool,                             // バッチ中に保留されたマップ更新があるか
+    editor: EditorState,                                  // マップ編集モードの状態
+    stack_cursor: HashMap<MapPosition, usize>, // 位置ごとに最後に選択したスタック内の順番
+    cursor: Option<MapPosition>,               // 仮想カーソルの位置（選択とは別）
+    dirty: bool,                               // 前回の描画から表示内容が変わったか
 }
 
 impl MapGUI {
@@ -48,17 +245,47 @@ impl MapGUI {
             event_bus,
             map: None,
             units: HashMap::new(),
+            position_index: HashMap::new(),
+            allow_stacking: false,
             view_options: MapViewOptions::default(),
             selected_position: None,
             selected_unit_id: None,
-            highlight_positions: Vec::new(),
+            highlights: HashMap::new(),
+            active_faction: None,
+            visibility: None,
+            batch_depth: 0,
+            pending_map_update: false,
+            editor: EditorState::default(),
+            stack_cursor: HashMap::new(),
+            cursor: None,
+            dirty: true,
+        }
+    }
+
+    /// 複数の変更をまとめて行い、マップ更新イベントを最後に1回だけ発行する
+    ///
+    /// クロージャ内で発生したMapUpdatedイベントは保留され、最も外側のbatchの
+    /// 終了時に変更があった場合のみ1回発行される。ネストして呼び出してもよい。
+    pub fn batch<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.batch_depth += 1;
+        let result = f(self);
+        self.batch_depth -= 1;
+        if self.batch_depth == 0 && self.pending_map_update {
+            self.pending_map_update = false;
+            self.event_bus
+                .notify(MAP_UPDATED_TOPIC, GameEvent::MapUpdated);
         }
+        result
     }
 
     /// マップを設定
+    ///
+    /// 編集の取り消し/やり直し履歴は前のマップに対するものなので消す。
     pub fn set_map(&mut self, map: Map) {
         self.map = Some(map);
-        self.publish_map_updated().ok();
+        self.editor.clear_history();
+        self.clamp_scroll();
+        self.publish_map_updated();
     }
 
     /// マップを取得
@@ -67,53 +294,277 @@ impl MapGUI {
     }
 
     /// ユニットを追加
-    pub fn add_unit(&mut self, unit: Unit) {
+    ///
+    /// 同じIDのユニットが既に存在する場合、マップ範囲外の位置、または
+    /// 他のユニットがいる位置（スタック不許可時）への配置はエラーになる。
+    pub fn add_unit(&mut self, unit: Unit) -> Result<()> {
+        if self.units.contains_key(&unit.id) {
+            return Err(anyhow::anyhow!(
+                "ユニットIDが重複しています: ID {}",
+                unit.id
+            ));
+        }
+        self.validate_placement(unit.id, &unit.position)?;
+        self.index_insert(unit.id, unit.position);
         self.units.insert(unit.id, unit);
-        self.publish_map_updated().ok();
+        self.publish_map_updated();
+        Ok(())
+    }
+
+    /// 複数のユニットをまとめて追加（マップ更新イベントは1回だけ発行）
+    ///
+    /// 配置できないユニットがあった時点でエラーを返す。それまでのユニットは追加済みとなる。
+    pub fn add_units(&mut self, units: impl IntoIterator<Item = Unit>) -> Result<()> {
+        self.batch(|gui| units.into_iter().try_for_each(|unit| gui.add_unit(unit)))
     }
 
     /// ユニットを更新
+    ///
+    /// 位置が変わる場合は配置先を検証し、配置できなければ更新せずfalseを返す。
     pub fn update_unit(&mut self, unit: Unit) -> bool {
-        if let std::collections::hash_map::Entry::Occupied(mut e) = self.units.entry(unit.id) {
-            e.insert(unit);
-            self.publish_map_updated().ok();
-            true
-        } else {
-            false
+        let Some(from) = self.units.get(&unit.id).map(|u| u.position) else {
+            return false;
+        };
+        let (unit_id, to) = (unit.id, unit.position);
+        if from != to {
+            if self.validate_placement(unit_id, &to).is_err() {
+                return false;
+            }
+            self.index_remove(unit_id, from);
+            self.index_insert(unit_id, to);
+        }
+        self.units.insert(unit_id, unit);
+        if from != to {
+            self.publish_unit_moved(unit_id, from, to);
         }
+        self.publish_map_updated();
+        true
     }
 
     /// ユニットを削除
     pub fn remove_unit(&mut self, unit_id: u32) -> bool {
-        if self.units.remove(&unit_id).is_some() {
+        if let Some(unit) = self.units.remove(&unit_id) {
+            self.index_remove(unit_id, unit.position);
             if let Some(selected_id) = self.selected_unit_id {
                 if selected_id == unit_id {
                     self.selected_unit_id = None;
                 }
             }
-            self.publish_map_updated().ok();
+            self.publish_map_updated();
             true
         } else {
             false
         }
     }
 
+    /// 同一セルへの複数ユニット配置を許可するかを設定
+    pub fn set_allow_stacking(&mut self, allow: bool) {
+        self.allow_stacking = allow;
+    }
+
+    /// 同一セルへの複数ユニット配置が許可されているか
+    pub fn get_allow_stacking(&self) -> bool {
+        self.allow_stacking
+    }
+
+    /// 指定位置にユニットを配置できるか検証する（unit_id自身は占有判定から除く）
+    fn validate_placement(&self, unit_id: u32, position: &MapPosition) -> Result<()> {
+        if let Some(map) = &self.map {
+            if !map.is_valid_position(position) {
+                return Err(anyhow::anyhow!("無効なマップ位置: {:?}", position));
+            }
+        }
+        if !self.allow_stacking {
+            if let Some(&other_id) = self
+                .position_index
+                .get(position)
+                .and_then(|ids| ids.iter().find(|&&id| id != unit_id))
+            {
+                return Err(anyhow::anyhow!(
+                    "位置{:?}には既にユニット{}がいます",
+                    position,
+                    other_id
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 位置インデックスにユニットを登録
+    fn index_insert(&mut self, unit_id: u32, position: MapPosition) {
+        self.position_index
+            .entry(position)
+            .or_default()
+            .push(unit_id);
+    }
+
+    /// 位置インデックスからユニットを削除
+    fn index_remove(&mut self, unit_id: u32, position: MapPosition) {
+        if let Some(ids) = self.position_index.get_mut(&position) {
+            ids.retain(|&id| id != unit_id);
+            if ids.is_empty() {
+                self.position_index.remove(&position);
+            }
+        }
+    }
+
+    /// 全ユニットを可変参照で取得（ターン処理などの一括更新用）
+    ///
+    /// 位置インデックスは更新されないため、位置の変更にはupdate_unitを使うこと。
+    /// 変更の有無は追えないため、描画が必要なことを示すフラグを立てる。
+    pub fn units_mut(&mut self) -> impl Iterator<Item = &mut Unit> {
+        self.dirty = true;
+        self.units.values_mut()
+    }
+
+    /// 手番制限を設定（Someの場合、その勢力以外のユニットは選択できない）
+    pub fn set_active_faction(&mut self, faction_id: Option<u32>) {
+        self.active_faction = faction_id;
+    }
+
+    /// 手番制限中の勢力IDを取得
+    pub fn get_active_faction(&self) -> Option<u32> {
+        self.active_faction
+    }
+
+    /// 表示に使う視界マップを設定（Noneで視界制限を解除）
+    pub fn set_visibility(&mut self, visibility: Option<VisibilityMap>) {
+        self.visibility = visibility;
+        self.publish_map_updated();
+    }
+
+    /// 表示に使う視界マップを取得
+    pub fn get_visibility(&self) -> Option<&VisibilityMap> {
+        self.visibility.as_ref()
+    }
+
+    /// 指定勢力の視界をユニット位置から再計算する
+    ///
+    /// 同じ勢力の視界が設定済みなら探索済みのセルを引き継ぐ。マップ未設定時はエラー。
+    pub fn update_visibility(&mut self, faction_id: u32, rule: SightRule) -> Result<()> {
+        let map = self
+            .map
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("マップが設定されていません"))?;
+        let mut visibility = match self.visibility.take() {
+            Some(visibility) if visibility.faction_id() == faction_id => visibility,
+            _ => VisibilityMap::new(map.width, map.height, faction_id),
+        };
+        visibility.update(map, self.units.values(), rule);
+        self.set_visibility(Some(visibility));
+        Ok(())
+    }
+
+    /// ユニットに経験値を与え、レベルアップごとにUnitLevelUpイベントを発行
+    pub fn grant_experience(&mut self, unit_id: u32, amount: u32) -> Result<Vec<LevelUp>> {
+        let unit = self
+            .units
+            .get_mut(&unit_id)
+            .ok_or_else(|| anyhow::anyhow!("ユニットが見つかりません: ID {}", unit_id))?;
+        let level_ups = unit.gain_experience(amount);
+        self.dirty = true;
+        self.publish_level_ups(unit_id, &level_ups);
+        Ok(level_ups)
+    }
+
     /// IDでユニットを取得
     pub fn get_unit(&self, unit_id: u32) -> Option<&Unit> {
         self.units.get(&unit_id)
     }
 
-    /// 指定された位置にあるユニットを取得
+    /// ユニットが別のユニットを攻撃した場合の戦闘結果の見積もり
+    ///
+    /// 与えるダメージには防御側、反撃には攻撃側のセルの防御ボーナスを含む
+    /// （セル未設定の位置は平地として扱う）。
+    /// どちらかのユニットがいない場合やマップ未設定時はNone。
+    pub fn attack_forecast(&self, attacker_id: u32, defender_id: u32) -> Option<CombatForecast> {
+        let attacker = self.units.get(&attacker_id)?;
+        let defender = self.units.get(&defender_id)?;
+        let (attacker_cell, defender_cell) = self.combat_cells(attacker, defender)?;
+        Some(model::forecast(
+            attacker,
+            defender,
+            &attacker_cell,
+            &defender_cell,
+        ))
+    }
+
+    /// ユニットが別のユニットを攻撃する
+    ///
+    /// 戦闘はattack_forecastと同じ条件で解決し、UnitAttackedと双方のレベルアップごとの
+    /// UnitLevelUpイベントを発行する。倒されたユニットはマップから取り除く。
+    /// どちらかのユニットがいない場合、マップ未設定時、防御側が攻撃側の射程
+    /// （UnitType::attack_range）の外にいる場合はエラー。
+    pub fn attack_unit(&mut self, attacker_id: u32, defender_id: u32) -> Result<CombatResult> {
+        let (Some(mut attacker), Some(mut defender)) = (
+            self.units.get(&attacker_id).cloned(),
+            self.units.get(&defender_id).cloned(),
+        ) else {
+            return Err(anyhow::anyhow!(
+                "ユニットが見つかりません: ID {} / {}",
+                attacker_id,
+                defender_id
+            ));
+        };
+        let (attacker_cell, defender_cell) = self
+            .combat_cells(&attacker, &defender)
+            .ok_or_else(|| anyhow::anyhow!("マップが設定されていません"))?;
+        if !self.in_attack_range(&attacker, &defender) {
+            return Err(anyhow::anyhow!(
+                "射程外のユニットは攻撃できません: ID {} → ID {}",
+                attacker_id,
+                defender_id
+            ));
+        }
+        let result =
+            model::resolve_attack(&mut attacker, &mut defender, &attacker_cell, &defender_cell);
+
+        self.batch(|gui| {
+            gui.units.insert(attacker_id, attacker);
+            gui.units.insert(defender_id, defender);
+            gui.event_bus.notify(
+                "map_gui",
+                GameEvent::UnitAttacked {
+                    attacker: attacker_id,
+                    defender: defender_id,
+                    damage: result.damage_dealt,
+                },
+            );
+            gui.publish_level_ups(attacker_id, &result.attacker_level_ups);
+            gui.publish_level_ups(defender_id, &result.defender_level_ups);
+            if result.attacker_destroyed {
+                gui.remove_unit(attacker_id);
+            }
+            if result.defender_destroyed {
+                gui.remove_unit(defender_id);
+            }
+            gui.publish_map_updated();
+        });
+        Ok(result)
+    }
+
+    /// 指定された位置にあるユニットを取得（スタック時は最初に配置されたユニット）
     pub fn get_unit_at_position(&self, position: &MapPosition) -> Option<&Unit> {
-        self.units
-            .values()
-            .find(|unit| unit.position.x == position.x && unit.position.y == position.y)
+        self.position_index
+            .get(position)
+            .and_then(|ids| ids.first())
+            .and_then(|id| self.units.get(id))
+    }
+
+    /// 指定された位置にある全ユニットを配置順に取得
+    pub fn get_units_at_position(&self, position: &MapPosition) -> Vec<&Unit> {
+        self.position_index
+            .get(position)
+            .map(|ids| ids.iter().filter_map(|id| self.units.get(id)).collect())
+            .unwrap_or_default()
     }
 
-    /// 表示オプションを設定
+    /// 表示オプションを設定（ズーム率とスクロール位置は範囲内に収める）
     pub fn set_view_options(&mut self, options: MapViewOptions) {
         self.view_options = options;
-        self.publish_map_updated().ok();
+        self.view_options.clamp_zoom();
+        self.clamp_scroll();
+        self.publish_map_updated();
     }
 
     /// 表示オプションを取得
@@ -122,36 +573,161 @@ impl MapGUI {
     }
 
     /// マップをスクロール
+    ///
+    /// スクロール位置が変わらなかった場合（範囲端での操作など）はイベントを発行しない。
+    /// 連続したスクロールはbatch()で囲むと1回の更新イベントにまとめられる。
     pub fn scroll(&mut self, dx: i32, dy: i32) {
-        self.view_options.scroll_x += dx;
-        self.view_options.scroll_y += dy;
-        self.publish_map_updated().ok();
+        let before = (self.view_options.scroll_x, self.view_options.scroll_y);
+        self.view_options.scroll_x = self.view_options.scroll_x.saturating_add(dx);
+        self.view_options.scroll_y = self.view_options.scroll_y.saturating_add(dy);
+        self.clamp_scroll();
+        if (self.view_options.scroll_x, self.view_options.scroll_y) != before {
+            self.publish_map_updated();
+        }
+    }
+
+    /// スクロール可能な範囲を返す (min_x, min_y, max_x, max_y)
+    ///
+    /// マップがビューポートに収まる方向は0に固定される。マップ未設定時は(0, 0, 0, 0)。
+    pub fn scroll_bounds(&self) -> (i32, i32, i32, i32) {
+        let Some(map) = &self.map else {
+            return (0, 0, 0, 0);
+        };
+        let tile_size = self.scaled_tile_size();
+        let max_x = (map.width as i32 - self.view_options.viewport_width as i32) * tile_size;
+        let max_y = (map.height as i32 - self.view_options.viewport_height as i32) * tile_size;
+        (0, 0, max_x.max(0), max_y.max(0))
+    }
+
+    /// スクロール位置をマップの範囲内に制限（マップ未設定時は何もしない）
+    fn clamp_scroll(&mut self) {
+        if self.map.is_none() {
+            return;
+        }
+        let (min_x, min_y, max_x, max_y) = self.scroll_bounds();
+        self.view_options.scroll_x = self.view_options.scroll_x.clamp(min_x, max_x);
+        self.view_options.scroll_y = self.view_options.scroll_y.clamp(min_y, max_y);
     }
 
-    /// マップのズームを変更
+    /// マップのズームを変更（ビューポート中央を基準にする）
     pub fn zoom(&mut self, factor: f32) {
+        let tile_size = self.scaled_tile_size();
+        let center_x = self.view_options.viewport_width as i32 * tile_size / 2;
+        let center_y = self.view_options.viewport_height as i32 * tile_size / 2;
+        self.zoom_at(factor, center_x, center_y);
+    }
+
+    /// 指定したスクリーン座標を基準にズームを変更
+    ///
+    /// 基準点の下にあるマップ上の位置がズーム後も同じスクリーン座標に留まるよう、
+    /// スクロール位置を補正する。
+    pub fn zoom_at(&mut self, factor: f32, screen_x: i32, screen_y: i32) {
+        let old_tile_size = self.scaled_tile_size();
         self.view_options.zoom *= factor;
         // ズーム値の制限
-        self.view_options.zoom = self.view_options.zoom.clamp(0.25, 2.0);
-        self.publish_map_updated().ok();
+        self.view_options.clamp_zoom();
+        let new_tile_size = self.scaled_tile_size();
+
+        if old_tile_size > 0 {
+            let ratio = new_tile_size as f32 / old_tile_size as f32;
+            let world_x = (screen_x + self.view_options.scroll_x) as f32;
+            let world_y = (screen_y + self.view_options.scroll_y) as f32;
+            self.view_options.scroll_x = (world_x * ratio).round() as i32 - screen_x;
+            self.view_options.scroll_y = (world_y * ratio).round() as i32 - screen_y;
+        }
+        self.clamp_scroll();
+        self.publish_map_updated();
+    }
+
+    /// ビューポート内に表示されるタイルの範囲を返す (start_x, start_y, end_x, end_y)
+    ///
+    /// 終端は含まない。スクロール位置をタイル単位に切り捨てて求め、マップの範囲に
+    /// 制限する。ASCII表示で1文字がN×Nタイルをまとめる場合、ビューポートの文字数は
+    /// 変わらないので範囲は各方向N倍になる。描画系はすべてこの範囲を使うこと。
+    /// マップ未設定時は(0, 0, 0, 0)。
+    pub fn visible_tile_bounds(&self) -> (i32, i32, i32, i32) {
+        let Some(map) = &self.map else {
+            return (0, 0, 0, 0);
+        };
+        let tile_size = self.scaled_tile_size();
+        let (scroll_tile_x, scroll_tile_y) = if tile_size > 0 {
+            (
+                self.view_options.scroll_x / tile_size,
+                self.view_options.scroll_y / tile_size,
+            )
+        } else {
+            (0, 0)
+        };
+
+        let block = self.ascii_block_size() as i32;
+        let start_x = scroll_tile_x.clamp(0, map.width as i32);
+        let start_y = scroll_tile_y.clamp(0, map.height as i32);
+        let end_x = scroll_tile_x
+            .saturating_add((self.view_options.viewport_width as i32).saturating_mul(block))
+            .clamp(start_x, map.width as i32);
+        let end_y = scroll_tile_y
+            .saturating_add((self.view_options.viewport_height as i32).saturating_mul(block))
+            .clamp(start_y, map.height as i32);
+        (start_x, start_y, end_x, end_y)
+    }
+
+    /// ズームを反映したタイルサイズ（ピクセル）
+    pub(crate) fn scaled_tile_size(&self) -> i32 {
+        (self.view_options.tile_size as f32 * self.view_options.zoom) as i32
     }
 
     /// セルを選択
+    ///
+    /// 複数のユニットがいるセルを続けて選択すると、配置順に次のユニットを選択する。
+    /// 別のセルを選択してから戻った場合は、そのセルで最後に選択したユニットから始める。
     pub fn select_position(&mut self, position: MapPosition) -> Result<()> {
         if let Some(map) = &self.map {
             if map.is_valid_position(&position) {
+                // ユニット選択の確認（スタック内の順番を進める）
+                let stack: Vec<(u32, u32)> = self
+                    .get_units_at_position(&position)
+                    .iter()
+                    .map(|unit| (unit.id, unit.faction_id))
+                    .collect();
+                let stack_index = (!stack.is_empty()).then(|| {
+                    let start = match self.stack_cursor.get(&position) {
+                        Some(&last) if self.selected_position == Some(position) => last + 1,
+                        Some(&last) => last,
+                        None => 0,
+                    };
+                    // 手番制限中は手番の勢力のユニットだけを巡回する（いなければ先頭で判定）
+                    (0..stack.len())
+                        .map(|offset| (start + offset) % stack.len())
+                        .find(|&index| {
+                            self.active_faction
+                                .is_none_or(|faction_id| stack[index].1 == faction_id)
+                        })
+                        .unwrap_or(start % stack.len())
+                });
+                let unit_at_position = stack_index.map(|index| stack[index]);
+                if let (Some((unit_id, faction_id)), Some(active_faction)) =
+                    (unit_at_position, self.active_faction)
+                {
+                    if faction_id != active_faction {
+                        return Err(anyhow::anyhow!(
+                            "勢力{}の手番ではユニット{}を選択できません",
+                            active_faction,
+                            unit_id
+                        ));
+                    }
+                }
                 self.selected_position = Some(position);
-                // ユニット選択の確認
-                let unit_at_position = self.get_unit_at_position(&position);
-                if let Some(unit) = unit_at_position {
-                    let unit_id = unit.id;
+                if let Some(index) = stack_index.filter(|_| stack.len() > 1) {
+                    self.stack_cursor.insert(position, index);
+                }
+                if let Some((unit_id, _)) = unit_at_position {
                     self.selected_unit_id = Some(unit_id);
-                    self.publish_unit_selected(unit_id)?;
+                    self.publish_unit_selected(unit_id);
                 } else {
                     self.selected_unit_id = None;
                 }
-                self.publish_position_selected(position)?;
-                self.publish_map_updated()?;
+                self.publish_position_selected(position);
+                self.publish_map_updated();
                 Ok(())
             } else {
                 Err(anyhow::anyhow!("無効なマップ位置: {:?}", position))
@@ -166,77 +742,280 @@ impl MapGUI {
         self.selected_position
     }
 
+    /// 選択ユニットのIDを取得（レンダラーへの選択状態の受け渡し用）
+    pub fn get_selected_unit_id(&self) -> Option<u32> {
+        self.selected_unit_id
+    }
+
     /// 選択ユニットを取得
     pub fn get_selected_unit(&self) -> Option<&Unit> {
         self.selected_unit_id.and_then(|id| self.units.get(&id))
     }
 
-    /// 選択解除
+    /// 選択解除（選択に伴う移動範囲・攻撃範囲のハイライトも消す）
     pub fn clear_selection(&mut self) {
         self.selected_position = None;
         self.selected_unit_id = None;
-        self.highlight_positions.clear();
-        self.publish_map_updated().ok();
+        self.highlights.remove(&HighlightKind::Movement);
+        self.highlights.remove(&HighlightKind::Attack);
+        self.publish_map_updated();
+    }
+
+    /// 種類を指定してハイライト表示する（同じ種類の以前の表示は置き換える）
+    pub fn set_highlights(&mut self, kind: HighlightKind, positions: Vec<MapPosition>) {
+        if positions.is_empty() {
+            self.highlights.remove(&kind);
+        } else {
+            self.highlights.insert(kind, positions);
+        }
+        self.publish_map_updated();
+    }
+
+    /// 指定した種類のハイライト表示を消す
+    pub fn clear_highlights(&mut self, kind: HighlightKind) {
+        if self.highlights.remove(&kind).is_some() {
+            self.publish_map_updated();
+        }
     }
 
-    /// 特定の位置をハイライト表示
+    /// 指定した種類のハイライト表示の位置を取得
+    pub fn get_highlights(&self, kind: HighlightKind) -> &[MapPosition] {
+        self.highlights
+            .get(&kind)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// 位置に表示されるハイライトの種類（複数重なる場合は攻撃・移動・危険・任意の順）
+    pub fn highlight_at(&self, position: &MapPosition) -> Option<HighlightKind> {
+        self.highlights
+            .iter()
+            .filter(|(_, positions)| positions.contains(position))
+            .map(|(kind, _)| *kind)
+            .max_by_key(|kind| (kind.display_priority(), *kind))
+    }
+
+    /// ユニットの攻撃射程内のセルを攻撃範囲としてハイライト表示する
+    ///
+    /// 射程はユニット種別で決まり（遠距離2、攻城3、その他1）、距離はマップの格子に従う。
+    /// ユニット自身の位置は含まない。ユニットがいない場合やマップ未設定時はfalse。
+    pub fn show_attack_range(&mut self, unit_id: u32) -> bool {
+        let (Some(map), Some(unit)) = (&self.map, self.units.get(&unit_id)) else {
+            return false;
+        };
+        let positions = map
+            .positions_within(unit.position, unit.unit_type.attack_range())
+            .into_iter()
+            .filter(|pos| *pos != unit.position)
+            .collect();
+        self.set_highlights(HighlightKind::Attack, positions);
+        true
+    }
+
+    /// 特定の位置を移動範囲としてハイライト表示（set_highlightsのMovementと同じ）
     pub fn highlight_positions(&mut self, positions: Vec<MapPosition>) {
-        self.highlight_positions = positions;
-        self.publish_map_updated().ok();
+        self.set_highlights(HighlightKind::Movement, positions);
     }
 
-    /// 現在ハイライト表示されている位置を取得
+    /// 指定位置の隣接セルをハイライト表示（diagonalがtrueなら8方向）
+    pub fn highlight_neighbors(&mut self, position: MapPosition, diagonal: bool) {
+        let positions = self
+            .map
+            .as_ref()
+            .map(|map| map.neighbors(position, diagonal))
+            .unwrap_or_default();
+        self.highlight_positions(positions);
+    }
+
+    /// 指定位置から一定距離以内のセルをハイライト表示
+    pub fn highlight_within(&mut self, position: MapPosition, radius: u32) {
+        let positions = self
+            .map
+            .as_ref()
+            .map(|map| map.positions_within(position, radius))
+            .unwrap_or_default();
+        self.highlight_positions(positions);
+    }
+
+    /// 移動範囲としてハイライト表示されている位置を取得
     pub fn get_highlight_positions(&self) -> &[MapPosition] {
-        &self.highlight_positions
+        self.get_highlights(HighlightKind::Movement)
+    }
+
+    /// スクリーン座標でのクリックを処理する
+    ///
+    /// ユニットをクリックすると選択、ユニット選択中にハイライトされたセルを
+    /// クリックすると移動要求イベントを発行、それ以外の場所では選択を解除する。
+    pub fn handle_click(&mut self, screen_x: i32, screen_y: i32) -> Result<ClickOutcome> {
+        let tile_size = self.scaled_tile_size();
+        let viewport_width = self.view_options.viewport_width as i32 * tile_size;
+        let viewport_height = self.view_options.viewport_height as i32 * tile_size;
+        if screen_x < 0 || screen_y < 0 || screen_x >= viewport_width || screen_y >= viewport_height
+        {
+            return Ok(ClickOutcome::OutOfBounds);
+        }
+
+        let position = self.screen_to_map_position(screen_x, screen_y);
+        self.click_position(position)
+    }
+
+    /// マップ上の位置をクリックしたときの処理（handle_clickと仮想カーソルの決定で共通）
+    fn click_position(&mut self, position: MapPosition) -> Result<ClickOutcome> {
+        let Some(map) = &self.map else {
+            return Ok(ClickOutcome::OutOfBounds);
+        };
+        if !map.is_valid_position(&position) {
+            return Ok(ClickOutcome::OutOfBounds);
+        }
+
+        if self.editor_mode_click(position) {
+            return Ok(ClickOutcome::Painted(position));
+        }
+
+        if self.get_unit_at_position(&position).is_some() {
+            self.select_position(position)?;
+            if let Some(unit_id) = self.selected_unit_id {
+                return Ok(ClickOutcome::SelectedUnit(unit_id));
+            }
+        }
+
+        if let Some(unit_id) = self.selected_unit_id {
+            if self.get_highlight_positions().contains(&position) {
+                self.publish_move_requested(unit_id, position);
+                self.clear_selection();
+                return Ok(ClickOutcome::MoveRequested {
+                    unit_id,
+                    to: position,
+                });
+            }
+            self.clear_selection();
+            return Ok(ClickOutcome::Deselected);
+        }
+
+        self.select_position(position)?;
+        Ok(ClickOutcome::SelectedCell(position))
     }
 
     /// スクリーン座標からマップ座標への変換
+    ///
+    /// 六角形の格子のマップでは中心が最も近いタイルを返す。
     pub fn screen_to_map_position(&self, screen_x: i32, screen_y: i32) -> MapPosition {
-        let tile_size = (self.view_options.tile_size as f32 * self.view_options.zoom) as i32;
+        let tile_size = self.scaled_tile_size();
+        let grid = self.grid_kind();
+        if grid != GridKind::Square {
+            return grid.pick(
+                (screen_x + self.view_options.scroll_x) as f32,
+                (screen_y + self.view_options.scroll_y) as f32,
+                tile_size as f32,
+            );
+        }
         let map_x = (screen_x + self.view_options.scroll_x) / tile_size;
         let map_y = (screen_y + self.view_options.scroll_y) / tile_size;
         MapPosition { x: map_x, y: map_y }
     }
 
-    /// マップ座標からスクリーン座標への変換
+    /// マップ座標からスクリーン座標への変換（タイルの外接矩形の左上）
     pub fn map_to_screen_position(&self, map_x: i32, map_y: i32) -> (i32, i32) {
-        let tile_size = (self.view_options.tile_size as f32 * self.view_options.zoom) as i32;
+        let tile_size = self.scaled_tile_size();
+        let grid = self.grid_kind();
+        if grid != GridKind::Square {
+            let (x, y) = grid.tile_origin(&MapPosition::new(map_x, map_y), tile_size as f32);
+            return (
+                x.round() as i32 - self.view_options.scroll_x,
+                y.round() as i32 - self.view_options.scroll_y,
+            );
+        }
         let screen_x = map_x * tile_size - self.view_options.scroll_x;
         let screen_y = map_y * tile_size - self.view_options.scroll_y;
         (screen_x, screen_y)
     }
 
-    /// マップ更新イベントを発行
-    fn publish_map_updated(&self) -> Result<()> {
-        self.event_bus.publish(
-            "map_gui",
-            GameEvent::Log {
-                message: "マップ表示が更新されました".to_string(),
-                level: crate::events::LogLevel::Info,
-            },
-        )
+    /// 表示中のマップの格子の種類（マップ未設定時は正方形）
+    fn grid_kind(&self) -> GridKind {
+        self.map.as_ref().map_or(GridKind::Square, |map| map.grid)
+    }
+
+    /// 前回の描画から表示内容が変わったかを返し、フラグを下ろす
+    ///
+    /// マップ更新イベントを発行する変更（スクロール・ズームなどの表示の変更を含む）で
+    /// 立つ。作成直後は描画が必要なため立っている。
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// 表示内容が変わったか（フラグは下ろさない）
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// マップ更新イベントをMAP_UPDATED_TOPICへ発行（バッチ中は保留する）
+    ///
+    /// トピックはCoalesceのため、購読者が受け取っていない更新通知は1件にまとめられる。
+    /// 描画が必要なことを示すフラグはバッチ中でもすぐに立てる。
+    fn publish_map_updated(&mut self) {
+        self.dirty = true;
+        if self.batch_depth > 0 {
+            self.pending_map_update = true;
+            return;
+        }
+        self.event_bus
+            .notify(MAP_UPDATED_TOPIC, GameEvent::MapUpdated);
     }
 
     /// 位置選択イベントを発行
-    fn publish_position_selected(&self, position: MapPosition) -> Result<()> {
-        self.event_bus.publish(
-            "map_gui",
-            GameEvent::Log {
-                message: format!("セル選択: ({}, {})", position.x, position.y),
-                level: crate::events::LogLevel::Info,
-            },
-        )
+    fn publish_position_selected(&self, position: MapPosition) {
+        self.event_bus
+            .notify("map_gui", GameEvent::PositionSelected { position });
     }
 
     /// ユニット選択イベントを発行
-    fn publish_unit_selected(&self, unit_id: u32) -> Result<()> {
-        self.event_bus.publish(
-            "map_gui",
-            GameEvent::Log {
-                message: format!("ユニット選択: ID {}", unit_id),
-                level: crate::events::LogLevel::Info,
-            },
-        )
+    fn publish_unit_selected(&self, unit_id: u32) {
+        self.event_bus
+            .notify("map_gui", GameEvent::UnitSelected { unit_id });
+    }
+
+    /// ユニット移動イベントを発行
+    fn publish_unit_moved(&self, unit_id: u32, from: MapPosition, to: MapPosition) {
+        self.event_bus
+            .notify("map_gui", GameEvent::UnitMoved { unit_id, from, to });
+    }
+
+    /// 攻撃側と防御側がいるセル（セル未設定の位置は平地、マップ未設定時はNone）
+    fn combat_cells(&self, attacker: &Unit, defender: &Unit) -> Option<(Cell, Cell)> {
+        let map = self.map.as_ref()?;
+        let cell_at = |position: &MapPosition| {
+            map.get_cell(position)
+                .cloned()
+                .unwrap_or_else(|| Cell::new(CellType::Plain))
+        };
+        Some((cell_at(&attacker.position), cell_at(&defender.position)))
+    }
+
+    /// 防御側が攻撃側の射程内にいるか（マップ未設定時はfalse）
+    fn in_attack_range(&self, attacker: &Unit, defender: &Unit) -> bool {
+        self.map.as_ref().is_some_and(|map| {
+            map.distance(&attacker.position, &defender.position)
+                <= attacker.unit_type.attack_range()
+        })
+    }
+
+    /// レベルアップ1回ごとにUnitLevelUpイベントを発行
+    fn publish_level_ups(&self, unit_id: u32, level_ups: &[LevelUp]) {
+        for level_up in level_ups {
+            self.event_bus.notify(
+                "map_gui",
+                GameEvent::UnitLevelUp {
+                    unit_id,
+                    new_level: level_up.new_level,
+                },
+            );
+        }
+    }
+
+    /// 移動要求イベントを発行
+    fn publish_move_requested(&self, unit_id: u32, to: MapPosition) {
+        self.event_bus
+            .notify("map_gui", GameEvent::MoveRequested { unit_id, to });
     }
 
     /// マップGUIの描画（実際の描画はレンダリングシステムに任せる）
@@ -246,132 +1025,397 @@ impl MapGUI {
     }
 
     /// ASCIIアートとしてマップを表示する
+    ///
+    /// 詳細度が2以上の場合はN×Nタイルを1文字にまとめて表示する。
     pub fn render_ascii(&self) -> String {
-        if let Some(map) = &self.map {
-            let mut output = String::new();
-
-            // スクロール位置をタイル単位に変換（小数点以下切り捨て）
-            let scaled_tile_size =
-                (self.view_options.tile_size as f32 * self.view_options.zoom) as i32;
-            let scroll_tile_x = if scaled_tile_size > 0 {
-                self.view_options.scroll_x / scaled_tile_size
-            } else {
-                0
-            };
-            let scroll_tile_y = if scaled_tile_size > 0 {
-                self.view_options.scroll_y / scaled_tile_size
+        let Some(map) = &self.map else {
+            return "マップが設定されていません。".to_string();
+        };
+        let block = self.ascii_block_size();
+        let mut output = String::new();
+
+        // ビューポート内に表示されるタイルの範囲
+        let (start_x, start_y, end_x, end_y) = self.visible_tile_bounds();
+
+        // スクロール情報を表示
+        output.push_str(&format!(
+            "スクロール位置: ({}, {}) タイル\n",
+            start_x, start_y
+        ));
+        output.push_str(&format!(
+            "表示範囲: {}×{} タイル\n",
+            end_x - start_x,
+            end_y - start_y
+        ));
+
+        // 1文字にまとめるブロックの左上のタイル座標
+        let columns: Vec<i32> = (start_x..end_x).step_by(block as usize).collect();
+        let rows: Vec<i32> = (start_y..end_y).step_by(block as usize).collect();
+
+        // ユニット一覧を表示する場合はマップ上のユニットも一覧の記号で表示する
+        let panel_units = if self.view_options.show_unit_panel {
+            self.ascii_panel_units()
+        } else {
+            Vec::new()
+        };
+        let markers: HashMap<u32, char> = panel_units
+            .iter()
+            .map(|(marker, unit)| (unit.id, *marker))
+            .collect();
+
+        // ヘッダー行（X座標、まとめた場合は各ブロックの左端）を追加
+        let mut grid = String::from("   ");
+        for x in &columns {
+            grid.push_str(&format!("{:2}", x % 10));
+        }
+        grid.push('\n');
+
+        // ブロックの右下のタイル座標
+        let block_end = |x: i32, y: i32| {
+            MapPosition::new(
+                (x + block as i32).min(end_x) - 1,
+                (y + block as i32).min(end_y) - 1,
+            )
+        };
+        let hex = map.grid == GridKind::HexPointyTop;
+        grid.push_str(&Self::ascii_grid(&columns, &rows, hex, |x, y| {
+            if block == 1 {
+                self.ascii_cell(map, MapPosition::new(x, y), &markers)
             } else {
-                0
-            };
-
-            // ビューポート内に表示されるタイルの範囲を計算
-            let start_x = scroll_tile_x.max(0);
-            let start_y = scroll_tile_y.max(0);
-            let end_x =
-                (scroll_tile_x + self.view_options.viewport_width as i32).min(map.width as i32);
-            let end_y =
-                (scroll_tile_y + self.view_options.viewport_height as i32).min(map.height as i32);
-
-            // スクロール情報を表示
-            output.push_str(&format!(
-                "スクロール位置: ({}, {}) タイル\n",
-                start_x, start_y
-            ));
-            output.push_str(&format!(
-                "表示範囲: {}×{} タイル\n",
-                end_x - start_x,
-                end_y - start_y
-            ));
+                self.ascii_block(map, MapPosition::new(x, y), block_end(x, y))
+            }
+        }));
+        if self.view_options.show_unit_panel {
+            let panel = self.ascii_unit_panel(&panel_units);
+            output.push_str(&unit_panel::attach_side_panel(&grid, &panel));
+        } else {
+            output.push_str(&grid);
+        }
 
-            // ヘッダー行（X座標）を追加
-            output.push_str("   ");
-            for x in start_x..end_x {
-                output.push_str(&format!("{:2}", x % 10));
-            }
-            output.push('\n');
-
-            // 境界線
-            output.push_str("  +");
-            for _ in start_x..end_x {
-                output.push_str("--");
-            }
-            output.push_str("+\n");
-
-            for y in start_y..end_y {
-                // Y座標を追加
-                output.push_str(&format!("{:2}|", y % 10));
-
-                for x in start_x..end_x {
-                    let pos = MapPosition::new(x, y);
-                    let is_selected = self
-                        .selected_position
-                        .is_some_and(|selected| selected.x == x && selected.y == y);
-                    let is_highlighted = self
-                        .highlight_positions
-                        .iter()
-                        .any(|p| p.x == x && p.y == y);
-
-                    // ユニットの確認
-                    let unit_at_pos = self.get_unit_at_position(&pos);
-
-                    // セルタイプに基づいて文字を選択
-                    let mut symbol = match map.get_cell(&pos) {
-                        Some(cell) => match cell.cell_type {
-                            model::CellType::Plain => ".",
-                            model::CellType::Forest => "T",
-                            model::CellType::Mountain => "^",
-                            model::CellType::Water => "~",
-                            model::CellType::Road => "=",
-                            model::CellType::City => "C",
-                            model::CellType::Base => "B",
-                        },
-                        None => " ",
-                    }
-                    .to_string();
-
-                    // ユニットがある場合はユニットの文字を優先
-                    if let Some(unit) = unit_at_pos {
-                        symbol = match unit.unit_type {
-                            model::UnitType::Infantry => "I",
-                            model::UnitType::Cavalry => "K",
-                            model::UnitType::Ranged => "R",
-                            model::UnitType::Siege => "S",
-                            model::UnitType::Support => "U",
-                        }
-                        .to_string();
-
-                        // ユニットの所有勢力によって色分けできないので、勢力IDを数字で表現（将来的にはANSIカラーコードなどで色付け可能）
-                        if unit.faction_id > 0 {
-                            symbol = format!("{}", unit.faction_id);
-                        }
-                    }
+        // カラー表示でない場合は所有勢力を別の表で表示する
+        if self.view_options.show_ownership && !self.view_options.use_color {
+            output.push_str("所有勢力:\n");
+            output.push_str(&Self::ascii_grid(&columns, &rows, hex, |x, y| {
+                let from = MapPosition::new(x, y);
+                let symbol = match self.ascii_owner(map, from, block_end(x, y)) {
+                    None => "?".to_string(),
+                    Some(None) => ".".to_string(),
+                    Some(Some(faction_id)) => (faction_id % 10).to_string(),
+                };
+                format!(" {} ", symbol)
+            }));
+        }
+        output
+    }
 
-                    // 選択または強調表示の装飾
-                    if is_selected {
-                        symbol = format!("[{}]", symbol);
-                    } else if is_highlighted {
-                        symbol = format!("*{}*", symbol);
-                    } else {
-                        symbol = format!(" {} ", symbol);
-                    }
+    /// 枠線付きの表を作る（各マスの表示はsymbol_atで3文字分を返す）
+    ///
+    /// hexがtrueなら六角形の格子を近似するため奇数行を1文字右にずらす。
+    fn ascii_grid(
+        columns: &[i32],
+        rows: &[i32],
+        hex: bool,
+        symbol_at: impl Fn(i32, i32) -> String,
+    ) -> String {
+        let mut output = String::new();
+
+        // 境界線
+        let padding = if hex { "-" } else { "" };
+        let border = format!("  +{}{}+\n", "--".repeat(columns.len()), padding);
+        output.push_str(&border);
+
+        for &y in rows {
+            // Y座標を追加
+            output.push_str(&format!("{:2}|", y % 10));
+            let shifted = hex && y & 1 == 1;
+            if shifted {
+                output.push(' ');
+            }
+            for &x in columns {
+                output.push_str(&symbol_at(x, y));
+            }
+            if hex && !shifted {
+                output.push(' ');
+            }
+            output.push_str("|\n");
+        }
 
-                    output.push_str(&symbol);
-                }
+        // 下部境界線
+        output.push_str(&border);
+        output
+    }
+
+    /// 2点を対角とする範囲の所有勢力
+    ///
+    /// 見えているセルがなければNone、見えているセルの所有勢力がすべて同じなら
+    /// Some(その勢力)、所有者なしや複数の勢力が混在する場合はSome(None)。
+    fn ascii_owner(&self, map: &Map, from: MapPosition, to: MapPosition) -> Option<Option<u32>> {
+        let mut owners = (from.y..=to.y)
+            .flat_map(|y| (from.x..=to.x).map(move |x| MapPosition::new(x, y)))
+            .filter(|pos| self.ascii_visibility(pos) != Visibility::Unknown)
+            .map(|pos| map.owner_at(&pos));
+        let first = owners.next()?;
+        Some(first.filter(|_| owners.all(|owner| owner == first)))
+    }
+
+    /// 所有勢力の背景色（カラー表示で所有勢力を表示する場合のみ）
+    fn ascii_owner_background(
+        &self,
+        map: &Map,
+        from: MapPosition,
+        to: MapPosition,
+    ) -> Option<&'static str> {
+        if !(self.view_options.show_ownership && self.view_options.use_color) {
+            return None;
+        }
+        let faction_id = self.ascii_owner(map, from, to).flatten()?;
+        let is_border = (from.y..=to.y)
+            .flat_map(|y| (from.x..=to.x).map(move |x| MapPosition::new(x, y)))
+            .any(|pos| map.is_territory_border(&pos));
+        Some(owner_background(faction_id, is_border))
+    }
+
+    /// ASCII表示で1文字にまとめるタイルの一辺の数
+    pub fn ascii_block_size(&self) -> u32 {
+        match self.view_options.ascii_lod {
+            AsciiLod::Fixed(size) => size.max(1),
+            AsciiLod::Auto => (1.0 / self.view_options.zoom).floor().max(1.0) as u32,
+        }
+    }
+
+    /// 位置の視界（視界未設定時はすべて視界内として扱う）
+    fn ascii_visibility(&self, pos: &MapPosition) -> Visibility {
+        self.visibility
+            .as_ref()
+            .map_or(Visibility::Visible, |v| v.get(pos))
+    }
+
+    /// 位置に表示するユニット（視界外の他勢力ユニットは表示しない）
+    ///
+    /// スタックしている場合は選択中のユニット、なければ最初に配置されたユニットを表示する。
+    fn ascii_unit_at(&self, pos: &MapPosition, visibility: Visibility) -> Option<&Unit> {
+        let units = self.ascii_units_at(pos, visibility);
+        units
+            .iter()
+            .find(|unit| Some(unit.id) == self.selected_unit_id)
+            .or(units.first())
+            .copied()
+    }
+
+    /// 位置にいる表示できるユニット（配置順）
+    fn ascii_units_at(&self, pos: &MapPosition, visibility: Visibility) -> Vec<&Unit> {
+        self.get_units_at_position(pos)
+            .into_iter()
+            .filter(|unit| self.is_unit_shown(unit, visibility))
+            .collect()
+    }
+
+    /// 指定した視界の位置にいるユニットを表示するか（自勢力のユニットは常に表示）
+    fn is_unit_shown(&self, unit: &Unit, visibility: Visibility) -> bool {
+        visibility == Visibility::Visible
+            || self
+                .visibility
+                .as_ref()
+                .is_some_and(|v| v.faction_id() == unit.faction_id)
+    }
+
+    /// 1タイルを3文字で表示する（markersにあるユニットは勢力IDの代わりにその記号で表示）
+    fn ascii_cell(&self, map: &Map, pos: MapPosition, markers: &HashMap<u32, char>) -> String {
+        let is_selected = self.selected_position == Some(pos);
+        let is_cursor = self.cursor == Some(pos);
+        let highlight = self.highlight_at(&pos);
+        let visibility = self.ascii_visibility(&pos);
+        let unit_at_pos = self.ascii_unit_at(&pos, visibility);
+
+        // セルタイプに基づいて文字を選択（未踏のセルは'?'）
+        let cell = match visibility {
+            Visibility::Unknown => None,
+            _ => map.get_cell(&pos),
+        };
+        let mut color = cell.map(|cell| cell_appearance(cell).1);
+        let mut symbol = match cell {
+            Some(cell) => cell_appearance(cell).0,
+            None if visibility == Visibility::Unknown => "?",
+            None => " ",
+        }
+        .to_string();
+
+        // ユニットがある場合はユニットの文字を優先
+        if let Some(unit) = unit_at_pos {
+            symbol = unit_symbol(unit.unit_type).to_string();
 
-                output.push_str("|\n");
+            // 勢力IDを数字で表現（カラー表示時は勢力ごとに色分け）
+            if let Some(marker) = markers.get(&unit.id) {
+                symbol = marker.to_string();
+            } else if unit.faction_id > 0 {
+                symbol = format!("{}", unit.faction_id);
             }
+            color = Some(faction_color(unit.faction_id));
+        }
+
+        self.decorate_ascii_symbol(
+            symbol,
+            color,
+            AsciiMarks {
+                background: self.ascii_owner_background(map, pos, pos),
+                is_selected,
+                is_cursor,
+                highlight,
+                is_explored: visibility == Visibility::Explored,
+                stacked: self
+                    .ascii_units_at(&pos, visibility)
+                    .len()
+                    .saturating_sub(1),
+            },
+        )
+    }
 
-            // 下部境界線
-            output.push_str("  +");
-            for _ in start_x..end_x {
-                output.push_str("--");
+    /// 2点を対角とするブロックを3文字で表示する
+    ///
+    /// 表示できるユニットがいればその数（10体以上は'+'）、見えている構造物があれば
+    /// その文字（都市・拠点を廃墟より優先）、どちらもなければ最も多い地形
+    /// （同数なら地形の定義順で先のもの）を表示する。
+    fn ascii_block(&self, map: &Map, from: MapPosition, to: MapPosition) -> String {
+        const TERRAINS: [CellType; 5] = [
+            CellType::Plain,
+            CellType::Forest,
+            CellType::Mountain,
+            CellType::Water,
+            CellType::Road,
+        ];
+        let mut counts = [0usize; TERRAINS.len()];
+        let mut structure: Option<Structure> = None;
+        let mut units: Vec<&Unit> = Vec::new();
+        let mut any_known = false;
+        let mut all_explored = true;
+        let mut is_selected = false;
+        let mut is_cursor = false;
+        let mut highlight: Option<HighlightKind> = None;
+        for y in from.y..=to.y {
+            for x in from.x..=to.x {
+                let pos = MapPosition::new(x, y);
+                is_selected |= self.selected_position == Some(pos);
+                is_cursor |= self.cursor == Some(pos);
+                highlight = highlight
+                    .into_iter()
+                    .chain(self.highlight_at(&pos))
+                    .max_by_key(|kind| (kind.display_priority(), *kind));
+                let visibility = self.ascii_visibility(&pos);
+                units.extend(self.ascii_units_at(&pos, visibility));
+                if visibility == Visibility::Unknown {
+                    continue;
+                }
+                any_known = true;
+                all_explored &= visibility == Visibility::Explored;
+                if let Some(cell) = map.get_cell(&pos) {
+                    let terrain = cell.cell_type.terrain();
+                    let index = TERRAINS.iter().position(|t| *t == terrain);
+                    counts[index.expect("全地形を列挙済み")] += 1;
+                    if structure.is_none_or(|s| !s.is_capturable()) {
+                        structure = cell.structure.or(structure);
+                    }
+                }
             }
-            output.push_str("+\n");
+        }
 
-            output
+        // 最多の地形（max_byは同数なら後の要素を返すため逆順に走査する）
+        let majority = TERRAINS
+            .iter()
+            .zip(counts)
+            .rev()
+            .filter(|(_, count)| *count > 0)
+            .max_by_key(|(_, count)| *count)
+            .map(|(terrain, _)| *terrain);
+        let (symbol, color) = match (units.first(), structure, majority) {
+            (Some(unit), _, _) => {
+                let symbol = match units.len() {
+                    count @ 1..=9 => count.to_string(),
+                    _ => "+".to_string(),
+                };
+                (symbol, Some(faction_color(unit.faction_id)))
+            }
+            (None, Some(structure), _) => {
+                (structure_symbol(structure).to_string(), Some("\x1b[2;35m"))
+            }
+            (None, None, Some(terrain)) => (
+                terrain_symbol(terrain).to_string(),
+                Some(terrain_color(terrain)),
+            ),
+            (None, None, None) if !any_known => ("?".to_string(), None),
+            (None, None, None) => (" ".to_string(), None),
+        };
+
+        self.decorate_ascii_symbol(
+            symbol,
+            color,
+            AsciiMarks {
+                background: self.ascii_owner_background(map, from, to),
+                is_selected,
+                is_cursor,
+                highlight,
+                is_explored: any_known && all_explored,
+                stacked: 0,
+            },
+        )
+    }
+
+    /// 表示文字に色と選択・カーソル・強調・探索済みの装飾を付けて3文字分にする
+    ///
+    /// 装飾は選択、仮想カーソル、強調の順に優先する。強調の枠は種類ごとに異なる
+    /// （移動'*'・攻撃'!'・危険'~'・任意':'）。
+    /// 所有勢力の背景色は選択・カーソル・強調表示の背景より優先度が低い。
+    /// スタックしたユニットは装飾のないときに限り"1+2"のように他のユニットの数
+    /// （9体まで）を添える（選択・強調・探索済みの枠を優先する）。
+    fn decorate_ascii_symbol(
+        &self,
+        mut symbol: String,
+        color: Option<&str>,
+        marks: AsciiMarks,
+    ) -> String {
+        let AsciiMarks {
+            background,
+            is_selected,
+            is_cursor,
+            highlight,
+            is_explored,
+            stacked,
+        } = marks;
+        let use_color = self.view_options.use_color;
+        if let (true, Some(color)) = (use_color, color) {
+            symbol = format!("{}{}{}", color, symbol, ANSI_RESET_FOREGROUND);
+        }
+
+        // 選択または強調表示の装飾
+        if is_selected {
+            symbol = format!("[{}]", symbol);
+            if use_color {
+                symbol = format!("{}{}{}", ANSI_INVERSE, symbol, ANSI_RESET);
+            }
+        } else if is_cursor {
+            symbol = format!("<{}>", symbol);
+            if use_color {
+                symbol = format!("{}{}{}", ANSI_CYAN_BACKGROUND, symbol, ANSI_RESET);
+            }
+        } else if let Some(kind) = highlight {
+            let (frame, background) = kind.ascii_decoration();
+            symbol = format!("{}{}{}", frame, symbol, frame);
+            if use_color {
+                symbol = format!("{}{}{}", background, symbol, ANSI_RESET);
+            }
         } else {
-            "マップが設定されていません。".to_string()
+            if is_explored {
+                // 探索済みで視界外のセルは括弧で薄く表示
+                symbol = format!("({})", symbol);
+            } else if stacked > 0 {
+                symbol = format!("{}+{}", symbol, stacked.min(9));
+            } else {
+                symbol = format!(" {} ", symbol);
+            }
+            if let Some(background) = background {
+                symbol = format!("{}{}{}", background, symbol, ANSI_RESET);
+            }
         }
+        symbol
     }
 
     /// コンソールにASCIIアートとしてマップを表示する
@@ -448,8 +1492,8 @@ mod tests {
         let unit1 = create_test_unit(1, 3, 4);
         let unit2 = create_test_unit(2, 5, 6);
 
-        map_gui.add_unit(unit1);
-        map_gui.add_unit(unit2);
+        map_gui.add_unit(unit1).unwrap();
+        map_gui.add_unit(unit2).unwrap();
 
         assert_eq!(map_gui.units.len(), 2);
 
@@ -515,7 +1559,7 @@ mod tests {
         map_gui.set_map(map);
 
         let unit = create_test_unit(1, 3, 4);
-        map_gui.add_unit(unit);
+        map_gui.add_unit(unit).unwrap();
 
         // ユニットがいる位置を選択
         assert!(map_gui.select_position(MapPosition::new(3, 4)).is_ok());
@@ -533,6 +1577,25 @@ mod tests {
         assert!(map_gui.get_selected_unit().is_none());
     }
 
+    #[test]
+    fn test_selected_unit_id() {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus);
+        map_gui.set_map(create_test_map());
+
+        map_gui.add_unit(create_test_unit(1, 3, 4)).unwrap();
+        map_gui.add_unit(create_test_unit(2, 6, 6)).unwrap();
+        assert_eq!(map_gui.get_selected_unit_id(), None);
+
+        // ユニットのいるセルを選択するとIDが取得できる
+        map_gui.select_position(MapPosition::new(3, 4)).unwrap();
+        assert_eq!(map_gui.get_selected_unit_id(), Some(1));
+
+        // 選択中のユニットを削除すると選択も解除される
+        map_gui.remove_unit(1);
+        assert_eq!(map_gui.get_selected_unit_id(), None);
+    }
+
     #[test]
     fn test_coordinate_conversion() {
         let event_bus = EventBus::new();
@@ -584,4 +1647,1009 @@ mod tests {
         map_gui.clear_selection();
         assert!(map_gui.get_highlight_positions().is_empty());
     }
+
+    #[test]
+    fn test_highlight_neighbors() {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus);
+
+        // マップ未設定の場合は何もハイライトしない
+        map_gui.highlight_neighbors(MapPosition::new(0, 0), true);
+        assert!(map_gui.get_highlight_positions().is_empty());
+
+        map_gui.set_map(create_test_map());
+        map_gui.highlight_neighbors(MapPosition::new(0, 0), true);
+        assert_eq!(map_gui.get_highlight_positions().len(), 3);
+
+        map_gui.highlight_within(MapPosition::new(5, 5), 1);
+        assert_eq!(map_gui.get_highlight_positions().len(), 5);
+    }
+
+    #[test]
+    fn test_handle_click_flow() {
+        let event_bus = EventBus::new();
+        let receiver = event_bus
+            .subscribe_to("map_gui", crate::events::EventKind::MoveRequested)
+            .unwrap();
+        let mut map_gui = MapGUI::new(event_bus);
+        map_gui.set_map(create_test_map());
+        map_gui.add_unit(create_test_unit(1, 3, 4)).unwrap();
+
+        // ユニットのいるタイルをクリックすると選択される（タイルサイズ32）
+        let outcome = map_gui.handle_click(3 * 32 + 5, 4 * 32 + 5).unwrap();
+        assert_eq!(outcome, ClickOutcome::SelectedUnit(1));
+        assert_eq!(map_gui.get_selected_unit_id(), Some(1));
+
+        // ハイライトされたタイルをクリックすると移動要求が発行される
+        map_gui.highlight_neighbors(MapPosition::new(3, 4), false);
+        let outcome = map_gui.handle_click(4 * 32, 4 * 32).unwrap();
+        assert_eq!(
+            outcome,
+            ClickOutcome::MoveRequested {
+                unit_id: 1,
+                to: MapPosition::new(4, 4)
+            }
+        );
+        let events: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(events.len(), 1);
+        match events[0].event {
+            GameEvent::MoveRequested { unit_id, to } => {
+                assert_eq!(unit_id, 1);
+                assert_eq!(to, MapPosition::new(4, 4));
+            }
+            _ => panic!("Unexpected event received"),
+        }
+        assert!(map_gui.get_selected_unit_id().is_none());
+
+        // ユニット選択中にハイライト外をクリックすると選択解除
+        map_gui.handle_click(3 * 32, 4 * 32).unwrap();
+        let outcome = map_gui.handle_click(8 * 32, 8 * 32).unwrap();
+        assert_eq!(outcome, ClickOutcome::Deselected);
+        assert!(map_gui.get_selected_position().is_none());
+        assert!(receiver.try_recv().is_err());
+
+        // 何も選択していない状態で空きセルをクリックするとセル選択
+        let outcome = map_gui.handle_click(8 * 32, 8 * 32).unwrap();
+        assert_eq!(outcome, ClickOutcome::SelectedCell(MapPosition::new(8, 8)));
+    }
+
+    #[test]
+    fn test_hex_grid_picking_and_ascii() {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        map_gui.set_map(Map::new(4, 3).with_grid(GridKind::HexPointyTop));
+
+        // タイルの中心付近をクリックするとそのタイルを選ぶ（32px、奇数行は16pxずれる）
+        for y in 0..3 {
+            for x in 0..4 {
+                let (sx, sy) = map_gui.map_to_screen_position(x, y);
+                assert_eq!(
+                    map_gui.screen_to_map_position(sx + 16, sy + 18),
+                    MapPosition::new(x, y)
+                );
+            }
+        }
+        assert_eq!(map_gui.map_to_screen_position(0, 1), (16, 28));
+        // 奇数行の左上の角は上の行のタイル
+        assert_eq!(
+            map_gui.screen_to_map_position(17, 29),
+            MapPosition::new(0, 0)
+        );
+        assert_eq!(
+            map_gui.handle_click(40, 40).unwrap(),
+            ClickOutcome::SelectedCell(MapPosition::new(0, 1))
+        );
+
+        // ASCII表示は奇数行を1文字ずらして近似する
+        let output = map_gui.render_ascii();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[3], "  +---------+");
+        assert_eq!(lines[4], " 0|             |");
+        assert_eq!(lines[5], " 1| [ ]         |");
+        assert_eq!(lines[6], " 2|             |");
+    }
+
+    #[test]
+    fn test_handle_click_out_of_bounds() {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus);
+
+        // マップ未設定
+        assert_eq!(
+            map_gui.handle_click(10, 10).unwrap(),
+            ClickOutcome::OutOfBounds
+        );
+
+        map_gui.set_map(create_test_map());
+        // ビューポート外
+        assert_eq!(
+            map_gui.handle_click(-1, 10).unwrap(),
+            ClickOutcome::OutOfBounds
+        );
+        assert_eq!(
+            map_gui.handle_click(20 * 32, 10).unwrap(),
+            ClickOutcome::OutOfBounds
+        );
+        // ビューポート内だがマップ外（10x10マップ）
+        assert_eq!(
+            map_gui.handle_click(12 * 32, 10).unwrap(),
+            ClickOutcome::OutOfBounds
+        );
+    }
+
+    #[test]
+    fn test_structured_events() {
+        let event_bus = EventBus::new();
+        let receiver = event_bus
+            .subscribe_filtered("map_gui", |event| !matches!(event, GameEvent::MapUpdated))
+            .unwrap();
+        let mut map_gui = MapGUI::new(event_bus);
+        map_gui.set_map(create_test_map());
+        map_gui.add_unit(create_test_unit(1, 3, 4)).unwrap();
+
+        map_gui.select_position(MapPosition::new(3, 4)).unwrap();
+        map_gui.update_unit(create_test_unit(1, 5, 5));
+
+        let events: Vec<_> = receiver.try_iter().map(|e| e.event).collect();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[0], GameEvent::UnitSelected { unit_id: 1 }));
+        assert!(matches!(
+            events[1],
+            GameEvent::PositionSelected { position } if position == MapPosition::new(3, 4)
+        ));
+        assert!(matches!(
+            events[2],
+            GameEvent::UnitMoved { unit_id: 1, from, to }
+                if from == MapPosition::new(3, 4) && to == MapPosition::new(5, 5)
+        ));
+    }
+
+    #[test]
+    fn test_zoom_at_keeps_focus_point() {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus);
+        map_gui.set_map(Map::new(100, 100));
+        map_gui.scroll(800, 640);
+
+        let tile = MapPosition::new(30, 25);
+        let (screen_x, screen_y) = map_gui.map_to_screen_position(tile.x, tile.y);
+
+        for factor in [1.5, 0.5, 1.25] {
+            map_gui.zoom_at(factor, screen_x, screen_y);
+            let (zoomed_x, zoomed_y) = map_gui.map_to_screen_position(tile.x, tile.y);
+            assert!((zoomed_x - screen_x).abs() <= 1);
+            assert!((zoomed_y - screen_y).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_zoom_centers_on_viewport() {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus);
+
+        // ビューポート中央（20x15タイル、32px）のマップ位置はズーム後も中央に残る
+        let center = map_gui.screen_to_map_position(320, 240);
+        map_gui.zoom(2.0);
+        assert_eq!(map_gui.screen_to_map_position(320, 240), center);
+        assert_eq!(map_gui.get_view_options().scroll_x, 320);
+        assert_eq!(map_gui.get_view_options().scroll_y, 240);
+    }
+
+    #[test]
+    fn test_scroll_pinned_for_small_map() {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus);
+        // 10x10マップは20x15タイルのビューポートに収まる
+        map_gui.set_map(create_test_map());
+
+        assert_eq!(map_gui.scroll_bounds(), (0, 0, 0, 0));
+        map_gui.scroll(100, -50);
+        assert_eq!(map_gui.get_view_options().scroll_x, 0);
+        assert_eq!(map_gui.get_view_options().scroll_y, 0);
+
+        let options = MapViewOptions {
+            scroll_x: 500,
+            scroll_y: 500,
+            ..MapViewOptions::default()
+        };
+        map_gui.set_view_options(options);
+        assert_eq!(map_gui.get_view_options().scroll_x, 0);
+        assert_eq!(map_gui.get_view_options().scroll_y, 0);
+    }
+
+    #[test]
+    fn test_scroll_saturates_without_map() {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        // マップ未設定時は範囲に制限されないため、加算が飽和することを確認
+        map_gui.scroll(i32::MAX, i32::MIN);
+        map_gui.scroll(i32::MAX, i32::MIN);
+        assert_eq!(map_gui.get_view_options().scroll_x, i32::MAX);
+        assert_eq!(map_gui.get_view_options().scroll_y, i32::MIN);
+    }
+
+    #[test]
+    fn test_scroll_clamped_for_large_map() {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus);
+        map_gui.set_map(Map::new(50, 40));
+
+        // (50 - 20) * 32, (40 - 15) * 32
+        assert_eq!(map_gui.scroll_bounds(), (0, 0, 960, 800));
+
+        map_gui.scroll(10_000, 10_000);
+        assert_eq!(map_gui.get_view_options().scroll_x, 960);
+        assert_eq!(map_gui.get_view_options().scroll_y, 800);
+
+        map_gui.scroll(-10_000, 100);
+        assert_eq!(map_gui.get_view_options().scroll_x, 0);
+        assert_eq!(map_gui.get_view_options().scroll_y, 800);
+
+        // 極端な移動量でもオーバーフローせずに端で止まる
+        map_gui.scroll(i32::MAX, i32::MIN);
+        assert_eq!(map_gui.get_view_options().scroll_x, 960);
+        assert_eq!(map_gui.get_view_options().scroll_y, 0);
+
+        // ズームアウトすると範囲も縮む
+        map_gui.zoom(0.5);
+        assert_eq!(map_gui.scroll_bounds(), (0, 0, 480, 400));
+        assert!(map_gui.get_view_options().scroll_y <= 400);
+    }
+
+    #[test]
+    fn test_turn_enforced_selection() {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus);
+        map_gui.set_map(create_test_map());
+
+        let mut enemy = create_test_unit(2, 6, 6);
+        enemy.faction_id = 2;
+        map_gui.add_unit(create_test_unit(1, 3, 4)).unwrap();
+        map_gui.add_unit(enemy).unwrap();
+
+        map_gui.set_active_faction(Some(1));
+        assert!(map_gui.select_position(MapPosition::new(3, 4)).is_ok());
+        assert_eq!(map_gui.get_selected_unit_id(), Some(1));
+
+        // 他勢力のユニットは選択できず、選択状態も変わらない
+        assert!(map_gui.select_position(MapPosition::new(6, 6)).is_err());
+        assert_eq!(map_gui.get_selected_unit_id(), Some(1));
+
+        // 制限を解除すると選択できる
+        map_gui.set_active_faction(None);
+        assert!(map_gui.select_position(MapPosition::new(6, 6)).is_ok());
+        assert_eq!(map_gui.get_selected_unit_id(), Some(2));
+    }
+
+    #[test]
+    fn test_turn_enforced_selection_skips_other_factions_in_stack() -> Result<()> {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        map_gui.set_map(create_test_map());
+        map_gui.set_allow_stacking(true);
+        let mut enemy = create_test_unit(1, 2, 2);
+        enemy.faction_id = 2;
+        map_gui.add_unit(enemy)?;
+        map_gui.add_unit(create_test_unit(2, 2, 2))?;
+        map_gui.add_unit(create_test_unit(3, 2, 2))?;
+        let stack = MapPosition::new(2, 2);
+
+        // 最初のクリックで手番の勢力のユニットを選び、他勢力のユニットは飛ばして巡回する
+        map_gui.set_active_faction(Some(1));
+        let mut selected = Vec::new();
+        for _ in 0..3 {
+            map_gui.select_position(stack)?;
+            selected.push(map_gui.get_selected_unit_id().unwrap());
+        }
+        assert_eq!(selected, vec![2, 3, 2]);
+
+        // 手番の勢力のユニットがいなければ選択できない
+        map_gui.set_active_faction(Some(3));
+        assert!(map_gui.select_position(stack).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_grant_experience_publishes_level_up() {
+        let event_bus = EventBus::new();
+        let receiver = event_bus
+            .subscribe_to("map_gui", crate::events::EventKind::UnitLevelUp)
+            .unwrap();
+        let mut map_gui = MapGUI::new(event_bus);
+        map_gui.add_unit(create_test_unit(1, 0, 0)).unwrap();
+
+        let level_ups = map_gui.grant_experience(1, 250).unwrap();
+        assert_eq!(level_ups.len(), 2);
+        assert_eq!(map_gui.get_unit(1).unwrap().level, 3);
+
+        let levels: Vec<u32> = receiver
+            .try_iter()
+            .map(|e| match e.event {
+                GameEvent::UnitLevelUp { unit_id, new_level } => {
+                    assert_eq!(unit_id, 1);
+                    new_level
+                }
+                _ => panic!("Unexpected event received"),
+            })
+            .collect();
+        assert_eq!(levels, vec![2, 3]);
+
+        assert!(map_gui.grant_experience(99, 10).is_err());
+    }
+
+    #[test]
+    fn test_attack_unit_publishes_level_ups() {
+        let event_bus = EventBus::new();
+        let receiver = event_bus.subscribe("map_gui").unwrap();
+        let mut map_gui = MapGUI::new(event_bus);
+        map_gui.set_map(Map::new(5, 5));
+        let mut attacker = create_test_unit(1, 0, 0);
+        attacker.experience = 95;
+        let mut defender = create_test_unit(2, 1, 0);
+        defender.faction_id = 2;
+        defender.health = 5;
+        map_gui.add_units([attacker, defender]).unwrap();
+
+        let result = map_gui.attack_unit(1, 2).unwrap();
+        assert!(result.defender_destroyed);
+        assert_eq!(result.attacker_level_ups.len(), 1);
+        assert_eq!(map_gui.get_unit(1).unwrap().level, 2);
+        // 倒されたユニットは取り除かれる
+        assert!(map_gui.get_unit(2).is_none());
+
+        let events: Vec<GameEvent> = receiver.try_iter().map(|e| e.event).collect();
+        assert!(matches!(
+            events.as_slice(),
+            [
+                GameEvent::UnitAttacked {
+                    attacker: 1,
+                    defender: 2,
+                    ..
+                },
+                GameEvent::UnitLevelUp {
+                    unit_id: 1,
+                    new_level: 2
+                },
+            ]
+        ));
+        assert!(map_gui.attack_unit(1, 2).is_err());
+    }
+
+    #[test]
+    fn test_attack_unit_honours_attack_range() {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        map_gui.set_map(Map::new(6, 6));
+        let mut archer = create_test_unit(1, 0, 0);
+        archer.unit_type = UnitType::Ranged;
+        let mut target = create_test_unit(2, 2, 0);
+        target.faction_id = 2;
+        let mut far_target = create_test_unit(3, 3, 0);
+        far_target.faction_id = 2;
+        let mut infantry = create_test_unit(4, 0, 2);
+        infantry.faction_id = 1;
+        map_gui
+            .add_units([archer, target, far_target, infantry])
+            .unwrap();
+
+        // 射程2の遠隔ユニットは距離2のユニットを攻撃できる
+        let result = map_gui.attack_unit(1, 2).unwrap();
+        assert!(result.damage_dealt > 0);
+        // 射程外は状態を変えずにエラー
+        let health = map_gui.get_unit(3).unwrap().health;
+        assert!(map_gui.attack_unit(1, 3).is_err());
+        assert_eq!(map_gui.get_unit(3).unwrap().health, health);
+        // 射程1の歩兵は距離2のユニットを攻撃できない
+        assert!(map_gui.attack_unit(4, 2).is_err());
+    }
+
+    #[test]
+    fn test_add_units_publishes_single_update() {
+        let event_bus = EventBus::new();
+        let receiver = event_bus
+            .subscribe(crate::events::MAP_UPDATED_TOPIC)
+            .unwrap();
+        let mut map_gui = MapGUI::new(event_bus);
+
+        map_gui
+            .add_units((1..=50).map(|id| create_test_unit(id, id as i32, 0)))
+            .unwrap();
+        assert_eq!(map_gui.units.len(), 50);
+        assert_eq!(receiver.try_iter().count(), 1);
+
+        // 変更のないバッチはイベントを発行しない
+        map_gui.batch(|_| {});
+        assert_eq!(receiver.try_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_batched_scroll_publishes_once() {
+        let event_bus = EventBus::new();
+        let receiver = event_bus
+            .subscribe(crate::events::MAP_UPDATED_TOPIC)
+            .unwrap();
+        let mut map_gui = MapGUI::new(event_bus);
+        map_gui.set_map(Map::new(100, 100));
+        receiver.try_iter().count();
+
+        map_gui.batch(|gui| {
+            for _ in 0..10 {
+                gui.scroll(32, 0);
+            }
+            // ネストしたバッチも外側にまとめられる
+            gui.batch(|gui| gui.scroll(0, 32));
+        });
+        assert_eq!(map_gui.get_view_options().scroll_x, 320);
+        assert_eq!(map_gui.get_view_options().scroll_y, 32);
+        assert_eq!(receiver.try_iter().count(), 1);
+
+        // 範囲端でのスクロールは位置が変わらないためイベントを発行しない
+        map_gui.scroll(-1000, -1000);
+        receiver.try_iter().count();
+        map_gui.scroll(-32, 0);
+        assert_eq!(receiver.try_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_dirty_flag() {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        assert!(map_gui.take_dirty());
+        assert!(!map_gui.take_dirty());
+
+        map_gui.set_map(Map::new(100, 100));
+        assert!(map_gui.is_dirty());
+        assert!(map_gui.take_dirty());
+        assert!(!map_gui.is_dirty());
+
+        map_gui.scroll(32, 0);
+        assert!(map_gui.take_dirty());
+        // 範囲端で位置が変わらないスクロールでは立たない
+        map_gui.scroll(-1000, 0);
+        map_gui.take_dirty();
+        map_gui.scroll(-32, 0);
+        assert!(!map_gui.take_dirty());
+
+        map_gui.zoom(1.5);
+        assert!(map_gui.take_dirty());
+        map_gui.batch(|gui| gui.select_position(MapPosition::new(1, 1)).unwrap());
+        assert!(map_gui.take_dirty());
+
+        // ユニットの状態を直接変える操作でも立つ
+        map_gui.add_unit(create_test_unit(1, 0, 0)).unwrap();
+        map_gui.take_dirty();
+        map_gui.grant_experience(1, 10).unwrap();
+        assert!(map_gui.take_dirty());
+        map_gui
+            .units_mut()
+            .for_each(|unit| unit.reset_for_new_turn());
+        assert!(map_gui.take_dirty());
+    }
+
+    #[test]
+    fn test_placement_validation() {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus);
+        map_gui.set_map(create_test_map());
+
+        // マップ範囲外への配置は拒否
+        assert!(map_gui.add_unit(create_test_unit(1, 10, 0)).is_err());
+        assert!(map_gui.add_unit(create_test_unit(1, -1, 3)).is_err());
+
+        // 同じ位置への重複配置は拒否
+        map_gui.add_unit(create_test_unit(1, 3, 4)).unwrap();
+        assert!(map_gui.add_unit(create_test_unit(2, 3, 4)).is_err());
+        // 同じIDの重複も拒否
+        assert!(map_gui.add_unit(create_test_unit(1, 5, 5)).is_err());
+        assert_eq!(map_gui.units.len(), 1);
+
+        // スタックを許可すると配置できる（先に配置されたユニットが返る）
+        map_gui.set_allow_stacking(true);
+        map_gui.add_unit(create_test_unit(2, 3, 4)).unwrap();
+        assert_eq!(
+            map_gui
+                .get_unit_at_position(&MapPosition::new(3, 4))
+                .unwrap()
+                .id,
+            1
+        );
+    }
+
+    #[test]
+    fn test_select_cycles_stacked_units() -> Result<()> {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        map_gui.set_map(create_test_map());
+        map_gui.set_allow_stacking(true);
+        for id in [3, 1, 2] {
+            map_gui.add_unit(create_test_unit(id, 2, 0))?;
+        }
+        map_gui.add_unit(create_test_unit(4, 0, 0))?;
+        let stack = MapPosition::new(2, 0);
+        let ids: Vec<u32> = map_gui
+            .get_units_at_position(&stack)
+            .iter()
+            .map(|unit| unit.id)
+            .collect();
+        assert_eq!(ids, vec![3, 1, 2]);
+        assert!(map_gui
+            .get_units_at_position(&MapPosition::new(5, 5))
+            .is_empty());
+
+        // 続けて選択すると配置順に巡回する
+        let mut selected = Vec::new();
+        for _ in 0..4 {
+            map_gui.select_position(stack)?;
+            selected.push(map_gui.get_selected_unit_id().unwrap());
+        }
+        assert_eq!(selected, vec![3, 1, 2, 3]);
+
+        // 別のセルを選択して戻ると最後に選択したユニットから始める
+        map_gui.select_position(MapPosition::new(0, 0))?;
+        assert_eq!(map_gui.get_selected_unit_id(), Some(4));
+        let (x, y) = map_gui.map_to_screen_position(2, 0);
+        assert_eq!(map_gui.handle_click(x, y)?, ClickOutcome::SelectedUnit(3));
+        assert_eq!(map_gui.handle_click(x, y)?, ClickOutcome::SelectedUnit(1));
+
+        // 選択中はそのユニットを枠付きで、選択を外すとスタックの数を添えて表示する
+        let row = |map_gui: &MapGUI| map_gui.render_ascii().lines().nth(4).unwrap().to_string();
+        assert_eq!(&row(&map_gui)[9..12], "[1]");
+        map_gui.clear_selection();
+        assert_eq!(&row(&map_gui)[3..12], " 1  T 1+2");
+        Ok(())
+    }
+
+    #[test]
+    fn test_position_index_follows_moves_and_removal() {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus);
+        map_gui.set_map(create_test_map());
+        map_gui.add_unit(create_test_unit(1, 3, 4)).unwrap();
+        map_gui.add_unit(create_test_unit(2, 6, 6)).unwrap();
+
+        // 移動後は新しい位置で見つかり、元の位置は空く
+        assert!(map_gui.update_unit(create_test_unit(1, 5, 5)));
+        assert!(map_gui
+            .get_unit_at_position(&MapPosition::new(3, 4))
+            .is_none());
+        assert_eq!(
+            map_gui
+                .get_unit_at_position(&MapPosition::new(5, 5))
+                .unwrap()
+                .id,
+            1
+        );
+
+        // 占有されたセルや範囲外への移動は拒否され、位置は変わらない
+        assert!(!map_gui.update_unit(create_test_unit(1, 6, 6)));
+        assert!(!map_gui.update_unit(create_test_unit(1, 20, 20)));
+        assert_eq!(
+            map_gui.get_unit(1).unwrap().position,
+            MapPosition::new(5, 5)
+        );
+
+        // 削除するとインデックスからも消える
+        assert!(map_gui.remove_unit(1));
+        assert!(map_gui
+            .get_unit_at_position(&MapPosition::new(5, 5))
+            .is_none());
+        map_gui.add_unit(create_test_unit(3, 5, 5)).unwrap();
+        assert_eq!(
+            map_gui
+                .get_unit_at_position(&MapPosition::new(5, 5))
+                .unwrap()
+                .id,
+            3
+        );
+    }
+
+    #[test]
+    fn test_visible_tile_bounds_within_map() {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus);
+        assert_eq!(map_gui.visible_tile_bounds(), (0, 0, 0, 0));
+        map_gui.set_map(Map::new(50, 40));
+
+        // 様々なズーム・スクロールの組み合わせで範囲がマップ内に収まり、
+        // 左上のタイルがビューポート左上を覆っていることを確認
+        for zoom_step in 0..8 {
+            map_gui.zoom(if zoom_step % 2 == 0 { 1.3 } else { 0.6 });
+            for scroll in [0, 17, 45, 300, 5000] {
+                map_gui.scroll(scroll, scroll / 2);
+                let (start_x, start_y, end_x, end_y) = map_gui.visible_tile_bounds();
+                assert!(0 <= start_x && start_x <= end_x && end_x <= 50);
+                assert!(0 <= start_y && start_y <= end_y && end_y <= 40);
+                // 1文字がまとめるブロック分だけ範囲が広がる
+                let block = map_gui.ascii_block_size() as i32;
+                let options = map_gui.get_view_options();
+                assert!(end_x - start_x <= options.viewport_width as i32 * block);
+                assert!(end_y - start_y <= options.viewport_height as i32 * block);
+
+                let tile_size = map_gui.scaled_tile_size();
+                let (screen_x, screen_y) = map_gui.map_to_screen_position(start_x, start_y);
+                assert!(screen_x <= 0 && screen_x + tile_size > 0);
+                assert!(screen_y <= 0 && screen_y + tile_size > 0);
+            }
+            map_gui.scroll(-10000, -10000);
+        }
+    }
+
+    #[test]
+    fn test_render_ascii_with_visibility() {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus);
+        let mut map = Map::new(10, 10);
+        for x in 0..10 {
+            for y in 0..10 {
+                map.set_cell(MapPosition::new(x, y), Cell::new(CellType::Plain));
+            }
+        }
+        map_gui.set_map(map);
+        map_gui.add_unit(create_test_unit(1, 1, 1)).unwrap();
+        let mut enemy = create_test_unit(2, 2, 1);
+        enemy.faction_id = 2;
+        map_gui.add_unit(enemy).unwrap();
+        let mut hidden_enemy = create_test_unit(3, 8, 8);
+        hidden_enemy.faction_id = 2;
+        map_gui.add_unit(hidden_enemy).unwrap();
+
+        // 描画結果から指定セルの3文字を取り出す
+        let cell_at = |output: &str, x: usize, y: usize| -> String {
+            let row = output.lines().nth(4 + y).unwrap();
+            row[3 + x * 3..6 + x * 3].to_string()
+        };
+
+        // 視界未設定時は従来どおり全表示
+        let output = map_gui.render_ascii();
+        assert!(!output.contains('?'));
+        assert_eq!(cell_at(&output, 8, 8), " 2 ");
+
+        // 視界内の敵は表示され、未踏のセルは'?'になる
+        map_gui.update_visibility(1, SightRule::Radius).unwrap();
+        let output = map_gui.render_ascii();
+        assert_eq!(cell_at(&output, 1, 1), " 1 ");
+        assert_eq!(cell_at(&output, 2, 1), " 2 ");
+        assert_eq!(cell_at(&output, 8, 8), " ? ");
+
+        // 移動すると元の位置は探索済みになり、視界外の敵は表示されない
+        map_gui.update_unit(create_test_unit(1, 7, 7));
+        map_gui.update_visibility(1, SightRule::Radius).unwrap();
+        let output = map_gui.render_ascii();
+        assert_eq!(cell_at(&output, 1, 1), "(.)");
+        assert_eq!(cell_at(&output, 2, 1), "(.)");
+        assert_eq!(cell_at(&output, 8, 8), " 2 ");
+        assert_eq!(
+            map_gui
+                .get_visibility()
+                .unwrap()
+                .get(&MapPosition::new(1, 1)),
+            Visibility::Explored
+        );
+    }
+
+    #[test]
+    fn test_highlight_kinds_are_independent() {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        map_gui.set_map(Map::new(6, 6));
+        map_gui.set_view_options(MapViewOptions {
+            use_color: false,
+            ..MapViewOptions::default()
+        });
+        let movement = vec![MapPosition::new(1, 1), MapPosition::new(2, 1)];
+        map_gui.highlight_positions(movement.clone());
+        map_gui.set_highlights(
+            HighlightKind::Attack,
+            vec![MapPosition::new(2, 1), MapPosition::new(3, 1)],
+        );
+        map_gui.set_highlights(HighlightKind::Danger, vec![MapPosition::new(4, 1)]);
+        map_gui.set_highlights(HighlightKind::Custom(7), vec![MapPosition::new(5, 1)]);
+
+        // 互換用のAPIは移動範囲を指す
+        assert_eq!(map_gui.get_highlights(HighlightKind::Movement), movement);
+        assert_eq!(map_gui.get_highlight_positions(), movement);
+        assert_eq!(map_gui.get_highlights(HighlightKind::Custom(1)), []);
+
+        // 種類ごとに枠の文字が異なり、重なったセルは攻撃範囲を優先する
+        assert_eq!(
+            map_gui.highlight_at(&MapPosition::new(2, 1)),
+            Some(HighlightKind::Attack)
+        );
+        let ascii = map_gui.render_ascii();
+        assert!(ascii.contains(" 1|   * *! !! !~ ~: :|"), "{}", ascii);
+
+        let mut options = map_gui.get_view_options().clone();
+        options.use_color = true;
+        map_gui.set_view_options(options);
+        let colored = map_gui.render_ascii();
+        assert!(colored.contains("\x1b[41m!"));
+        assert!(colored.contains("\x1b[45m~"));
+        assert!(colored.contains("\x1b[44m:"));
+
+        // 消すのは指定した種類だけ
+        map_gui.clear_highlights(HighlightKind::Attack);
+        assert!(map_gui.get_highlights(HighlightKind::Attack).is_empty());
+        assert_eq!(map_gui.get_highlight_positions().len(), 2);
+        assert_eq!(
+            map_gui.highlight_at(&MapPosition::new(2, 1)),
+            Some(HighlightKind::Movement)
+        );
+
+        // 選択解除では選択に伴う範囲だけが消える
+        map_gui.show_attack_range(99);
+        map_gui.clear_selection();
+        assert!(map_gui.get_highlight_positions().is_empty());
+        assert_eq!(map_gui.get_highlights(HighlightKind::Danger).len(), 1);
+        assert_eq!(map_gui.get_highlights(HighlightKind::Custom(7)).len(), 1);
+    }
+
+    #[test]
+    fn test_show_attack_range() {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        map_gui.set_map(Map::new(10, 10));
+        for (id, unit_type, x, y) in [
+            (1, UnitType::Infantry, 5, 5),
+            (2, UnitType::Ranged, 5, 5),
+            (3, UnitType::Siege, 0, 0),
+        ] {
+            let mut unit = create_test_unit(id, x, y);
+            unit.unit_type = unit_type;
+            map_gui.units.insert(id, unit);
+        }
+
+        assert!(map_gui.show_attack_range(1));
+        let mut range = map_gui.get_highlights(HighlightKind::Attack).to_vec();
+        range.sort_by_key(|pos| (pos.y, pos.x));
+        assert_eq!(
+            range,
+            vec![
+                MapPosition::new(5, 4),
+                MapPosition::new(4, 5),
+                MapPosition::new(6, 5),
+                MapPosition::new(5, 6),
+            ]
+        );
+
+        // 射程2はマンハッタン距離2以内（自分の位置を除く）
+        assert!(map_gui.show_attack_range(2));
+        let range = map_gui.get_highlights(HighlightKind::Attack);
+        assert_eq!(range.len(), 12);
+        assert!(range.contains(&MapPosition::new(7, 5)));
+        assert!(!range.contains(&MapPosition::new(7, 6)));
+
+        // 射程3でもマップの外は含まない
+        assert!(map_gui.show_attack_range(3));
+        assert_eq!(map_gui.get_highlights(HighlightKind::Attack).len(), 9);
+        assert!(!map_gui.show_attack_range(99));
+
+        // 六角形の格子では隣接6セルが射程1
+        map_gui.set_map(Map::new(10, 10).with_grid(GridKind::HexPointyTop));
+        assert!(map_gui.show_attack_range(1));
+        assert_eq!(map_gui.get_highlights(HighlightKind::Attack).len(), 6);
+    }
+
+    #[test]
+    fn test_render_ascii_color() {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus);
+        map_gui.set_map(create_test_map());
+        map_gui.add_unit(create_test_unit(1, 1, 1)).unwrap();
+        let mut enemy = create_test_unit(2, 3, 3);
+        enemy.faction_id = 3;
+        map_gui.add_unit(enemy).unwrap();
+        map_gui.select_position(MapPosition::new(1, 1)).unwrap();
+        map_gui.highlight_positions(vec![MapPosition::new(2, 2)]);
+
+        let plain = map_gui.render_ascii();
+        assert!(!plain.contains('\x1b'));
+
+        let mut options = map_gui.get_view_options().clone();
+        options.use_color = true;
+        map_gui.set_view_options(options);
+        let colored = map_gui.render_ascii();
+
+        // 選択セルは反転表示、勢力1のユニットは青
+        assert!(colored.contains("\x1b[7m[\x1b[34m1\x1b[22;39m]\x1b[0m"));
+        // 勢力3のユニットは赤
+        assert!(colored.contains(" \x1b[31m3\x1b[22;39m "));
+        // ハイライトは黄色の背景（(2, 2)は森）
+        assert!(colored.contains("\x1b[43m*\x1b[2;32mT\x1b[22;39m*\x1b[0m"));
+        // 地形は暗い色で表示される
+        assert!(colored.contains("\x1b[2;37m.\x1b[22;39m"));
+
+        // エスケープシーケンスを取り除くと色なしの出力と一致する
+        let stripped = strip_ansi(&colored);
+        assert_eq!(stripped, plain);
+
+        // 無効にすると色なしの出力に戻る
+        let mut options = map_gui.get_view_options().clone();
+        options.use_color = false;
+        map_gui.set_view_options(options);
+        assert_eq!(map_gui.render_ascii(), plain);
+    }
+
+    #[test]
+    fn test_render_ascii_structure_layers() {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        let mut map = Map::new(4, 4);
+        for x in 0..4 {
+            for y in 0..4 {
+                map.set_cell(MapPosition::new(x, y), Cell::new(CellType::Forest));
+            }
+        }
+        let forest_city = Cell::new(CellType::Forest).with_structure(Structure::City { level: 1 });
+        map.set_cell(MapPosition::new(0, 0), forest_city.clone());
+        map.set_cell(MapPosition::new(1, 0), forest_city);
+        map.set_cell(
+            MapPosition::new(2, 0),
+            Cell::new(CellType::Road).with_structure(Structure::Ruins),
+        );
+        map_gui.set_map(map);
+        map_gui.add_unit(create_test_unit(1, 1, 0)).unwrap();
+
+        // 地形の上に構造物、構造物の上にユニットを表示する
+        let output = map_gui.render_ascii();
+        let row = output.lines().nth(4).unwrap();
+        assert_eq!(&row[3..15], " C  1  x  T ");
+
+        // まとめて表示するときも構造物は地形より優先される
+        let mut options = map_gui.get_view_options().clone();
+        options.ascii_lod = AsciiLod::Fixed(2);
+        map_gui.set_view_options(options);
+        map_gui.remove_unit(1);
+        let output = map_gui.render_ascii();
+        let row = output.lines().nth(4).unwrap();
+        assert_eq!(&row[3..9], " C  x ");
+    }
+
+    #[test]
+    fn test_render_ascii_ownership() {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        // 左列が勢力1、右2列が勢力2、(1, 1)のみ所有者なし
+        let mut map = Map::new(3, 2);
+        for x in 0..3 {
+            for y in 0..2 {
+                let cell = match (x, y) {
+                    (1, 1) => Cell::new(CellType::Plain),
+                    (0, _) => Cell::with_faction(CellType::Plain, 1),
+                    _ => Cell::with_faction(CellType::Plain, 2),
+                };
+                map.set_cell(MapPosition::new(x, y), cell);
+            }
+        }
+        map_gui.set_map(map);
+        let plain = map_gui.render_ascii();
+        assert!(!plain.contains("所有勢力"));
+
+        // 色なしでは所有勢力を別の表で表示する
+        let mut options = map_gui.get_view_options().clone();
+        options.show_ownership = true;
+        map_gui.set_view_options(options);
+        let output = map_gui.render_ascii();
+        let ownership: Vec<&str> = output
+            .split("所有勢力:\n")
+            .nth(1)
+            .unwrap()
+            .lines()
+            .collect();
+        assert_eq!(ownership[1], " 0| 1  2  2 |");
+        assert_eq!(ownership[2], " 1| 1  .  2 |");
+
+        // カラー表示では背景色で表示し、境界のセルは明るい色にする
+        let mut options = map_gui.get_view_options().clone();
+        options.use_color = true;
+        map_gui.set_view_options(options);
+        let colored = map_gui.render_ascii();
+        assert!(!colored.contains("所有勢力"));
+        let row: Vec<&str> = colored.lines().collect();
+        assert!(row[4].starts_with(" 0|\x1b[104m \x1b[2;37m.\x1b[22;39m \x1b[0m\x1b[102m"));
+        assert!(row[4].contains("\x1b[42m \x1b[2;37m.\x1b[22;39m \x1b[0m|"));
+        assert!(row[5].contains(" \x1b[2;37m.\x1b[22;39m \x1b[102m"));
+        assert_eq!(strip_ansi(&colored), plain);
+    }
+
+    #[test]
+    fn test_render_ascii_lod() {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        let mut map = Map::new(40, 40);
+        for x in 0..40 {
+            for y in 0..40 {
+                map.set_cell(MapPosition::new(x, y), Cell::new(CellType::Plain));
+            }
+        }
+        // 左上のブロックは森が3、平地が1
+        for (x, y) in [(0, 0), (1, 0), (0, 1)] {
+            map.set_cell(MapPosition::new(x, y), Cell::new(CellType::Forest));
+        }
+        // (2, 0)のブロックは水域と山が同数（定義順で先の山）
+        for (x, y, cell_type) in [
+            (2, 0, CellType::Water),
+            (3, 0, CellType::Water),
+            (2, 1, CellType::Mountain),
+            (3, 1, CellType::Mountain),
+        ] {
+            map.set_cell(MapPosition::new(x, y), Cell::new(cell_type));
+        }
+        map_gui.set_map(map);
+        map_gui.add_unit(create_test_unit(1, 4, 0)).unwrap();
+        map_gui.add_unit(create_test_unit(2, 5, 1)).unwrap();
+        map_gui.set_view_options(MapViewOptions {
+            viewport_width: 40,
+            viewport_height: 40,
+            ascii_lod: AsciiLod::Fixed(2),
+            ..MapViewOptions::default()
+        });
+
+        let output = map_gui.render_ascii();
+        let lines: Vec<&str> = output.lines().collect();
+        // 情報2行・ヘッダー・境界線2本と20行
+        assert_eq!(lines.len(), 2 + 1 + 20 + 2);
+        assert_eq!(lines[2].trim(), "0 2 4 6 8 0 2 4 6 8 0 2 4 6 8 0 2 4 6 8");
+        let rows = &lines[4..24];
+        assert!(rows.iter().all(|row| row.len() == 3 + 20 * 3 + 1));
+        assert_eq!(&rows[0][3..12], " T  ^  2 ");
+        assert_eq!(&rows[1][3..6], " . ");
+
+        // Autoではズーム率から詳細度を決める
+        let mut options = map_gui.get_view_options().clone();
+        options.ascii_lod = AsciiLod::Auto;
+        map_gui.set_view_options(options);
+        assert_eq!(map_gui.ascii_block_size(), 1);
+        map_gui.zoom(0.25);
+        assert_eq!(map_gui.ascii_block_size(), 4);
+    }
+
+    #[test]
+    fn test_set_view_options_clamps_zoom() {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        for (zoom, expected) in [
+            (0.01, 0.25),
+            (10.0, 2.0),
+            (1.5, 1.5),
+            (f32::NAN, 1.0),
+            (f32::INFINITY, 1.0),
+        ] {
+            map_gui.set_view_options(MapViewOptions {
+                zoom,
+                ..MapViewOptions::default()
+            });
+            assert_eq!(map_gui.get_view_options().zoom, expected);
+        }
+        assert!(map_gui.scaled_tile_size() > 0);
+    }
+
+    #[test]
+    fn test_auto_lod_widens_visible_tiles_when_zoomed_out() {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        map_gui.set_map(Map::new(40, 40));
+        map_gui.set_view_options(MapViewOptions {
+            viewport_width: 10,
+            viewport_height: 10,
+            ascii_lod: AsciiLod::Auto,
+            ..MapViewOptions::default()
+        });
+        assert_eq!(map_gui.visible_tile_bounds(), (0, 0, 10, 10));
+        let full = map_gui.render_ascii();
+        assert!(full.contains("表示範囲: 10×10 タイル"));
+
+        // ズーム0.5では1文字が2×2タイルをまとめるので、同じ文字数で4倍のタイルを表示する
+        map_gui.zoom(0.5);
+        assert_eq!(map_gui.ascii_block_size(), 2);
+        assert_eq!(map_gui.visible_tile_bounds(), (0, 0, 20, 20));
+        let zoomed_out = map_gui.render_ascii();
+        assert!(zoomed_out.contains("表示範囲: 20×20 タイル"));
+        let row_len = |output: &str| output.lines().nth(4).map(str::len).unwrap_or(0);
+        assert_eq!(row_len(&full), row_len(&zoomed_out));
+    }
+
+    /// ANSIエスケープシーケンス（ESC [ ... m）を取り除く
+    fn strip_ansi(text: &str) -> String {
+        let mut result = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                for c in chars.by_ref() {
+                    if c == 'm' {
+                        break;
+                    }
+                }
+            } else {
+                result.push(c);
+            }
+        }
+        result
+    }
 }
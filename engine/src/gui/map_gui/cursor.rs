@@ -0,0 +1,242 @@
+//! MapGUIの仮想カーソルとユニットの順送り選択
+//!
+//! ゲームパッドのようにポインタを持たない入力のため、選択とは別にタイル単位で
+//! 動くカーソルを持つ。カーソルの位置で決定するとクリックと同じ処理を行う。
+use super::{ClickOutcome, MapGUI};
+use anyhow::Result;
+use model::MapPosition;
+
+impl MapGUI {
+    /// 仮想カーソルの位置（未設定ならNone）
+    pub fn get_cursor(&self) -> Option<MapPosition> {
+        self.cursor
+    }
+
+    /// 仮想カーソルを指定位置に置く（マップの範囲外ならfalse）
+    ///
+    /// カーソルがビューポートの外なら表示範囲に入るようスクロールする。
+    pub fn set_cursor(&mut self, position: MapPosition) -> bool {
+        if !self
+            .map
+            .as_ref()
+            .is_some_and(|map| map.is_valid_position(&position))
+        {
+            return false;
+        }
+        self.batch(|gui| {
+            gui.cursor = Some(position);
+            gui.scroll_into_view(position);
+            gui.publish_map_updated();
+        });
+        true
+    }
+
+    /// 仮想カーソルをタイル単位で動かす（マップの端で止まる）
+    ///
+    /// カーソルが未設定なら選択位置から、選択もなければ表示範囲の左上から動かす。
+    /// マップ未設定時はfalse。
+    pub fn move_cursor(&mut self, dx: i32, dy: i32) -> bool {
+        let Some(map) = &self.map else {
+            return false;
+        };
+        let (max_x, max_y) = (map.width as i32 - 1, map.height as i32 - 1);
+        let (start_x, start_y, _, _) = self.visible_tile_bounds();
+        let from = self
+            .cursor
+            .or(self.selected_position)
+            .unwrap_or(MapPosition::new(start_x, start_y));
+        let to = MapPosition::new(
+            (from.x + dx).min(max_x).max(0),
+            (from.y + dy).min(max_y).max(0),
+        );
+        self.set_cursor(to)
+    }
+
+    /// 仮想カーソルを消す
+    pub fn clear_cursor(&mut self) {
+        if self.cursor.take().is_some() {
+            self.publish_map_updated();
+        }
+    }
+
+    /// 仮想カーソルの位置でクリックと同じ処理を行う（カーソル未設定ならOutOfBounds）
+    pub fn select_at_cursor(&mut self) -> Result<ClickOutcome> {
+        match self.cursor {
+            Some(position) => self.click_position(position),
+            None => Ok(ClickOutcome::OutOfBounds),
+        }
+    }
+
+    /// 勢力のユニットをID順に次へ選択する（最後のユニットの次は先頭に戻る）
+    ///
+    /// 選択中のユニットが勢力のものでなければ最小IDのユニットを選ぶ。選んだユニットの
+    /// 位置にカーソルを移す。勢力のユニットがいなければNone。手番制限中に他の勢力を
+    /// 指定した場合はエラー。
+    pub fn select_next_unit(&mut self, faction_id: u32) -> Result<Option<u32>> {
+        self.cycle_faction_units(faction_id, false)
+    }
+
+    /// 勢力のユニットをID順に前へ選択する（先頭のユニットの前は最後に戻る）
+    pub fn select_previous_unit(&mut self, faction_id: u32) -> Result<Option<u32>> {
+        self.cycle_faction_units(faction_id, true)
+    }
+
+    /// 勢力のユニットをID順に巡回して選択する
+    fn cycle_faction_units(&mut self, faction_id: u32, reverse: bool) -> Result<Option<u32>> {
+        if let Some(active_faction) = self.active_faction.filter(|id| *id != faction_id) {
+            return Err(anyhow::anyhow!(
+                "勢力{}の手番では勢力{}のユニットを選択できません",
+                active_faction,
+                faction_id
+            ));
+        }
+        let mut unit_ids: Vec<u32> = self
+            .units
+            .values()
+            .filter(|unit| unit.faction_id == faction_id)
+            .map(|unit| unit.id)
+            .collect();
+        unit_ids.sort_unstable();
+        if reverse {
+            unit_ids.reverse();
+        }
+        let next = match self
+            .selected_unit_id
+            .and_then(|id| unit_ids.iter().position(|candidate| *candidate == id))
+        {
+            Some(index) => unit_ids.get((index + 1) % unit_ids.len()),
+            None => unit_ids.first(),
+        };
+        let Some(&unit_id) = next else {
+            return Ok(None);
+        };
+
+        let position = self.units[&unit_id].position;
+        self.batch(|gui| {
+            gui.selected_position = Some(position);
+            gui.selected_unit_id = Some(unit_id);
+            // セルを選び直したときに同じユニットから巡回するようスタック内の順番を記録
+            let stack = gui.position_index.get(&position);
+            if let Some(index) = stack
+                .filter(|ids| ids.len() > 1)
+                .and_then(|ids| ids.iter().position(|id| *id == unit_id))
+            {
+                gui.stack_cursor.insert(position, index);
+            }
+            gui.set_cursor(position);
+            gui.publish_unit_selected(unit_id);
+            gui.publish_position_selected(position);
+            gui.publish_map_updated();
+        });
+        Ok(Some(unit_id))
+    }
+
+    /// 位置がビューポートに入るようタイル単位でスクロールする
+    fn scroll_into_view(&mut self, position: MapPosition) {
+        let (start_x, start_y, end_x, end_y) = self.visible_tile_bounds();
+        let offset = |pos: i32, start: i32, end: i32| {
+            if pos < start {
+                pos - start
+            } else if pos >= end {
+                pos - end + 1
+            } else {
+                0
+            }
+        };
+        let tile_size = self.scaled_tile_size();
+        self.scroll(
+            offset(position.x, start_x, end_x) * tile_size,
+            offset(position.y, start_y, end_y) * tile_size,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::events::{EventBus, EventKind};
+    use crate::gui::map_gui::{ClickOutcome, MapGUI, MapViewOptions};
+    use anyhow::Result;
+    use model::{Map, MapPosition, Unit, UnitType};
+
+    fn create_gui(event_bus: EventBus) -> Result<MapGUI> {
+        let mut map_gui = MapGUI::new(event_bus);
+        map_gui.set_map(Map::new(20, 20));
+        map_gui.set_view_options(MapViewOptions {
+            viewport_width: 5,
+            viewport_height: 5,
+            use_color: false,
+            ..MapViewOptions::default()
+        });
+        for (id, faction_id, x, y) in [(3, 1, 1, 1), (1, 1, 12, 3), (2, 2, 2, 2), (7, 1, 4, 4)] {
+            map_gui.add_unit(Unit::new(
+                id,
+                format!("ユニット{}", id),
+                UnitType::Infantry,
+                faction_id,
+                MapPosition::new(x, y),
+            ))?;
+        }
+        Ok(map_gui)
+    }
+
+    #[test]
+    fn test_cursor_moves_within_map_and_scrolls() -> Result<()> {
+        let mut map_gui = create_gui(EventBus::new())?;
+        assert_eq!(map_gui.get_cursor(), None);
+
+        // 未設定なら表示範囲の左上から動き、マップの端で止まる
+        assert!(map_gui.move_cursor(-1, 1));
+        assert_eq!(map_gui.get_cursor(), Some(MapPosition::new(0, 1)));
+        assert!(!map_gui.set_cursor(MapPosition::new(20, 0)));
+
+        // ビューポートの外へ出るとスクロールする
+        assert!(map_gui.move_cursor(6, 0));
+        assert_eq!(map_gui.get_cursor(), Some(MapPosition::new(6, 1)));
+        assert_eq!(map_gui.visible_tile_bounds(), (2, 0, 7, 5));
+
+        // カーソルは選択とは別に表示される
+        assert!(map_gui.set_cursor(MapPosition::new(3, 1)));
+        map_gui.select_position(MapPosition::new(2, 2))?;
+        let ascii = map_gui.render_ascii();
+        assert!(ascii.contains("< >"), "{}", ascii);
+        assert!(ascii.contains("[2]"), "{}", ascii);
+        assert_eq!(map_gui.get_selected_unit_id(), Some(2));
+
+        // 決定はクリックと同じ処理になる
+        assert!(map_gui.set_cursor(MapPosition::new(4, 4)));
+        assert_eq!(map_gui.select_at_cursor()?, ClickOutcome::SelectedUnit(7));
+        map_gui.clear_cursor();
+        assert_eq!(map_gui.select_at_cursor()?, ClickOutcome::OutOfBounds);
+        Ok(())
+    }
+
+    #[test]
+    fn test_select_next_unit_wraps_by_id() -> Result<()> {
+        let event_bus = EventBus::new();
+        let mut map_gui = create_gui(event_bus.clone())?;
+        let selections = event_bus.subscribe_to("map_gui", EventKind::UnitSelected)?;
+
+        assert_eq!(map_gui.select_next_unit(1)?, Some(1));
+        assert_eq!(map_gui.get_cursor(), Some(MapPosition::new(12, 3)));
+        assert_eq!(map_gui.visible_tile_bounds(), (8, 0, 13, 5));
+        assert_eq!(map_gui.select_next_unit(1)?, Some(3));
+        assert_eq!(map_gui.select_next_unit(1)?, Some(7));
+        assert_eq!(map_gui.select_next_unit(1)?, Some(1));
+        assert_eq!(map_gui.select_previous_unit(1)?, Some(7));
+        assert_eq!(
+            map_gui.get_selected_position(),
+            Some(MapPosition::new(4, 4))
+        );
+        assert_eq!(selections.try_iter().count(), 5);
+
+        // 他の勢力のユニットを選択中なら先頭から
+        map_gui.select_position(MapPosition::new(2, 2))?;
+        assert_eq!(map_gui.select_previous_unit(1)?, Some(7));
+        assert_eq!(map_gui.select_next_unit(3)?, None);
+
+        map_gui.set_active_faction(Some(2));
+        assert!(map_gui.select_next_unit(1).is_err());
+        assert_eq!(map_gui.select_next_unit(2)?, Some(2));
+        Ok(())
+    }
+}
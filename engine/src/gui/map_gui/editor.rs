@@ -0,0 +1,220 @@
+//! MapGUIのマップ編集モード
+//!
+//! 地形の塗り・矩形塗り・塗りつぶしを行い、編集ごとの逆パッチを
+//! 取り消し/やり直し用のスタックに積む。
+use super::MapGUI;
+use model::{CellPatch, CellType, Map, MapPosition};
+
+/// マップ編集の状態
+#[derive(Debug)]
+pub(super) struct EditorState {
+    enabled: bool,              // 編集モードが有効か（クリックで塗る）
+    terrain: CellType,          // クリックで塗る地形
+    undo_stack: Vec<CellPatch>, // 取り消し用の逆パッチ
+    redo_stack: Vec<CellPatch>, // やり直し用の逆パッチ
+}
+
+impl Default for EditorState {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            terrain: CellType::Plain,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+}
+
+impl EditorState {
+    /// 取り消し/やり直しの履歴を消す（別のマップに差し替えたときに使う）
+    pub(super) fn clear_history(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+impl MapGUI {
+    /// 編集モードを設定（有効な間はクリックで選択中の地形を塗る）
+    pub fn set_editor_mode(&mut self, enabled: bool) {
+        self.editor.enabled = enabled;
+    }
+
+    /// 編集モードが有効か
+    pub fn is_editor_mode(&self) -> bool {
+        self.editor.enabled
+    }
+
+    /// クリックで塗る地形を設定
+    pub fn set_editor_terrain(&mut self, terrain: CellType) {
+        self.editor.terrain = terrain;
+    }
+
+    /// クリックで塗る地形
+    pub fn get_editor_terrain(&self) -> CellType {
+        self.editor.terrain
+    }
+
+    /// セルの地形を変更する（変更があればtrue）
+    pub fn paint_cell(&mut self, position: MapPosition, terrain: CellType) -> bool {
+        self.edit_map(|map| map.paint_cell(position, terrain))
+    }
+
+    /// 2点を対角とする矩形の地形を変更する（変更があればtrue）
+    pub fn fill_rect(&mut self, from: MapPosition, to: MapPosition, terrain: CellType) -> bool {
+        self.edit_map(|map| map.fill_rect(from, to, terrain))
+    }
+
+    /// 連続する同じ地形の領域を塗りつぶす（変更があればtrue）
+    pub fn flood_fill(&mut self, position: MapPosition, terrain: CellType) -> bool {
+        self.edit_map(|map| map.flood_fill(position, terrain))
+    }
+
+    /// 直前の編集を取り消す（取り消す編集がなければfalse）
+    pub fn undo(&mut self) -> bool {
+        let Some(patch) = self.editor.undo_stack.pop() else {
+            return false;
+        };
+        let Some(map) = &mut self.map else {
+            return false;
+        };
+        let redo = map.apply_patch(&patch);
+        self.editor.redo_stack.push(redo);
+        self.publish_map_updated();
+        true
+    }
+
+    /// 取り消した編集をやり直す（やり直す編集がなければfalse）
+    pub fn redo(&mut self) -> bool {
+        let Some(patch) = self.editor.redo_stack.pop() else {
+            return false;
+        };
+        let Some(map) = &mut self.map else {
+            return false;
+        };
+        let undo = map.apply_patch(&patch);
+        self.editor.undo_stack.push(undo);
+        self.publish_map_updated();
+        true
+    }
+
+    /// 取り消せる編集があるか
+    pub fn can_undo(&self) -> bool {
+        !self.editor.undo_stack.is_empty()
+    }
+
+    /// やり直せる編集があるか
+    pub fn can_redo(&self) -> bool {
+        !self.editor.redo_stack.is_empty()
+    }
+
+    /// 編集モードならクリック位置を選択中の地形で塗る（編集モードでなければfalse）
+    pub(super) fn editor_mode_click(&mut self, position: MapPosition) -> bool {
+        if !self.editor.enabled {
+            return false;
+        }
+        self.paint_cell(position, self.editor.terrain);
+        true
+    }
+
+    /// 編集を適用し、逆パッチを取り消しスタックに積む
+    fn edit_map(&mut self, edit: impl FnOnce(&mut Map) -> Option<CellPatch>) -> bool {
+        let Some(patch) = self.map.as_mut().and_then(edit) else {
+            return false;
+        };
+        self.editor.undo_stack.push(patch);
+        self.editor.redo_stack.clear();
+        self.publish_map_updated();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::events::{EventBus, MAP_UPDATED_TOPIC};
+    use crate::gui::map_gui::{ClickOutcome, MapGUI};
+    use anyhow::Result;
+    use model::{Cell, CellType, Map, MapPosition};
+
+    fn terrain(map_gui: &MapGUI, x: i32, y: i32) -> Option<CellType> {
+        map_gui
+            .get_map()
+            .and_then(|map| map.get_cell(&MapPosition::new(x, y)))
+            .map(|cell| cell.cell_type)
+    }
+
+    #[test]
+    fn test_undo_redo() -> Result<()> {
+        let event_bus = EventBus::new();
+        let mut map_gui = MapGUI::new(event_bus.clone());
+        let mut map = Map::new(6, 6);
+        map.set_cell(MapPosition::new(1, 1), Cell::new(CellType::Forest));
+        map_gui.set_map(map);
+        let original = map_gui.get_map().cloned();
+        let updates = event_bus.subscribe(MAP_UPDATED_TOPIC)?;
+
+        assert!(map_gui.fill_rect(
+            MapPosition::new(0, 0),
+            MapPosition::new(2, 2),
+            CellType::Road
+        ));
+        assert_eq!(updates.try_iter().count(), 1);
+        assert!(map_gui.paint_cell(MapPosition::new(5, 5), CellType::Water));
+        assert_eq!(updates.try_iter().count(), 1);
+        assert!(!map_gui.paint_cell(MapPosition::new(9, 9), CellType::Water));
+        assert_eq!(updates.try_iter().count(), 0);
+
+        assert!(map_gui.undo());
+        assert_eq!(terrain(&map_gui, 5, 5), None);
+        assert_eq!(terrain(&map_gui, 1, 1), Some(CellType::Road));
+        assert!(map_gui.undo());
+        assert_eq!(map_gui.get_map().cloned(), original);
+        assert!(!map_gui.undo());
+
+        assert!(map_gui.redo());
+        assert_eq!(terrain(&map_gui, 1, 1), Some(CellType::Road));
+        assert!(map_gui.can_redo());
+
+        // 新しい編集でやり直し履歴は消える
+        assert!(map_gui.flood_fill(MapPosition::new(4, 4), CellType::Forest));
+        assert!(!map_gui.can_redo());
+        assert_eq!(terrain(&map_gui, 5, 5), Some(CellType::Forest));
+        assert_eq!(terrain(&map_gui, 0, 0), Some(CellType::Road));
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_map_clears_history() {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        map_gui.set_map(Map::new(6, 6));
+        assert!(map_gui.paint_cell(MapPosition::new(1, 1), CellType::Water));
+        assert!(map_gui.paint_cell(MapPosition::new(2, 2), CellType::Water));
+        assert!(map_gui.undo());
+        assert!(map_gui.can_undo() && map_gui.can_redo());
+
+        // 別のマップに差し替えると前のマップの逆パッチは適用しない
+        map_gui.set_map(Map::new(3, 3));
+        assert!(!map_gui.can_undo());
+        assert!(!map_gui.can_redo());
+        assert!(!map_gui.undo());
+        assert_eq!(terrain(&map_gui, 1, 1), None);
+    }
+
+    #[test]
+    fn test_click_paints_in_editor_mode() -> Result<()> {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        map_gui.set_map(Map::new(10, 10));
+        map_gui.set_editor_mode(true);
+        map_gui.set_editor_terrain(CellType::Mountain);
+
+        let outcome = map_gui.handle_click(40, 40)?;
+        assert_eq!(outcome, ClickOutcome::Painted(MapPosition::new(1, 1)));
+        assert_eq!(terrain(&map_gui, 1, 1), Some(CellType::Mountain));
+        assert!(map_gui.get_selected_position().is_none());
+
+        // 編集モードを切ると通常の選択に戻る
+        map_gui.set_editor_mode(false);
+        let outcome = map_gui.handle_click(40, 40)?;
+        assert_eq!(outcome, ClickOutcome::SelectedCell(MapPosition::new(1, 1)));
+        Ok(())
+    }
+}
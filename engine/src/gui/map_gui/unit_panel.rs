@@ -0,0 +1,365 @@
+//! MapGUIのASCII表示のユニット一覧
+//!
+//! 表示範囲内で見えているユニットを勢力・ID順に並べ、体力バー・移動力・状態を
+//! マップの右側に表示する。各行の記号はマップ上のユニットの表示と共通にする。
+//! ユニットを選択中は、射程内の他勢力のユニットを攻撃した場合の見積もりも表示する。
+use super::MapGUI;
+use model::{Unit, UnitStatus};
+
+/// 体力バーの長さ（文字数）
+const HEALTH_BAR_WIDTH: u32 = 10;
+
+/// マップと一覧の間の空白
+const PANEL_GAP: &str = "  ";
+
+/// 一覧の順番に対応する記号（a-z、A-Zの後は'*'）
+fn panel_marker(index: usize) -> char {
+    const MARKERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    MARKERS.get(index).map_or('*', |&marker| marker as char)
+}
+
+/// 状態ごとの表示記号
+fn status_icon(status: UnitStatus) -> char {
+    match status {
+        UnitStatus::Idle => '-',
+        UnitStatus::Moving => '>',
+        UnitStatus::Attacking => 'x',
+        UnitStatus::Defending => '#',
+        UnitStatus::Exhausted => 'z',
+        UnitStatus::Wounded => '!',
+    }
+}
+
+/// 体力を10文字のバーで表す（端数は四捨五入、体力が残っていれば最低1文字）
+fn health_bar(unit: &Unit) -> String {
+    let filled = match (unit.health, unit.max_health) {
+        (0, _) | (_, 0) => 0,
+        (health, max) => ((health * HEALTH_BAR_WIDTH + max / 2) / max).clamp(1, HEALTH_BAR_WIDTH),
+    };
+    format!(
+        "[{}{}]",
+        "#".repeat(filled as usize),
+        "-".repeat((HEALTH_BAR_WIDTH - filled) as usize)
+    )
+}
+
+/// 端末上の表示幅（ANSIエスケープシーケンスを除き、ASCII以外の文字は2桁とみなす）
+fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c == 'm' {
+                    break;
+                }
+            }
+        } else if c.is_ascii() {
+            width += 1;
+        } else {
+            width += 2;
+        }
+    }
+    width
+}
+
+/// 表示幅がwidthになるよう右を空白で埋める
+fn pad_to_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+/// マップの各行の右に一覧の行を並べる（行数が足りない側は空行として扱う）
+pub(super) fn attach_side_panel(map: &str, panel: &[String]) -> String {
+    let map_lines: Vec<&str> = map.lines().collect();
+    let map_width = map_lines
+        .iter()
+        .map(|line| display_width(line))
+        .max()
+        .unwrap_or(0);
+    let mut output = String::new();
+    for index in 0..map_lines.len().max(panel.len()) {
+        let line = map_lines.get(index).copied().unwrap_or("");
+        match panel.get(index) {
+            Some(row) => {
+                output.push_str(&pad_to_width(line, map_width));
+                output.push_str(PANEL_GAP);
+                output.push_str(row);
+            }
+            None => output.push_str(line),
+        }
+        output.push('\n');
+    }
+    output
+}
+
+impl MapGUI {
+    /// 一覧に載せるユニット（表示範囲内で見えているもの、勢力・ID順）と記号
+    pub(super) fn ascii_panel_units(&self) -> Vec<(char, &Unit)> {
+        let (start_x, start_y, end_x, end_y) = self.visible_tile_bounds();
+        let mut units: Vec<&Unit> = self
+            .units
+            .values()
+            .filter(|unit| {
+                let pos = unit.position;
+                (start_x..end_x).contains(&pos.x)
+                    && (start_y..end_y).contains(&pos.y)
+                    && self.is_unit_shown(unit, self.ascii_visibility(&pos))
+            })
+            .collect();
+        units.sort_by_key(|unit| (unit.faction_id, unit.id));
+        units
+            .into_iter()
+            .enumerate()
+            .map(|(index, unit)| (panel_marker(index), unit))
+            .collect()
+    }
+
+    /// ユニット一覧の各行（名前の列幅は最も長い名前に合わせる）
+    pub(super) fn ascii_unit_panel(&self, units: &[(char, &Unit)]) -> Vec<String> {
+        let name_width = units
+            .iter()
+            .map(|(_, unit)| display_width(&unit.name))
+            .max()
+            .unwrap_or(0);
+        let mut rows = vec!["ユニット一覧".to_string()];
+        let mut faction_id = None;
+        for (marker, unit) in units {
+            if faction_id != Some(unit.faction_id) {
+                faction_id = Some(unit.faction_id);
+                rows.push(format!("勢力{}:", unit.faction_id));
+            }
+            rows.push(format!(
+                " {} {} {} {} {:>3}/{:<3} 移動{} {}",
+                marker,
+                super::unit_symbol(unit.unit_type),
+                pad_to_width(&unit.name, name_width),
+                health_bar(unit),
+                unit.health,
+                unit.max_health,
+                unit.movement_points,
+                status_icon(unit.status)
+            ));
+        }
+        if units.is_empty() {
+            rows.push(" （なし）".to_string());
+        }
+        rows.extend(self.ascii_forecast_rows(units));
+        rows
+    }
+
+    /// 選択中のユニットが射程内の他勢力のユニットを攻撃した場合の見積もりの行
+    fn ascii_forecast_rows(&self, units: &[(char, &Unit)]) -> Vec<String> {
+        let (Some(selected), Some(map)) = (self.get_selected_unit(), &self.map) else {
+            return Vec::new();
+        };
+        let Some((attacker_marker, _)) = units.iter().find(|(_, unit)| unit.id == selected.id)
+        else {
+            return Vec::new();
+        };
+        let range = selected.unit_type.attack_range();
+        let rows: Vec<String> = units
+            .iter()
+            .filter(|(_, unit)| {
+                unit.faction_id != selected.faction_id
+                    && map.distance(&selected.position, &unit.position) <= range
+            })
+            .filter_map(|(marker, unit)| {
+                let forecast = self.attack_forecast(selected.id, unit.id)?;
+                let mut row = format!(
+                    " {}→{} 与{} 被{}",
+                    attacker_marker, marker, forecast.damage_dealt, forecast.counter_damage
+                );
+                if forecast.defender_destroyed {
+                    row.push_str(" 撃破");
+                }
+                if forecast.attacker_destroyed {
+                    row.push_str(" 全滅");
+                }
+                Some(row)
+            })
+            .collect();
+        if rows.is_empty() {
+            return rows;
+        }
+        std::iter::once("攻撃予測:".to_string())
+            .chain(rows)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventBus;
+    use model::{resolve_attack, Cell, CellType, Map, MapPosition, UnitType};
+
+    /// 3体のユニットを置いた4x3のマップ
+    fn create_panel_fixture() -> MapGUI {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        map_gui.set_map(Map::new(4, 3));
+
+        let mut cavalry = Unit::new(
+            1,
+            "騎兵隊".to_string(),
+            UnitType::Cavalry,
+            2,
+            MapPosition::new(2, 1),
+        );
+        cavalry.health = 55;
+        cavalry.movement_points = 2;
+        cavalry.status = UnitStatus::Moving;
+        let infantry = Unit::new(
+            2,
+            "歩兵".to_string(),
+            UnitType::Infantry,
+            1,
+            MapPosition::new(0, 0),
+        );
+        let mut archer = Unit::new(
+            3,
+            "Archer".to_string(),
+            UnitType::Ranged,
+            1,
+            MapPosition::new(3, 2),
+        );
+        archer.take_damage(80);
+        for unit in [cavalry, infantry, archer] {
+            map_gui.add_unit(unit).unwrap();
+        }
+        map_gui
+    }
+
+    #[test]
+    fn test_render_unit_panel() {
+        let mut map_gui = create_panel_fixture();
+        let without_panel = map_gui.render_ascii();
+        let mut options = map_gui.get_view_options().clone();
+        options.show_unit_panel = true;
+        map_gui.set_view_options(options);
+
+        // 勢力・ID順に記号を振り、マップ上のユニットも同じ記号で表示する
+        let expected = concat!(
+            "スクロール位置: (0, 0) タイル\n",
+            "表示範囲: 4×3 タイル\n",
+            "    0 1 2 3       ユニット一覧\n",
+            "  +--------+      勢力1:\n",
+            " 0| a          |   a I 歩兵   [##########] 100/100 移動3 -\n",
+            " 1|       c    |   b R Archer [##--------]  20/100 移動2 !\n",
+            " 2|          b |  勢力2:\n",
+            "  +--------+       c K 騎兵隊 [######----]  55/100 移動2 >\n",
+        );
+        assert_eq!(map_gui.render_ascii(), expected);
+
+        // 一覧を閉じると元の表示に戻る
+        let mut options = map_gui.get_view_options().clone();
+        options.show_unit_panel = false;
+        map_gui.set_view_options(options);
+        assert_eq!(map_gui.render_ascii(), without_panel);
+    }
+
+    #[test]
+    fn test_unit_panel_shows_attack_forecast() {
+        let mut map_gui = create_panel_fixture();
+        let mut options = map_gui.get_view_options().clone();
+        options.show_unit_panel = true;
+        map_gui.set_view_options(options);
+        // 騎兵隊(c)の隣に歩兵(a)を移動して選択する
+        let mut infantry = map_gui.get_unit(2).unwrap().clone();
+        infantry.position = MapPosition::new(1, 1);
+        map_gui.update_unit(infantry);
+        map_gui.select_position(MapPosition::new(1, 1)).unwrap();
+
+        // 見積もりは実際の戦闘結果と一致する
+        let forecast = map_gui.attack_forecast(2, 1).unwrap();
+        let mut attacker = map_gui.get_unit(2).unwrap().clone();
+        let mut defender = map_gui.get_unit(1).unwrap().clone();
+        let result = resolve_attack(
+            &mut attacker,
+            &mut defender,
+            &Cell::new(CellType::Plain),
+            &Cell::new(CellType::Plain),
+        );
+        assert_eq!(forecast.damage_dealt, result.damage_dealt);
+        assert_eq!(forecast.counter_damage, result.counter_damage);
+        assert!(map_gui.attack_forecast(2, 99).is_none());
+
+        let output = map_gui.render_ascii();
+        // マップより下の行はマップの幅と間隔の分だけ空白を空ける
+        assert!(output.contains(&format!(
+            "攻撃予測:\n{} a→c 与{} 被{}\n",
+            " ".repeat(18),
+            result.damage_dealt,
+            result.counter_damage
+        )));
+        // 隣接していない弓兵(b)は対象にならない
+        assert!(!output.contains("a→b"));
+
+        // 選択を解除すると表示しない
+        map_gui.clear_selection();
+        assert!(!map_gui.render_ascii().contains("攻撃予測"));
+    }
+
+    #[test]
+    fn test_unit_panel_forecasts_ranged_attack_at_distance_two() {
+        let mut map_gui = create_panel_fixture();
+        let mut options = map_gui.get_view_options().clone();
+        options.show_unit_panel = true;
+        map_gui.set_view_options(options);
+
+        // 射程2の弓兵(b)は距離2の騎兵隊(c)を攻撃対象にする
+        map_gui.select_position(MapPosition::new(3, 2)).unwrap();
+        let forecast = map_gui.attack_forecast(3, 1).unwrap();
+        let output = map_gui.render_ascii();
+        assert!(output.contains(&format!(
+            "b→c 与{} 被{}",
+            forecast.damage_dealt, forecast.counter_damage
+        )));
+
+        // 射程1の歩兵(a)からは距離2の騎兵隊は対象外
+        map_gui.select_position(MapPosition::new(0, 0)).unwrap();
+        assert!(!map_gui.render_ascii().contains("攻撃予測"));
+    }
+
+    #[test]
+    fn test_unit_panel_lists_only_units_in_view() {
+        let mut map_gui = create_panel_fixture();
+        let mut options = map_gui.get_view_options().clone();
+        options.show_unit_panel = true;
+        options.viewport_width = 2;
+        map_gui.set_view_options(options);
+
+        let units = map_gui.ascii_panel_units();
+        let ids: Vec<(char, u32)> = units
+            .iter()
+            .map(|(marker, unit)| (*marker, unit.id))
+            .collect();
+        assert_eq!(ids, vec![('a', 2)]);
+        assert!(map_gui.render_ascii().contains(" a I 歩兵 [##########]"));
+    }
+
+    #[test]
+    fn test_health_bar_and_width() {
+        let mut unit = Unit::new(
+            1,
+            "歩兵".to_string(),
+            UnitType::Infantry,
+            1,
+            MapPosition::new(0, 0),
+        );
+        assert_eq!(health_bar(&unit), "[##########]");
+        unit.health = 44;
+        assert_eq!(health_bar(&unit), "[####------]");
+        unit.health = 1;
+        assert_eq!(health_bar(&unit), "[#---------]");
+        unit.health = 0;
+        assert_eq!(health_bar(&unit), "[----------]");
+
+        assert_eq!(display_width("歩兵A"), 5);
+        assert_eq!(display_width("\x1b[34m1\x1b[22;39m"), 1);
+        assert_eq!(pad_to_width("歩兵", 6), "歩兵  ");
+        assert_eq!(panel_marker(0), 'a');
+        assert_eq!(panel_marker(27), 'B');
+        assert_eq!(panel_marker(99), '*');
+    }
+}
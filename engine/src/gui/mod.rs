@@ -2,4 +2,4 @@
 
 pub mod map_gui;
 
-pub use self::map_gui::MapGUI;
+pub use self::map_gui::{ClickOutcome, MapGUI};
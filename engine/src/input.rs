@@ -0,0 +1,585 @@
+//! 入力イベントをマップ操作のアクションへ変換するモジュール
+//!
+//! ウィンドウシステムに依存しないキー・マウス入力の表現を定義し、
+//! MapGUIに対する高レベルなアクションへ変換する。キーの割り当ては
+//! KeyBindingsで変更でき、RON形式のファイルから読み込める。
+use crate::gui::map_gui::{ClickOutcome, MapGUI};
+use anyhow::{Context, Result};
+use log::warn;
+use model::CellType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// 入力キー
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Key {
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    W,
+    A,
+    S,
+    D,
+    G,
+    E,
+    Z,
+    Y,
+    Digit(u8), // 数字キー（0〜9）
+    Plus,
+    Minus,
+    Escape,
+}
+
+/// 修飾キーの組み合わせ
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+/// キーと修飾キーの組み合わせ
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct KeyCombo {
+    pub key: Key,
+    #[serde(default)]
+    pub modifiers: Modifiers,
+}
+
+impl KeyCombo {
+    /// 修飾キーなしの組み合わせ
+    pub fn new(key: Key) -> Self {
+        Self {
+            key,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    /// Ctrlを押しながらの組み合わせ
+    pub fn ctrl(key: Key) -> Self {
+        Self {
+            key,
+            modifiers: Modifiers {
+                ctrl: true,
+                ..Modifiers::default()
+            },
+        }
+    }
+}
+
+/// 入力イベント
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputEvent {
+    KeyPressed(Key),                // 修飾キーなしのキー入力
+    KeyPressedWith(Key, Modifiers), // 修飾キー付きのキー入力
+    MouseClicked { x: i32, y: i32 },
+}
+
+/// マップ操作アクション
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputAction {
+    ScrollTiles { dx: i32, dy: i32 },  // タイル単位のスクロール
+    ScrollPixels { dx: i32, dy: i32 }, // ピクセル単位のスクロール（スティック操作）
+    Zoom(f32),                         // ズーム倍率
+    Click { x: i32, y: i32 },          // スクリーン座標でのクリック
+    MoveCursor { dx: i32, dy: i32 },   // 仮想カーソルのタイル単位の移動
+    SelectAtCursor,                    // 仮想カーソルの位置でのクリック
+    CycleUnit { reverse: bool },       // 手番勢力のユニットをID順に選択
+    ClearSelection,
+    ToggleGrid,
+    ToggleEditor,            // マップ編集モードの切り替え
+    SelectTerrain(CellType), // 編集モードで塗る地形の選択
+    Undo,
+    Redo,
+}
+
+/// ズームイン/アウト時の倍率
+const ZOOM_STEP: f32 = 1.25;
+
+/// 数字キーに対応する編集用の地形（1:平地 2:森 3:山 4:水域 5:道路 6:都市 7:拠点）
+#[allow(deprecated)] // 都市・拠点は地形を保ったまま構造物として塗られる
+fn terrain_for_digit(digit: u8) -> Option<CellType> {
+    match digit {
+        1 => Some(CellType::Plain),
+        2 => Some(CellType::Forest),
+        3 => Some(CellType::Mountain),
+        4 => Some(CellType::Water),
+        5 => Some(CellType::Road),
+        6 => Some(CellType::City),
+        7 => Some(CellType::Base),
+        _ => None,
+    }
+}
+
+/// キーに割り当てられる操作（設定ファイルでは名前で指定する）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum KeyAction {
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+    ZoomIn,
+    ZoomOut,
+    Cancel, // 選択解除
+    ToggleGrid,
+    ToggleEditor,
+    Undo,
+    Redo,
+    SelectTerrain(u8), // 編集用の地形の選択（1〜7、数字キーの並びと同じ）
+}
+
+impl KeyAction {
+    /// 設定ファイルで使う名前
+    pub fn name(&self) -> String {
+        match self {
+            KeyAction::SelectTerrain(digit) => format!("SelectTerrain{}", digit),
+            action => format!("{:?}", action),
+        }
+    }
+
+    /// 名前から操作を求める（未知の名前はNone）
+    pub fn from_name(name: &str) -> Option<Self> {
+        let action = match name {
+            "ScrollUp" => KeyAction::ScrollUp,
+            "ScrollDown" => KeyAction::ScrollDown,
+            "ScrollLeft" => KeyAction::ScrollLeft,
+            "ScrollRight" => KeyAction::ScrollRight,
+            "ZoomIn" => KeyAction::ZoomIn,
+            "ZoomOut" => KeyAction::ZoomOut,
+            "Cancel" => KeyAction::Cancel,
+            "ToggleGrid" => KeyAction::ToggleGrid,
+            "ToggleEditor" => KeyAction::ToggleEditor,
+            "Undo" => KeyAction::Undo,
+            "Redo" => KeyAction::Redo,
+            _ => {
+                let digit = name.strip_prefix("SelectTerrain")?.parse().ok()?;
+                terrain_for_digit(digit)?;
+                KeyAction::SelectTerrain(digit)
+            }
+        };
+        Some(action)
+    }
+
+    /// 操作に対応するマップ操作アクション
+    pub fn input_action(&self) -> Option<InputAction> {
+        let action = match *self {
+            KeyAction::ScrollUp => InputAction::ScrollTiles { dx: 0, dy: -1 },
+            KeyAction::ScrollDown => InputAction::ScrollTiles { dx: 0, dy: 1 },
+            KeyAction::ScrollLeft => InputAction::ScrollTiles { dx: -1, dy: 0 },
+            KeyAction::ScrollRight => InputAction::ScrollTiles { dx: 1, dy: 0 },
+            KeyAction::ZoomIn => InputAction::Zoom(ZOOM_STEP),
+            KeyAction::ZoomOut => InputAction::Zoom(1.0 / ZOOM_STEP),
+            KeyAction::Cancel => InputAction::ClearSelection,
+            KeyAction::ToggleGrid => InputAction::ToggleGrid,
+            KeyAction::ToggleEditor => InputAction::ToggleEditor,
+            KeyAction::Undo => InputAction::Undo,
+            KeyAction::Redo => InputAction::Redo,
+            KeyAction::SelectTerrain(digit) => {
+                InputAction::SelectTerrain(terrain_for_digit(digit)?)
+            }
+        };
+        Some(action)
+    }
+}
+
+/// 操作ごとのキーの割り当て
+///
+/// 設定ファイルは操作名からキーの組み合わせの一覧への対応で、未知の操作名は
+/// 警告を出して無視する。1つのキーに複数の操作が割り当てられている場合は
+/// conflictsで検出でき、入力時は操作の定義順で先のものを使う。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    from = "BTreeMap<String, Vec<KeyCombo>>",
+    into = "BTreeMap<String, Vec<KeyCombo>>"
+)]
+pub struct KeyBindings {
+    bindings: BTreeMap<KeyAction, Vec<KeyCombo>>,
+}
+
+impl Default for KeyBindings {
+    /// 組み込みのキー配置（矢印キーとWASDでスクロール、+/-でズームなど）
+    fn default() -> Self {
+        let mut bindings = BTreeMap::from([
+            (
+                KeyAction::ScrollUp,
+                vec![KeyCombo::new(Key::ArrowUp), KeyCombo::new(Key::W)],
+            ),
+            (
+                KeyAction::ScrollDown,
+                vec![KeyCombo::new(Key::ArrowDown), KeyCombo::new(Key::S)],
+            ),
+            (
+                KeyAction::ScrollLeft,
+                vec![KeyCombo::new(Key::ArrowLeft), KeyCombo::new(Key::A)],
+            ),
+            (
+                KeyAction::ScrollRight,
+                vec![KeyCombo::new(Key::ArrowRight), KeyCombo::new(Key::D)],
+            ),
+            (KeyAction::ZoomIn, vec![KeyCombo::new(Key::Plus)]),
+            (KeyAction::ZoomOut, vec![KeyCombo::new(Key::Minus)]),
+            (KeyAction::Cancel, vec![KeyCombo::new(Key::Escape)]),
+            (KeyAction::ToggleGrid, vec![KeyCombo::new(Key::G)]),
+            (KeyAction::ToggleEditor, vec![KeyCombo::new(Key::E)]),
+            (KeyAction::Undo, vec![KeyCombo::new(Key::Z)]),
+            (KeyAction::Redo, vec![KeyCombo::new(Key::Y)]),
+        ]);
+        for digit in 1..=7 {
+            bindings.insert(
+                KeyAction::SelectTerrain(digit),
+                vec![KeyCombo::new(Key::Digit(digit))],
+            );
+        }
+        Self { bindings }
+    }
+}
+
+impl From<BTreeMap<String, Vec<KeyCombo>>> for KeyBindings {
+    fn from(entries: BTreeMap<String, Vec<KeyCombo>>) -> Self {
+        let mut bindings = BTreeMap::new();
+        for (name, combos) in entries {
+            match KeyAction::from_name(&name) {
+                Some(action) => {
+                    bindings.insert(action, combos);
+                }
+                None => warn!("未知の操作名のキー割り当てを無視します: {}", name),
+            }
+        }
+        Self { bindings }
+    }
+}
+
+impl From<KeyBindings> for BTreeMap<String, Vec<KeyCombo>> {
+    fn from(key_bindings: KeyBindings) -> Self {
+        key_bindings
+            .bindings
+            .into_iter()
+            .map(|(action, combos)| (action.name(), combos))
+            .collect()
+    }
+}
+
+impl KeyBindings {
+    /// 割り当てのない状態
+    pub fn empty() -> Self {
+        Self {
+            bindings: BTreeMap::new(),
+        }
+    }
+
+    /// ファイルの割り当てで組み込みの配置を上書きしたものを読み込む
+    ///
+    /// ファイルに書かれていない操作は組み込みの割り当てのまま。
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("キー割り当てを読み込めません: {}", path.display()))?;
+        let overrides: KeyBindings = ron::from_str(&text)
+            .with_context(|| format!("キー割り当てが壊れています: {}", path.display()))?;
+        let mut bindings = Self::default();
+        bindings.merge(overrides);
+        Ok(bindings)
+    }
+
+    /// 割り当てをファイルに保存
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    /// 別の割り当てで上書きする（上書き側にある操作はキーの一覧ごと置き換える）
+    pub fn merge(&mut self, overrides: KeyBindings) {
+        self.bindings.extend(overrides.bindings);
+    }
+
+    /// 操作のキーの割り当てを設定（空にすると割り当てなし）
+    pub fn bind(&mut self, action: KeyAction, combos: Vec<KeyCombo>) {
+        self.bindings.insert(action, combos);
+    }
+
+    /// 操作に割り当てられたキー
+    pub fn combos(&self, action: KeyAction) -> &[KeyCombo] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// キーに割り当てられた操作（重複している場合は定義順で先のもの）
+    pub fn action_for(&self, combo: &KeyCombo) -> Option<KeyAction> {
+        self.bindings
+            .iter()
+            .find(|(_, combos)| combos.contains(combo))
+            .map(|(action, _)| *action)
+    }
+
+    /// 複数の操作に割り当てられているキーと、その操作の一覧（キー順）
+    pub fn conflicts(&self) -> Vec<(KeyCombo, Vec<KeyAction>)> {
+        let mut actions_by_combo: BTreeMap<KeyCombo, Vec<KeyAction>> = BTreeMap::new();
+        for (action, combos) in &self.bindings {
+            for combo in combos {
+                let actions = actions_by_combo.entry(*combo).or_default();
+                if !actions.contains(action) {
+                    actions.push(*action);
+                }
+            }
+        }
+        actions_by_combo
+            .into_iter()
+            .filter(|(_, actions)| actions.len() > 1)
+            .collect()
+    }
+
+    /// 入力イベントをアクションに変換する
+    pub fn translate(&self, event: &InputEvent) -> Option<InputAction> {
+        let combo = match *event {
+            InputEvent::KeyPressed(key) => KeyCombo::new(key),
+            InputEvent::KeyPressedWith(key, modifiers) => KeyCombo { key, modifiers },
+            InputEvent::MouseClicked { x, y } => return Some(InputAction::Click { x, y }),
+        };
+        self.action_for(&combo)?.input_action()
+    }
+}
+
+/// 組み込みのキー配置で入力イベントをアクションに変換する
+pub fn translate(event: &InputEvent) -> Option<InputAction> {
+    KeyBindings::default().translate(event)
+}
+
+impl MapGUI {
+    /// 入力アクションをMapGUIに適用する（クリックやカーソル位置での決定時はその結果を返す）
+    ///
+    /// 編集モード中のクリックは選択ではなく地形の塗りになる。
+    pub fn apply_input(&mut self, action: InputAction) -> Result<Option<ClickOutcome>> {
+        match action {
+            InputAction::ScrollTiles { dx, dy } => {
+                let tile_size = self.scaled_tile_size();
+                self.scroll(dx * tile_size, dy * tile_size);
+            }
+            InputAction::ScrollPixels { dx, dy } => self.scroll(dx, dy),
+            InputAction::Zoom(factor) => self.zoom(factor),
+            InputAction::Click { x, y } => return self.handle_click(x, y).map(Some),
+            InputAction::MoveCursor { dx, dy } => {
+                self.move_cursor(dx, dy);
+            }
+            InputAction::SelectAtCursor => return self.select_at_cursor().map(Some),
+            InputAction::CycleUnit { reverse } => {
+                // 手番制限中はその勢力、なければ選択中のユニットの勢力
+                let faction_id = self
+                    .get_active_faction()
+                    .or(self.get_selected_unit().map(|unit| unit.faction_id));
+                if let Some(faction_id) = faction_id {
+                    if reverse {
+                        self.select_previous_unit(faction_id)?;
+                    } else {
+                        self.select_next_unit(faction_id)?;
+                    }
+                }
+            }
+            InputAction::ClearSelection => self.clear_selection(),
+            InputAction::ToggleGrid => {
+                let mut options = self.get_view_options().clone();
+                options.show_grid = !options.show_grid;
+                self.set_view_options(options);
+            }
+            InputAction::ToggleEditor => self.set_editor_mode(!self.is_editor_mode()),
+            InputAction::SelectTerrain(terrain) => self.set_editor_terrain(terrain),
+            InputAction::Undo => {
+                self.undo();
+            }
+            InputAction::Redo => {
+                self.redo();
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventBus;
+    use model::{Cell, Map, MapPosition};
+
+    #[test]
+    fn test_translate_keys() {
+        assert_eq!(
+            translate(&InputEvent::KeyPressed(Key::ArrowUp)),
+            Some(InputAction::ScrollTiles { dx: 0, dy: -1 })
+        );
+        assert_eq!(
+            translate(&InputEvent::KeyPressed(Key::D)),
+            Some(InputAction::ScrollTiles { dx: 1, dy: 0 })
+        );
+        assert_eq!(
+            translate(&InputEvent::KeyPressed(Key::Plus)),
+            Some(InputAction::Zoom(ZOOM_STEP))
+        );
+        assert_eq!(
+            translate(&InputEvent::KeyPressed(Key::Escape)),
+            Some(InputAction::ClearSelection)
+        );
+        assert_eq!(
+            translate(&InputEvent::KeyPressed(Key::G)),
+            Some(InputAction::ToggleGrid)
+        );
+        assert_eq!(
+            translate(&InputEvent::KeyPressed(Key::Digit(3))),
+            Some(InputAction::SelectTerrain(CellType::Mountain))
+        );
+        assert_eq!(translate(&InputEvent::KeyPressed(Key::Digit(9))), None);
+        assert_eq!(
+            translate(&InputEvent::KeyPressed(Key::Z)),
+            Some(InputAction::Undo)
+        );
+        assert_eq!(
+            translate(&InputEvent::MouseClicked { x: 10, y: 20 }),
+            Some(InputAction::Click { x: 10, y: 20 })
+        );
+    }
+
+    #[test]
+    fn test_key_bindings_merge_precedence() {
+        let mut bindings = KeyBindings::default();
+        let mut overrides = KeyBindings::empty();
+        overrides.bind(KeyAction::Undo, vec![KeyCombo::ctrl(Key::Z)]);
+        overrides.bind(KeyAction::ToggleGrid, Vec::new());
+        bindings.merge(overrides);
+
+        // 上書きした操作はキーの一覧ごと置き換わり、それ以外は組み込みのまま
+        assert_eq!(bindings.combos(KeyAction::Undo), &[KeyCombo::ctrl(Key::Z)]);
+        assert_eq!(
+            translate(&InputEvent::KeyPressed(Key::Z)),
+            Some(InputAction::Undo)
+        );
+        assert_eq!(bindings.translate(&InputEvent::KeyPressed(Key::Z)), None);
+        assert_eq!(
+            bindings.translate(&InputEvent::KeyPressedWith(
+                Key::Z,
+                Modifiers {
+                    ctrl: true,
+                    ..Modifiers::default()
+                }
+            )),
+            Some(InputAction::Undo)
+        );
+        assert_eq!(bindings.translate(&InputEvent::KeyPressed(Key::G)), None);
+        assert_eq!(
+            bindings.translate(&InputEvent::KeyPressed(Key::W)),
+            Some(InputAction::ScrollTiles { dx: 0, dy: -1 })
+        );
+    }
+
+    #[test]
+    fn test_key_binding_conflicts() {
+        let mut bindings = KeyBindings::default();
+        assert!(bindings.conflicts().is_empty());
+
+        bindings.bind(
+            KeyAction::ZoomIn,
+            vec![KeyCombo::new(Key::Plus), KeyCombo::new(Key::Z)],
+        );
+        bindings.bind(
+            KeyAction::Redo,
+            vec![KeyCombo::new(Key::Z), KeyCombo::new(Key::Z)],
+        );
+        assert_eq!(
+            bindings.conflicts(),
+            vec![(
+                KeyCombo::new(Key::Z),
+                vec![KeyAction::ZoomIn, KeyAction::Undo, KeyAction::Redo]
+            )]
+        );
+        // 重複したキーは操作の定義順で先のものになる
+        assert_eq!(
+            bindings.translate(&InputEvent::KeyPressed(Key::Z)),
+            Some(InputAction::Zoom(ZOOM_STEP))
+        );
+    }
+
+    #[test]
+    fn test_key_bindings_file() -> Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("sl_gem_keybindings_{}.ron", std::process::id()));
+
+        // 保存したものをそのまま読み込める
+        let mut bindings = KeyBindings::default();
+        bindings.bind(
+            KeyAction::SelectTerrain(2),
+            vec![KeyCombo::ctrl(Key::Digit(2))],
+        );
+        bindings.save(&path)?;
+        assert_eq!(KeyBindings::load(&path)?, bindings);
+        let text = fs::read_to_string(&path)?;
+        assert!(text.contains("\"SelectTerrain2\""));
+
+        // 未知の操作名は無視し、書かれていない操作は組み込みの割り当てを使う
+        fs::write(
+            &path,
+            "{\"Redo\": [(key: Y, modifiers: (shift: true))], \"Fly\": [(key: A)]}",
+        )?;
+        let loaded = KeyBindings::load(&path)?;
+        assert_eq!(
+            loaded.combos(KeyAction::Redo),
+            &[KeyCombo {
+                key: Key::Y,
+                modifiers: Modifiers {
+                    shift: true,
+                    ..Modifiers::default()
+                },
+            }]
+        );
+        assert_eq!(loaded.combos(KeyAction::Undo), &[KeyCombo::new(Key::Z)]);
+        assert!(loaded.conflicts().is_empty());
+
+        fs::write(&path, "{\"Redo\": [(key: Enter)]}")?;
+        assert!(KeyBindings::load(&path).is_err());
+        fs::remove_file(&path).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_input() -> Result<()> {
+        let mut map_gui = MapGUI::new(EventBus::new());
+        let mut map = Map::new(40, 40);
+        map.set_cell(MapPosition::new(1, 1), Cell::new(CellType::Plain));
+        map_gui.set_map(map);
+
+        // 1タイル分スクロール
+        map_gui.apply_input(InputAction::ScrollTiles { dx: 1, dy: 0 })?;
+        assert_eq!(map_gui.get_view_options().scroll_x, 32);
+
+        // グリッド表示の切り替え
+        assert!(map_gui.get_view_options().show_grid);
+        map_gui.apply_input(InputAction::ToggleGrid)?;
+        assert!(!map_gui.get_view_options().show_grid);
+
+        // クリックでセル選択、Escapeで解除
+        let outcome = map_gui.apply_input(InputAction::Click { x: 0, y: 32 })?;
+        assert_eq!(
+            outcome,
+            Some(ClickOutcome::SelectedCell(MapPosition::new(1, 1)))
+        );
+        map_gui.apply_input(InputAction::ClearSelection)?;
+        assert!(map_gui.get_selected_position().is_none());
+
+        // 編集モードではクリックで選択中の地形を塗り、Undoで戻す
+        map_gui.apply_input(InputAction::ToggleEditor)?;
+        map_gui.apply_input(InputAction::SelectTerrain(CellType::Water))?;
+        let outcome = map_gui.apply_input(InputAction::Click { x: 0, y: 32 })?;
+        assert_eq!(outcome, Some(ClickOutcome::Painted(MapPosition::new(1, 1))));
+        let terrain = |gui: &MapGUI| {
+            gui.get_map()
+                .and_then(|map| map.get_cell(&MapPosition::new(1, 1)))
+                .map(|cell| cell.cell_type)
+        };
+        assert_eq!(terrain(&map_gui), Some(CellType::Water));
+        map_gui.apply_input(InputAction::Undo)?;
+        assert_eq!(terrain(&map_gui), Some(CellType::Plain));
+        Ok(())
+    }
+}
@@ -1,21 +1,101 @@
 pub mod core;
 pub mod events;
+pub mod gamepad;
 pub mod gui;
+pub mod input;
+pub mod replay;
+pub mod save;
+pub mod settings;
+pub mod turn;
 
-use self::core::{GameLoop as CoreGameLoop, LoopConfig as CoreLoopConfig};
-pub use self::events::{EventBus, GameEvent, LogLevel, PrioritizedEvent, Priority};
-pub use self::gui::{map_gui::MapGUI, map_gui::MapViewOptions};
+pub use self::core::{FrameProfiler, FrameStats, PhaseStats};
+use self::core::{
+    GameLoop as CoreGameLoop, LoopConfig as CoreLoopConfig, RenderCallback, RenderGate,
+    UpdateCallback,
+};
+pub use self::events::{
+    forward_logs, BackpressurePolicy, BusStats, EventBus, EventKind, GameEvent, LogLevel,
+    PrioritizedEvent, Priority, PublishObserver, RequestError, Subscription, SubscriptionId,
+    TopicConfig, TopicStats, LOG_TOPIC, MAP_UPDATED_TOPIC,
+};
+pub use self::gui::{
+    map_gui::AsciiLod, map_gui::ClickOutcome, map_gui::HighlightKind, map_gui::MapGUI,
+    map_gui::MapViewOptions,
+};
+pub use self::replay::{EventRecorder, EventReplayer, RecordedEvent};
+pub use self::save::SaveGame;
+pub use self::settings::UserSettings;
+pub use self::turn::{AutosaveContext, AutosaveHook, TurnManager, TurnState};
 // modelのPositionをre-exportしない - 直接modelからインポートする
 use anyhow::Result;
+use crossbeam_channel::{RecvTimeoutError, SendTimeoutError};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 // CoreLoopConfigをLoopConfigとして再エクスポート
 pub type LoopConfig = CoreLoopConfig;
 
+/// 購読転送スレッドが終了通知を確認する間隔
+const FORWARDER_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// デバッグ用メトリクスを出力する既定の間隔
+const DEFAULT_METRICS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Engine::subscribeで起動した転送スレッドを管理する
+///
+/// Engine::stopの呼び出し時と最後のEngineが破棄されたときに全スレッドへ終了を通知し、
+/// 終了を待つ。
+#[derive(Default)]
+struct Forwarders {
+    shutdown: Arc<AtomicBool>,
+    active: Arc<AtomicUsize>,
+    handles: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl Forwarders {
+    /// 全スレッドを終了させ、以降の購読のために終了通知を戻す
+    fn shutdown(&self) {
+        // 終了を待つ間に新しい転送スレッドが起動しないよう、ロックを保持したまま行う
+        let mut handles = self.handles.lock().unwrap();
+        self.shutdown.store(true, Ordering::SeqCst);
+        for handle in handles.drain(..) {
+            handle.join().ok();
+        }
+        self.shutdown.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Drop for Forwarders {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        for handle in self.handles.get_mut().unwrap().drain(..) {
+            handle.join().ok();
+        }
+    }
+}
+
+/// エンジンの実行状態
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EngineState {
+    #[default]
+    Stopped,
+    Running,
+    Paused,
+}
+
 /// ゲームエンジンの主要な構造体
 #[derive(Clone)]
 pub struct Engine {
     event_bus: EventBus,
-    running: bool,
+    state: EngineState,
+    forwarders: Arc<Forwarders>,
+    debug_metrics: bool,
+    metrics_interval: Duration,
+    last_metrics_report: Option<Instant>,
+    frame_stats: Option<Arc<Mutex<FrameStats>>>,
 }
 
 impl Engine {
@@ -29,29 +109,138 @@ impl Engine {
     }
 
     /// イベントの購読を登録
+    ///
+    /// 優先度を取り除いて転送するスレッドを起動する。スレッドは受信側が破棄されるか、
+    /// Engine::stopが呼ばれるか、Engineがすべて破棄された時点で、受信済みのイベントを
+    /// 詰まらない範囲で渡してから購読を解除して終了する。終了後の受信側は切断される。
     pub fn subscribe(&self, event_type: &str) -> Result<crossbeam_channel::Receiver<GameEvent>> {
-        let prioritized_receiver = self.event_bus.subscribe(event_type)?;
+        let mut handles = self.forwarders.handles.lock().unwrap();
+        let (id, prioritized_receiver) = self.event_bus.subscribe_with_id(event_type)?;
 
         // PrioritizedEventからGameEventに変換するチャネルを作成
         let (sender, receiver) = crossbeam_channel::bounded(100);
 
-        // 別スレッドでPrioritizedEventを受信してGameEventに変換して送信
-        std::thread::spawn(move || {
-            while let Ok(prioritized_event) = prioritized_receiver.recv() {
-                if sender.send(prioritized_event.event).is_err() {
+        let event_bus = self.event_bus.clone();
+        let topic = event_type.to_string();
+        let shutdown = self.forwarders.shutdown.clone();
+        let active = self.forwarders.active.clone();
+        active.fetch_add(1, Ordering::SeqCst);
+        let handle = std::thread::spawn(move || {
+            'forward: loop {
+                if shutdown.load(Ordering::SeqCst) {
+                    // 終了前に受信済みのイベントを詰まらない範囲で渡す
+                    for prioritized_event in prioritized_receiver.try_iter() {
+                        if sender.try_send(prioritized_event.event).is_err() {
+                            break;
+                        }
+                    }
                     break;
                 }
+                let mut event = match prioritized_receiver.recv_timeout(FORWARDER_POLL_INTERVAL) {
+                    Ok(prioritized_event) => prioritized_event.event,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+                // 受信側が詰まっていても終了通知に応答できるよう、タイムアウト付きで送信
+                loop {
+                    match sender.send_timeout(event, FORWARDER_POLL_INTERVAL) {
+                        Ok(()) => break,
+                        Err(SendTimeoutError::Timeout(pending)) => {
+                            if shutdown.load(Ordering::SeqCst) {
+                                break 'forward;
+                            }
+                            event = pending;
+                        }
+                        Err(SendTimeoutError::Disconnected(_)) => break 'forward,
+                    }
+                }
             }
+            event_bus.unsubscribe(&topic, id);
+            active.fetch_sub(1, Ordering::SeqCst);
         });
+        handles.retain(|handle| !handle.is_finished());
+        handles.push(handle);
 
         Ok(receiver)
     }
 
-    /// イベントを発行
-    pub fn publish(&self, event_type: &str, event: GameEvent) -> Result<()> {
+    /// 優先度付きのままイベントを購読（転送スレッドを使わない）
+    pub fn subscribe_prioritized(&self, event_type: &str) -> Result<Subscription> {
+        self.event_bus.subscribe(event_type)
+    }
+
+    /// イベントを発行し、配信できた購読者数を返す
+    pub fn publish(&self, event_type: &str, event: GameEvent) -> Result<usize> {
         self.event_bus.publish(event_type, event)
     }
 
+    /// イベントバスのメトリクス出力を有効/無効にする
+    pub fn set_debug_metrics(&mut self, enabled: bool) {
+        self.debug_metrics = enabled;
+        self.last_metrics_report = None;
+    }
+
+    /// メトリクス出力が有効か
+    pub fn is_debug_metrics(&self) -> bool {
+        self.debug_metrics
+    }
+
+    /// メトリクスを出力する間隔を設定
+    pub fn set_metrics_interval(&mut self, interval: Duration) {
+        self.metrics_interval = interval;
+    }
+
+    /// メトリクスに含めるフレーム処理時間の統計を設定（GameLoop::frame_statsを渡す）
+    pub fn set_frame_stats(&mut self, stats: Arc<Mutex<FrameStats>>) {
+        self.frame_stats = Some(stats);
+    }
+
+    /// メトリクス出力が有効で前回から間隔が経過していれば、統計をLogイベントとして発行する
+    ///
+    /// "engine"トピックにInfoレベルで発行する（キューが満杯なら破棄）。発行した場合はtrueを返す。
+    /// set_frame_statsで統計が設定されていれば、更新・描画の処理時間も含める。
+    pub fn report_metrics(&mut self) -> bool {
+        if !self.debug_metrics {
+            return false;
+        }
+        let now = Instant::now();
+        if self
+            .last_metrics_report
+            .is_some_and(|last| now.duration_since(last) < self.metrics_interval)
+        {
+            return false;
+        }
+        self.last_metrics_report = Some(now);
+
+        let mut message = format!("EventBus統計: {}", self.event_bus.stats());
+        if let Some(frame_stats) = &self.frame_stats {
+            let frame_stats = *frame_stats.lock().unwrap();
+            message.push_str(&format!(" / フレーム統計: {}", frame_stats));
+        }
+        log::debug!("{}", message);
+        self.event_bus.publish_or_drop(
+            "engine",
+            GameEvent::Log {
+                message,
+                level: LogLevel::Info,
+            },
+        );
+        true
+    }
+
+    /// イベントバスに発行されるすべてのイベントをファイルに記録する
+    pub fn enable_recording(&self, path: impl AsRef<Path>) -> Result<()> {
+        EventRecorder::create(path)?.attach(&self.event_bus);
+        Ok(())
+    }
+
+    /// 記録ファイルのイベントを記録時のタイミングで再発行し、発行した件数を返す
+    ///
+    /// 待たずに再生する場合はEventReplayer::with_realtime(false)を直接使う。
+    pub fn replay(&self, path: impl AsRef<Path>) -> Result<usize> {
+        Ok(EventReplayer::load(path)?.replay(&self.event_bus))
+    }
+
     /// エンジンの実行を開始
     pub fn run(&mut self) -> Result<()> {
         self.start()?;
@@ -60,15 +249,50 @@ impl Engine {
 
     /// ゲームループを開始
     pub fn start(&mut self) -> Result<()> {
-        self.running = true;
+        self.state = EngineState::Running;
         self.publish("engine", GameEvent::Start)?;
         Ok(())
     }
 
     /// ゲームループを停止
+    ///
+    /// Stopイベントを発行した後、Engine::subscribeの転送スレッドをすべて終了させる。
     pub fn stop(&mut self) -> Result<()> {
-        self.running = false;
-        self.publish("engine", GameEvent::Stop)?;
+        self.state = EngineState::Stopped;
+        let published = self.publish("engine", GameEvent::Stop);
+        self.forwarders.shutdown();
+        published?;
+        Ok(())
+    }
+
+    /// 現在の実行状態
+    pub fn state(&self) -> EngineState {
+        self.state
+    }
+
+    /// 実行中のエンジンを一時停止する（実行中でなければエラー）
+    pub fn pause(&mut self) -> Result<()> {
+        if self.state != EngineState::Running {
+            return Err(anyhow::anyhow!(
+                "実行中でないため一時停止できません: {:?}",
+                self.state
+            ));
+        }
+        self.state = EngineState::Paused;
+        self.publish("engine", GameEvent::Pause)?;
+        Ok(())
+    }
+
+    /// 一時停止中のエンジンを再開する（一時停止中でなければエラー）
+    pub fn resume(&mut self) -> Result<()> {
+        if self.state != EngineState::Paused {
+            return Err(anyhow::anyhow!(
+                "一時停止中でないため再開できません: {:?}",
+                self.state
+            ));
+        }
+        self.state = EngineState::Running;
+        self.publish("engine", GameEvent::Resume)?;
         Ok(())
     }
 
@@ -88,7 +312,12 @@ impl Default for Engine {
     fn default() -> Self {
         Engine {
             event_bus: EventBus::new(),
-            running: false,
+            state: EngineState::Stopped,
+            forwarders: Arc::new(Forwarders::default()),
+            debug_metrics: false,
+            metrics_interval: DEFAULT_METRICS_INTERVAL,
+            last_metrics_report: None,
+            frame_stats: None,
         }
     }
 }
@@ -97,11 +326,49 @@ impl Default for Engine {
 pub struct GameLoop {
     config: LoopConfig,
     receiver: crossbeam_channel::Receiver<GameEvent>,
+    on_update: Option<UpdateCallback>,
+    on_render: Option<RenderCallback>,
+    render_gate: Option<RenderGate>,
+    fps: Arc<AtomicU32>,
+    frame_stats: Arc<Mutex<FrameStats>>,
 }
 
 impl GameLoop {
     pub fn new(config: LoopConfig, receiver: crossbeam_channel::Receiver<GameEvent>) -> Self {
-        GameLoop { config, receiver }
+        GameLoop {
+            config,
+            receiver,
+            on_update: None,
+            on_render: None,
+            render_gate: None,
+            fps: Arc::new(AtomicU32::new(0)),
+            frame_stats: Arc::new(Mutex::new(FrameStats::default())),
+        }
+    }
+
+    /// 計測したFPSを共有するカウンタ（run中に別スレッドから参照できる）
+    pub fn fps_counter(&self) -> Arc<AtomicU32> {
+        self.fps.clone()
+    }
+
+    /// 直近のフレームの処理時間の統計（run中に別スレッドから参照できる）
+    pub fn frame_stats(&self) -> Arc<Mutex<FrameStats>> {
+        self.frame_stats.clone()
+    }
+
+    /// 更新コールバックを登録（固定時間ステップごとに経過秒を受け取る）
+    pub fn set_on_update(&mut self, callback: impl FnMut(f32) -> Result<()> + 'static) {
+        self.on_update = Some(Box::new(callback));
+    }
+
+    /// 描画コールバックを登録
+    pub fn set_on_render(&mut self, callback: impl FnMut() -> Result<()> + 'static) {
+        self.on_render = Some(Box::new(callback));
+    }
+
+    /// 描画が必要か判定するコールバックを登録（未登録なら毎フレーム描画する）
+    pub fn set_render_gate(&mut self, gate: impl FnMut() -> bool + 'static) {
+        self.render_gate = Some(Box::new(gate));
     }
 
     pub fn run(&mut self) -> Result<()> {
@@ -113,13 +380,7 @@ impl GameLoop {
         std::thread::spawn(move || {
             while let Ok(event) = receiver.recv() {
                 let priority = event.default_priority();
-                if sender
-                    .send(PrioritizedEvent {
-                        priority,
-                        event: event.clone(),
-                    })
-                    .is_err()
-                {
+                if sender.send(PrioritizedEvent::new(priority, event)).is_err() {
                     break;
                 }
             }
@@ -127,6 +388,174 @@ impl GameLoop {
 
         // コアGameLoopを初期化して実行
         let mut core_loop = CoreGameLoop::new(self.config.clone(), prioritized_receiver);
+        core_loop.set_fps_counter(self.fps.clone());
+        core_loop.set_frame_stats(self.frame_stats.clone());
+        if let Some(on_update) = self.on_update.take() {
+            core_loop.set_on_update(on_update);
+        }
+        if let Some(on_render) = self.on_render.take() {
+            core_loop.set_on_render(on_render);
+        }
+        if let Some(render_gate) = self.render_gate.take() {
+            core_loop.set_render_gate(render_gate);
+        }
         core_loop.run()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscribe_forwards_events() -> Result<()> {
+        let engine = Engine::new();
+        let receiver = engine.subscribe("test")?;
+        engine.publish("test", GameEvent::Start)?;
+        let event = receiver.recv_timeout(Duration::from_secs(1))?;
+        assert!(matches!(event, GameEvent::Start));
+        Ok(())
+    }
+
+    #[test]
+    fn test_subscribe_prioritized_without_thread() -> Result<()> {
+        let engine = Engine::new();
+        let receiver = engine.subscribe_prioritized("test")?;
+        engine.publish("test", GameEvent::Stop)?;
+        let event = receiver.try_recv()?;
+        assert_eq!(event.priority, Priority::High);
+        assert_eq!(engine.forwarders.active.load(Ordering::SeqCst), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_forwarders_terminate_on_drop() -> Result<()> {
+        let engine = Engine::new();
+        let active = engine.forwarders.active.clone();
+
+        // 受信側を保持したまま100件購読する
+        let receivers: Vec<_> = (0..100)
+            .map(|_| engine.subscribe("test"))
+            .collect::<Result<_>>()?;
+        assert_eq!(active.load(Ordering::SeqCst), 100);
+
+        // クローンが残っている間はスレッドは動き続ける
+        let clone = engine.clone();
+        drop(engine);
+        assert_eq!(active.load(Ordering::SeqCst), 100);
+
+        // 最後のEngineの破棄で全スレッドが終了する
+        drop(clone);
+        assert_eq!(active.load(Ordering::SeqCst), 0);
+        drop(receivers);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stop_terminates_forwarders_and_unsubscribes() -> Result<()> {
+        let mut engine = Engine::new();
+        let active = engine.forwarders.active.clone();
+        let receiver = engine.subscribe("engine")?;
+        let _other = engine.subscribe("test")?;
+        assert_eq!(active.load(Ordering::SeqCst), 2);
+
+        // Stopは転送されてから受信側が切断され、バスの購読も解除される
+        engine.start()?;
+        engine.stop()?;
+        assert_eq!(active.load(Ordering::SeqCst), 0);
+        let events: Vec<_> = receiver.try_iter().collect();
+        assert!(matches!(events[..], [GameEvent::Start, GameEvent::Stop]));
+        assert!(matches!(
+            receiver.recv_timeout(Duration::from_secs(1)),
+            Err(RecvTimeoutError::Disconnected)
+        ));
+        assert_eq!(engine.publish("engine", GameEvent::Start)?, 0);
+        assert_eq!(engine.publish("test", GameEvent::Start)?, 0);
+
+        // 停止後も新たに購読できる
+        let receiver = engine.subscribe("test")?;
+        engine.publish("test", GameEvent::Resume)?;
+        let event = receiver.recv_timeout(Duration::from_secs(1))?;
+        assert!(matches!(event, GameEvent::Resume));
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_metrics() -> Result<()> {
+        let mut engine = Engine::new();
+        let receiver = engine.subscribe_prioritized("engine")?;
+        engine.publish("map_gui", GameEvent::MapUpdated)?;
+
+        // 無効時は何も発行しない
+        assert!(!engine.report_metrics());
+        assert!(receiver.try_recv().is_err());
+
+        engine.set_debug_metrics(true);
+        engine.set_metrics_interval(Duration::from_secs(3600));
+        assert!(engine.report_metrics());
+        match receiver.try_recv()?.event {
+            GameEvent::Log { message, level } => {
+                assert_eq!(level, LogLevel::Info);
+                assert!(message.contains("map_gui: published=1"));
+                assert!(!message.contains("フレーム統計"));
+            }
+            _ => panic!("Unexpected event received"),
+        }
+
+        // 間隔が経過するまでは再度発行しない
+        assert!(!engine.report_metrics());
+        engine.set_metrics_interval(Duration::ZERO);
+        assert!(engine.report_metrics());
+        receiver.try_recv()?;
+
+        // ゲームループのフレーム統計を設定すると更新・描画の処理時間も含める
+        let (_sender, loop_receiver) = crossbeam_channel::unbounded();
+        let game_loop = GameLoop::new(LoopConfig::default(), loop_receiver);
+        let mut profiler = FrameProfiler::new(4);
+        profiler.record(Duration::from_millis(2), Duration::from_millis(5));
+        profiler.record(Duration::from_millis(4), Duration::from_millis(7));
+        *game_loop.frame_stats().lock().unwrap() = profiler.stats();
+        engine.set_frame_stats(game_loop.frame_stats());
+        assert!(engine.report_metrics());
+        match receiver.try_recv()?.event {
+            GameEvent::Log { message, .. } => {
+                assert!(message.contains("map_gui: published=1"));
+                assert!(message.contains("frames=2"));
+                assert!(message.contains("update: avg=3ms min=2ms max=4ms"));
+                assert!(message.contains("render: avg=6ms min=5ms max=7ms"));
+            }
+            _ => panic!("Unexpected event received"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_engine_pause_resume() -> Result<()> {
+        let mut engine = Engine::new();
+        let receiver = engine.subscribe_prioritized("engine")?;
+        assert_eq!(engine.state(), EngineState::Stopped);
+        assert!(engine.pause().is_err());
+
+        engine.start()?;
+        assert!(engine.resume().is_err());
+        engine.pause()?;
+        assert_eq!(engine.state(), EngineState::Paused);
+        assert!(engine.pause().is_err());
+        engine.resume()?;
+        assert_eq!(engine.state(), EngineState::Running);
+        engine.stop()?;
+        assert_eq!(engine.state(), EngineState::Stopped);
+
+        let kinds: Vec<_> = receiver.try_iter().map(|e| e.event.kind()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                EventKind::Start,
+                EventKind::Pause,
+                EventKind::Resume,
+                EventKind::Stop
+            ]
+        );
+        Ok(())
+    }
+}
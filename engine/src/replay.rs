@@ -0,0 +1,238 @@
+//! イベントの記録と再生
+//!
+//! EventBusに発行されたイベントを1行1イベントのRON形式でファイルに記録し、
+//! 後から同じ順序・同じ相対タイミングで別のEventBusへ再発行する。
+//! 不具合の再現を決定的に行うためのデバッグ機能。
+use crate::events::{EventBus, PrioritizedEvent};
+use anyhow::{Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{LineWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 記録された1件のイベント
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub topic: String,
+    pub elapsed_micros: u64, // 記録開始からの経過時間（マイクロ秒）
+    pub event: PrioritizedEvent,
+}
+
+/// EventBusに発行されたイベントをファイルに記録する
+#[derive(Clone)]
+pub struct EventRecorder {
+    writer: Arc<Mutex<LineWriter<File>>>,
+    started: Instant,
+}
+
+impl EventRecorder {
+    /// 記録先のファイルを作成する（既存のファイルは上書き）
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("記録ファイルを作成できません: {}", path.display()))?;
+        Ok(Self {
+            writer: Arc::new(Mutex::new(LineWriter::new(file))),
+            started: Instant::now(),
+        })
+    }
+
+    /// イベントバスに接続し、以降に発行されるイベントを記録する
+    pub fn attach(&self, event_bus: &EventBus) {
+        let recorder = self.clone();
+        event_bus.observe(move |topic, event| recorder.record(topic, event));
+    }
+
+    /// 1件のイベントを記録する（シリアライズできないイベントは警告を出して読み飛ばす）
+    pub fn record(&self, topic: &str, event: &PrioritizedEvent) {
+        let recorded = RecordedEvent {
+            topic: topic.to_string(),
+            elapsed_micros: self.started.elapsed().as_micros() as u64,
+            event: event.clone(),
+        };
+        let line = match ron::to_string(&recorded) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("イベントを記録できません: {:?} ({})", event.event, e);
+                return;
+            }
+        };
+        // 警告がログ転送で再びここへ来ることがあるので、書き込みのロックを外してから出す
+        let written = writeln!(self.writer.lock().unwrap(), "{}", line);
+        if let Err(e) = written {
+            warn!("記録ファイルへの書き込みに失敗しました: {}", e);
+        }
+    }
+}
+
+/// 記録したイベントをEventBusへ再発行する
+#[derive(Clone, Debug, Default)]
+pub struct EventReplayer {
+    events: Vec<RecordedEvent>,
+    realtime: bool, // 記録時の相対タイミングを再現するか
+}
+
+impl EventReplayer {
+    /// 記録ファイルを読み込む
+    ///
+    /// 読み込めない行（このバージョンにないイベントなど）は警告を出して読み飛ばす。
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("記録ファイルを読み込めません: {}", path.display()))?;
+        let events = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter_map(|(i, line)| match ron::from_str(line) {
+                Ok(event) => Some(event),
+                Err(e) => {
+                    warn!("{}行目のイベントを読み飛ばします: {}", i + 1, e);
+                    None
+                }
+            })
+            .collect();
+        Ok(Self {
+            events,
+            realtime: true,
+        })
+    }
+
+    /// 記録時のタイミングを再現するか（falseなら待たずに連続で発行する）
+    pub fn with_realtime(mut self, realtime: bool) -> Self {
+        self.realtime = realtime;
+        self
+    }
+
+    /// 読み込んだイベント
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// イベントを記録時と同じトピック・優先度で発行し、発行した件数を返す
+    ///
+    /// 購読者のキューが満杯で配信できなかったイベントは警告を出して続行する。
+    pub fn replay(&self, event_bus: &EventBus) -> usize {
+        let started = Instant::now();
+        for recorded in &self.events {
+            if self.realtime {
+                let target = Duration::from_micros(recorded.elapsed_micros);
+                if let Some(wait) = target.checked_sub(started.elapsed()) {
+                    std::thread::sleep(wait);
+                }
+            }
+            if let Err(e) = event_bus.publish_with_priority(
+                &recorded.topic,
+                recorded.event.event.clone(),
+                Some(recorded.event.priority),
+            ) {
+                warn!("再生中のイベントを配信できませんでした: {}", e);
+            }
+        }
+        self.events.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{GameEvent, LogLevel, Priority};
+    use model::MapPosition;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sl_gem_{}_{}.ron", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_record_and_replay() -> Result<()> {
+        let path = temp_path("replay");
+        let event_bus = EventBus::new();
+        let recorder = EventRecorder::create(&path)?;
+        recorder.attach(&event_bus);
+
+        let script = vec![
+            ("turn", GameEvent::TurnStart { faction_id: 1 }),
+            (
+                "map_gui",
+                GameEvent::UnitMoved {
+                    unit_id: 3,
+                    from: MapPosition::new(1, 1),
+                    to: MapPosition::new(1, 2),
+                },
+            ),
+            (
+                "engine",
+                GameEvent::Log {
+                    message: "記録テスト".to_string(),
+                    level: LogLevel::Warning,
+                },
+            ),
+            ("turn", GameEvent::TurnEnded { faction_id: 1 }),
+        ];
+        for (topic, event) in &script {
+            event_bus.publish(topic, event.clone())?;
+        }
+        event_bus.publish_with_priority("engine", GameEvent::Pause, Some(Priority::Low))?;
+
+        // 新しいイベントバスに再生し、同じ順序・優先度で届くことを確認
+        let replay_bus = EventBus::new();
+        let receivers: Vec<_> = ["turn", "map_gui", "engine"]
+            .iter()
+            .map(|topic| replay_bus.subscribe(topic).map(|r| (*topic, r)))
+            .collect::<Result<_>>()?;
+        let replayer = EventReplayer::load(&path)?.with_realtime(false);
+        assert_eq!(replayer.replay(&replay_bus), 5);
+
+        let received: Vec<(String, String)> = replayer
+            .events()
+            .iter()
+            .map(|recorded| {
+                let (_, receiver) = receivers
+                    .iter()
+                    .find(|(topic, _)| *topic == recorded.topic)
+                    .unwrap();
+                let event = receiver.try_recv().unwrap();
+                assert_eq!(event.priority, recorded.event.priority);
+                (recorded.topic.clone(), format!("{:?}", event.event))
+            })
+            .collect();
+        let mut expected: Vec<(String, String)> = script
+            .iter()
+            .map(|(topic, event)| (topic.to_string(), format!("{:?}", event)))
+            .collect();
+        expected.push(("engine".to_string(), format!("{:?}", GameEvent::Pause)));
+        assert_eq!(received, expected);
+        assert_eq!(replayer.events()[4].event.priority, Priority::Low);
+
+        fs::remove_file(&path).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_skips_unknown_events() -> Result<()> {
+        let path = temp_path("replay_unknown");
+        let event_bus = EventBus::new();
+        let recorder = EventRecorder::create(&path)?;
+        recorder.attach(&event_bus);
+        event_bus.publish("turn", GameEvent::TurnStart { faction_id: 2 })?;
+
+        // 将来のバージョンで追加されたイベントを含む記録を模擬する
+        let mut text = fs::read_to_string(&path)?;
+        text.push_str(
+            "(topic:\"turn\",elapsed_micros:10,event:(priority:Normal,event:FutureEvent,sequence:0))\n",
+        );
+        fs::write(&path, text)?;
+
+        let replayer = EventReplayer::load(&path)?;
+        assert_eq!(replayer.events().len(), 1);
+        assert!(matches!(
+            replayer.events()[0].event.event,
+            GameEvent::TurnStart { faction_id: 2 }
+        ));
+        fs::remove_file(&path).ok();
+        Ok(())
+    }
+}
@@ -0,0 +1,331 @@
+//! ゲーム状態のバイナリ保存
+//!
+//! マップ・ユニット・勢力・手番・表示設定をまとめてbincodeで保存する。
+//! ファイルの先頭にはマジックバイトとフォーマットのバージョンを置き、
+//! より新しいバージョンで保存されたファイルは読み込まずにエラーにする。
+//! 古いバージョンのファイルは当時の並びで読み込んでから現在の型へ変換する。
+//! bincodeは項目名を持たず`#[serde(default)]`も効かないので、保存する型の
+//! 項目や列挙子を変えたときは必ずSAVE_VERSIONを上げて変換を追加する。
+mod v1;
+
+use crate::gui::map_gui::MapViewOptions;
+use crate::turn::TurnState;
+use anyhow::{Context, Result};
+use model::{FactionManager, Map, Unit};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// セーブファイルの先頭に置くマジックバイト
+const SAVE_MAGIC: &[u8; 4] = b"SLGS";
+
+/// 現在のセーブフォーマットのバージョン
+///
+/// 2: 構造物の分離・格子の種類・8方向の向き・勢力の資源の一本化・表示設定の追加
+pub const SAVE_VERSION: u8 = 2;
+
+/// 自動保存で残す世代数（最新のファイルを含む）
+pub const AUTOSAVE_GENERATIONS: usize = 3;
+
+/// 保存するゲーム状態一式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub map: Map,
+    pub units: Vec<Unit>,
+    pub factions: FactionManager,
+    pub turn: TurnState,
+    pub view_options: MapViewOptions,
+}
+
+impl SaveGame {
+    /// バイナリ形式でファイルに保存する
+    pub fn save_binary(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut bytes = SAVE_MAGIC.to_vec();
+        bytes.push(SAVE_VERSION);
+        bincode::serialize_into(&mut bytes, self).context("ゲーム状態をシリアライズできません")?;
+        fs::write(path, bytes)
+            .with_context(|| format!("セーブファイルを書き込めません: {}", path.display()))
+    }
+
+    /// バイナリ形式のファイルから読み込む
+    pub fn load_binary(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)
+            .with_context(|| format!("セーブファイルを読み込めません: {}", path.display()))?;
+        let Some(body) = bytes.strip_prefix(SAVE_MAGIC.as_slice()) else {
+            return Err(anyhow::anyhow!(
+                "セーブファイルではありません: {}",
+                path.display()
+            ));
+        };
+        let Some((&version, payload)) = body.split_first() else {
+            return Err(anyhow::anyhow!(
+                "セーブファイルが途中で切れています: {}",
+                path.display()
+            ));
+        };
+        let save = match version {
+            1 => bincode::deserialize::<v1::SaveGameV1>(payload).map(SaveGame::from),
+            SAVE_VERSION => bincode::deserialize(payload),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "セーブファイルのバージョン{}には対応していません（対応: 1〜{}）: {}",
+                    version,
+                    SAVE_VERSION,
+                    path.display()
+                ))
+            }
+        };
+        save.with_context(|| format!("セーブファイルが壊れています: {}", path.display()))
+    }
+
+    /// 自動保存する
+    ///
+    /// 既存のファイルは `<path>.1.bak`、`<path>.2.bak` …へ順に退避し、
+    /// 最新を含めて `AUTOSAVE_GENERATIONS` 世代を残す。
+    pub fn autosave(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        for generation in (1..AUTOSAVE_GENERATIONS).rev() {
+            let from = if generation == 1 {
+                path.to_path_buf()
+            } else {
+                backup_path(path, generation - 1)
+            };
+            if from.exists() {
+                fs::rename(&from, backup_path(path, generation))
+                    .with_context(|| format!("自動保存を退避できません: {}", from.display()))?;
+            }
+        }
+        self.save_binary(path)
+    }
+}
+
+/// 自動保存の退避先のパス（世代は1が最も新しい）
+pub fn backup_path(path: &Path, generation: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{}.bak", generation));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventBus;
+    use crate::gui::map_gui::AsciiLod;
+    use crate::turn::TurnManager;
+    use model::{
+        Cell, CellType, Direction, Faction, FactionType, GridKind, MapPosition, Relationship,
+        Resources, Structure, UnitType,
+    };
+    use std::time::{Duration, Instant};
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("sl_gem_{}_{}.sav", name, std::process::id()))
+    }
+
+    /// 大きさとユニット数を指定したゲーム状態
+    fn create_save(size: u32, unit_count: u32) -> SaveGame {
+        let mut map = Map::new(size, size);
+        for x in 0..size as i32 {
+            for y in 0..size as i32 {
+                let cell_type = match (x + y) % 5 {
+                    0 => CellType::Forest,
+                    1 => CellType::Water,
+                    _ => CellType::Plain,
+                };
+                map.set_cell(MapPosition::new(x, y), Cell::new(cell_type));
+            }
+        }
+        map.set_cell(
+            MapPosition::new(0, 0),
+            Cell::with_faction(CellType::Plain, 2).with_structure(Structure::City { level: 1 }),
+        );
+
+        let units = (0..unit_count)
+            .map(|id| {
+                Unit::new(
+                    id + 1,
+                    format!("ユニット{}", id + 1),
+                    UnitType::Cavalry,
+                    id % 2 + 1,
+                    MapPosition::new((id % size) as i32, (id / size) as i32),
+                )
+            })
+            .collect();
+
+        let mut factions = FactionManager::new();
+        for id in 1..=2 {
+            factions.add_faction(Faction::new(
+                id,
+                format!("勢力{}", id),
+                FactionType::Player,
+                (0, 0, 0),
+            ));
+        }
+        factions.get_mut(2).unwrap().gold = 42;
+
+        SaveGame {
+            map,
+            units,
+            factions,
+            turn: TurnManager::new(EventBus::new(), vec![1, 2])
+                .unwrap()
+                .state(),
+            view_options: MapViewOptions {
+                zoom: 0.5,
+                show_ownership: true,
+                show_unit_panel: true,
+                ..MapViewOptions::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_round_trip_large_map() -> Result<()> {
+        let path = temp_path("save_large");
+        let save = create_save(100, 200);
+
+        let started = Instant::now();
+        save.save_binary(&path)?;
+        let loaded = SaveGame::load_binary(&path)?;
+        assert!(started.elapsed() < Duration::from_secs(5));
+        // 1セルあたり数十バイト以内に収まる
+        assert!(fs::metadata(&path)?.len() < 100 * 100 * 40);
+
+        assert_eq!(loaded.map, save.map);
+        assert_eq!(loaded.units.len(), 200);
+        assert_eq!(loaded.units[199].name, "ユニット200");
+        assert_eq!(loaded.units[199].position, save.units[199].position);
+        assert_eq!(loaded.factions, save.factions);
+        assert_eq!(loaded.factions.resources(2).gold, 42);
+        assert_eq!(loaded.turn, save.turn);
+        assert_eq!(loaded.view_options, save.view_options);
+        fs::remove_file(&path).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_newer_version_and_garbage() -> Result<()> {
+        let path = temp_path("save_version");
+        create_save(4, 1).save_binary(&path)?;
+        let mut bytes = fs::read(&path)?;
+        bytes[SAVE_MAGIC.len()] = SAVE_VERSION + 1;
+        fs::write(&path, &bytes)?;
+        let error = SaveGame::load_binary(&path).unwrap_err().to_string();
+        assert!(
+            error.contains(&format!(
+                "バージョン{}には対応していません",
+                SAVE_VERSION + 1
+            )),
+            "{}",
+            error
+        );
+        bytes[SAVE_MAGIC.len()] = 0;
+        fs::write(&path, &bytes)?;
+        assert!(SaveGame::load_binary(&path).is_err());
+
+        fs::write(&path, b"(map: ())")?;
+        assert!(SaveGame::load_binary(&path).is_err());
+        fs::remove_file(&path).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_version_1_fixture() -> Result<()> {
+        // バージョン1のコードで保存したファイル（都市・拠点がCellTypeだった頃のもの）
+        let path = temp_path("save_v1");
+        fs::write(
+            &path,
+            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/save_v1.sav")),
+        )?;
+        let save = SaveGame::load_binary(&path)?;
+        fs::remove_file(&path).ok();
+
+        // バージョン1のマップは正方形の格子
+        assert_eq!(save.map.grid, GridKind::Square);
+
+        // 旧来の都市・拠点は平地と構造物の組になる
+        let city = save.map.get_cell(&MapPosition::new(0, 0)).unwrap();
+        assert_eq!(city.cell_type, CellType::Plain);
+        assert_eq!(city.structure, Some(Structure::City { level: 1 }));
+        assert_eq!(
+            city.structure_hp,
+            Structure::City { level: 1 }.max_hit_points()
+        );
+        assert_eq!(city.faction_id, Some(2));
+        let base = save.map.get_cell(&MapPosition::new(2, 1)).unwrap();
+        assert_eq!(base.structure, Some(Structure::Base));
+        assert!(base.is_capturable());
+        let forest = save.map.get_cell(&MapPosition::new(1, 0)).unwrap();
+        assert_eq!(forest.cell_type, CellType::Forest);
+        assert_eq!(forest.structure, None);
+        assert_eq!(
+            (forest.elevation, forest.movement_cost_override),
+            (2, Some(3))
+        );
+
+        assert_eq!(save.units.len(), 2);
+        let archer = save.units.iter().find(|unit| unit.id == 2).unwrap();
+        assert_eq!(archer.name, "弓兵");
+        assert_eq!(archer.unit_type, UnitType::Ranged);
+        assert_eq!((archer.health, archer.experience), (40, 7));
+        assert_eq!(archer.facing, Direction::S);
+
+        assert_eq!(save.factions.resources(1), Resources::new(35, 0));
+        assert_eq!(save.factions.resources(2), Resources::new(12, 4));
+        assert_eq!(save.factions.relationship(2, 1), Relationship::AtWar);
+        assert!(save.factions.get(1).unwrap().can_attack(2));
+
+        assert_eq!((save.turn.turn_number, save.turn.current_index), (2, 1));
+        assert_eq!(save.view_options.zoom, 0.5);
+        assert_eq!(save.view_options.scroll_x, 64);
+        assert_eq!(save.view_options.ascii_lod, AsciiLod::Fixed(2));
+        // バージョン1にない表示設定は既定値になる
+        assert!(!save.view_options.show_ownership);
+        assert!(!save.view_options.show_unit_panel);
+
+        // 読み込んだ状態は現在のバージョンで保存し直せる
+        let path = temp_path("save_v1_resaved");
+        save.save_binary(&path)?;
+        assert_eq!(fs::read(&path)?[SAVE_MAGIC.len()], SAVE_VERSION);
+        assert_eq!(SaveGame::load_binary(&path)?.map, save.map);
+
+        // 六角形の格子も保存される
+        let mut hex = save;
+        hex.map = hex.map.with_grid(GridKind::HexPointyTop);
+        hex.save_binary(&path)?;
+        assert_eq!(
+            SaveGame::load_binary(&path)?.map.grid,
+            GridKind::HexPointyTop
+        );
+        fs::remove_file(&path).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_autosave_rotation() -> Result<()> {
+        let path = temp_path("autosave");
+        let mut save = create_save(4, 1);
+        for turn_number in 1..=4 {
+            save.turn.turn_number = turn_number;
+            save.autosave(&path)?;
+        }
+
+        let turn_of = |path: &Path| SaveGame::load_binary(path).map(|save| save.turn.turn_number);
+        assert_eq!(turn_of(&path)?, 4);
+        assert_eq!(turn_of(&backup_path(&path, 1))?, 3);
+        assert_eq!(turn_of(&backup_path(&path, 2))?, 2);
+        assert!(!backup_path(&path, 3).exists());
+
+        for generation in 0..AUTOSAVE_GENERATIONS {
+            let file = if generation == 0 {
+                path.clone()
+            } else {
+                backup_path(&path, generation)
+            };
+            fs::remove_file(file).ok();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+//! バージョン1のセーブデータの読み込み
+//!
+//! bincodeは項目名を持たないため、旧フォーマットは保存時の並びのまま読み込んでから
+//! 現在の型へ変換する。バージョン1からの変更点は次のとおり。
+//!
+//! - セルに構造物（structure, structure_hp）が加わり、都市・拠点は平地と構造物の組になった
+//! - マップに格子の種類（grid）が加わった
+//! - 向き（Direction）が8方向になり、列挙子の並びが変わった
+//! - 勢力の資源と関係がFactionへ一本化された（資金はi64になり、物資が加わった）
+//! - 表示設定に所有勢力とユニット一覧の表示（show_ownership, show_unit_panel）が加わった
+use super::SaveGame;
+use crate::gui::map_gui::{AsciiLod, MapViewOptions};
+use crate::turn::TurnState;
+use model::{
+    Cell, CellType, Direction, Faction, FactionManager, FactionType, Map, MapPosition,
+    Relationship, Resources, Unit, UnitStatus, UnitType,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// バージョン1のゲーム状態一式
+#[derive(Deserialize)]
+pub(super) struct SaveGameV1 {
+    map: MapV1,
+    units: Vec<UnitV1>,
+    factions: FactionManagerV1,
+    turn: TurnState,
+    view_options: MapViewOptionsV1,
+}
+
+#[derive(Deserialize)]
+struct MapV1 {
+    width: u32,
+    height: u32,
+    cells: HashMap<MapPosition, CellV1>,
+}
+
+#[derive(Deserialize)]
+struct CellV1 {
+    cell_type: CellType,
+    faction_id: Option<u32>,
+    elevation: i8,
+    movement_cost_override: Option<u8>,
+}
+
+/// バージョン1の4方向の向き
+#[derive(Deserialize)]
+enum DirectionV1 {
+    N,
+    E,
+    S,
+    W,
+}
+
+#[derive(Deserialize)]
+struct UnitV1 {
+    id: u32,
+    name: String,
+    unit_type: UnitType,
+    faction_id: u32,
+    position: MapPosition,
+    health: u32,
+    max_health: u32,
+    experience: u32,
+    level: u32,
+    status: UnitStatus,
+    facing: DirectionV1,
+    movement_points: u32,
+    attack_bonus: i32,
+    defense_bonus: i32,
+}
+
+#[derive(Deserialize)]
+struct FactionV1 {
+    id: u32,
+    name: String,
+    faction_type: FactionType,
+    color: (u8, u8, u8),
+    _gold: u32, // 経済処理では使われていなかった資金
+    diplomatic_points: u32,
+    relationships: HashMap<u32, Relationship>,
+}
+
+#[derive(Deserialize)]
+struct FactionManagerV1 {
+    factions: HashMap<u32, FactionV1>,
+    relationships: HashMap<(u32, u32), Relationship>,
+    resources: HashMap<u32, Resources>,
+}
+
+#[derive(Deserialize)]
+struct MapViewOptionsV1 {
+    tile_size: u32,
+    scroll_x: i32,
+    scroll_y: i32,
+    zoom: f32,
+    show_grid: bool,
+    viewport_width: u32,
+    viewport_height: u32,
+    use_color: bool,
+    ascii_lod: AsciiLod,
+}
+
+impl From<SaveGameV1> for SaveGame {
+    fn from(save: SaveGameV1) -> Self {
+        Self {
+            map: save.map.into(),
+            units: save.units.into_iter().map(Unit::from).collect(),
+            factions: save.factions.into(),
+            turn: save.turn,
+            view_options: save.view_options.into(),
+        }
+    }
+}
+
+impl From<MapV1> for Map {
+    fn from(map: MapV1) -> Self {
+        let mut migrated = Map::new(map.width, map.height);
+        for (position, cell) in map.cells {
+            // 旧来の都市・拠点はCell::newで平地と構造物の組になる
+            let mut migrated_cell = Cell::new(cell.cell_type);
+            migrated_cell.faction_id = cell.faction_id;
+            migrated_cell.elevation = cell.elevation;
+            migrated_cell.movement_cost_override = cell.movement_cost_override;
+            migrated.set_cell(position, migrated_cell);
+        }
+        migrated
+    }
+}
+
+impl From<DirectionV1> for Direction {
+    fn from(direction: DirectionV1) -> Self {
+        match direction {
+            DirectionV1::N => Direction::N,
+            DirectionV1::E => Direction::E,
+            DirectionV1::S => Direction::S,
+            DirectionV1::W => Direction::W,
+        }
+    }
+}
+
+impl From<UnitV1> for Unit {
+    fn from(unit: UnitV1) -> Self {
+        Self {
+            id: unit.id,
+            name: unit.name,
+            unit_type: unit.unit_type,
+            faction_id: unit.faction_id,
+            position: unit.position,
+            health: unit.health,
+            max_health: unit.max_health,
+            experience: unit.experience,
+            level: unit.level,
+            status: unit.status,
+            facing: unit.facing.into(),
+            movement_points: unit.movement_points,
+            attack_bonus: unit.attack_bonus,
+            defense_bonus: unit.defense_bonus,
+        }
+    }
+}
+
+impl From<FactionManagerV1> for FactionManager {
+    /// 経済処理はマネージャー側の資源を使っていたので、資金と物資はそちらを引き継ぐ
+    /// （資源の記録がない勢力は0）。関係はマネージャー側の表を勢力ごとの関係に重ねる。
+    fn from(manager: FactionManagerV1) -> Self {
+        let mut migrated = FactionManager::new();
+        for (id, faction) in manager.factions {
+            let resources = manager.resources.get(&id).copied().unwrap_or_default();
+            let mut migrated_faction = Faction::new(
+                faction.id,
+                faction.name,
+                faction.faction_type,
+                faction.color,
+            );
+            migrated_faction.gold = resources.gold;
+            migrated_faction.supplies = resources.supplies;
+            migrated_faction.diplomatic_points = faction.diplomatic_points;
+            migrated_faction.relationships = faction.relationships;
+            migrated.add_faction(migrated_faction);
+        }
+        for ((a, b), relationship) in manager.relationships {
+            migrated.set_relationship(a, b, relationship);
+        }
+        migrated
+    }
+}
+
+impl From<MapViewOptionsV1> for MapViewOptions {
+    fn from(options: MapViewOptionsV1) -> Self {
+        Self {
+            tile_size: options.tile_size,
+            scroll_x: options.scroll_x,
+            scroll_y: options.scroll_y,
+            zoom: options.zoom,
+            show_grid: options.show_grid,
+            viewport_width: options.viewport_width,
+            viewport_height: options.viewport_height,
+            use_color: options.use_color,
+            ascii_lod: options.ascii_lod,
+            ..MapViewOptions::default()
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! セッション間で保持するユーザー設定
+//!
+//! 設定はRON形式で保存する。読み込みに失敗した場合や、より新しいバージョンで
+//! 保存されたファイルの場合は警告を出して既定値を使う。
+use crate::gui::map_gui::MapViewOptions;
+use anyhow::Result;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// 現在の設定ファイルのバージョン
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// ユーザー設定
+///
+/// 項目が欠けているファイルは既定値で補って読み込む。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserSettings {
+    pub version: u32,
+    pub view_options: MapViewOptions,
+    pub debug_metrics: bool, // EventBus統計の定期出力
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            view_options: MapViewOptions::default(),
+            debug_metrics: false,
+        }
+    }
+}
+
+impl UserSettings {
+    /// ファイルから設定を読み込む
+    ///
+    /// ファイルがない場合は既定値を返す。壊れたファイルや未対応のバージョンの
+    /// ファイルは警告を出して既定値を返す。ズーム率は表示できる範囲に収める。
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                warn!("設定ファイルを読み込めません: {} ({})", path.display(), e);
+                return Self::default();
+            }
+        };
+
+        match ron::from_str::<UserSettings>(&text) {
+            Ok(settings) if settings.version > SETTINGS_VERSION => {
+                warn!(
+                    "設定ファイルのバージョン{}には対応していません（対応: {}）: {}",
+                    settings.version,
+                    SETTINGS_VERSION,
+                    path.display()
+                );
+                Self::default()
+            }
+            Ok(mut settings) => {
+                settings.version = SETTINGS_VERSION;
+                settings.view_options.clamp_zoom();
+                settings
+            }
+            Err(e) => {
+                warn!("設定ファイルが壊れています: {} ({})", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    /// 設定をファイルに保存
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// テストごとに一意な一時ファイルのパス
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "sl-gem-settings-{}-{}.ron",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn test_settings_round_trip() -> Result<()> {
+        let path = temp_path("round-trip");
+        let mut settings = UserSettings::default();
+        settings.view_options.zoom = 1.5;
+        settings.view_options.scroll_x = 96;
+        settings.view_options.use_color = true;
+        settings.debug_metrics = true;
+
+        settings.save(&path)?;
+        let loaded = UserSettings::load(&path);
+        fs::remove_file(&path).ok();
+
+        assert_eq!(loaded, settings);
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() -> Result<()> {
+        let path = temp_path("partial");
+        fs::write(&path, "(version: 1, view_options: (zoom: 2.0))")?;
+        let loaded = UserSettings::load(&path);
+        fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.view_options.zoom, 2.0);
+        assert_eq!(loaded.view_options.tile_size, 32);
+        assert!(!loaded.debug_metrics);
+        Ok(())
+    }
+
+    #[test]
+    fn test_out_of_range_zoom_is_clamped() -> Result<()> {
+        let path = temp_path("zoom");
+        fs::write(&path, "(version: 1, view_options: (zoom: 50.0))")?;
+        assert_eq!(UserSettings::load(&path).view_options.zoom, 2.0);
+        fs::write(&path, "(version: 1, view_options: (zoom: -3.0))")?;
+        assert_eq!(UserSettings::load(&path).view_options.zoom, 0.25);
+        fs::write(&path, "(version: 1, view_options: (zoom: NaN))")?;
+        assert_eq!(UserSettings::load(&path).view_options.zoom, 1.0);
+        fs::remove_file(&path).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_or_future_file_falls_back_to_defaults() -> Result<()> {
+        let path = temp_path("corrupt");
+        fs::write(&path, "(version: 1, view_options: (zoom: \"大きい\"")?;
+        assert_eq!(UserSettings::load(&path), UserSettings::default());
+
+        fs::write(&path, "(version: 99, debug_metrics: true)")?;
+        assert_eq!(UserSettings::load(&path), UserSettings::default());
+        fs::remove_file(&path).ok();
+
+        // ファイルがない場合も既定値
+        assert_eq!(
+            UserSettings::load(temp_path("missing")),
+            UserSettings::default()
+        );
+        Ok(())
+    }
+}
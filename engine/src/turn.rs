@@ -0,0 +1,513 @@
+//! ターン管理モジュール
+use crate::events::{EventBus, GameEvent};
+use crate::gui::map_gui::{MapGUI, MapViewOptions};
+use crate::save::SaveGame;
+use anyhow::Result;
+use log::warn;
+use model::{FactionManager, Map, Production, Unit};
+use serde::{Deserialize, Serialize};
+
+/// 保存・復元用の手番の状態
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnState {
+    pub faction_order: Vec<u32>,
+    pub current_index: usize,
+    pub turn_number: u32,
+}
+
+/// 手番の終了時に呼ばれる自動保存フック（手番終了後のゲーム状態を受け取る）
+pub type AutosaveHook = Box<dyn FnMut(&AutosaveContext<'_>) -> Result<()> + Send>;
+
+/// 自動保存フックに渡す手番終了後のゲーム状態
+///
+/// 表示設定はゲーム状態の外にあるため、保存するときに呼び出し側が加える。
+pub struct AutosaveContext<'a> {
+    pub map: &'a Map,
+    pub units: &'a [Unit],
+    pub factions: &'a FactionManager,
+    pub turn: TurnState,
+}
+
+impl AutosaveContext<'_> {
+    /// 表示設定を加えて保存するゲーム状態一式を作る
+    pub fn to_save_game(&self, view_options: MapViewOptions) -> SaveGame {
+        SaveGame {
+            map: self.map.clone(),
+            units: self.units.to_vec(),
+            factions: self.factions.clone(),
+            turn: self.turn.clone(),
+            view_options,
+        }
+    }
+}
+
+/// 勢力ごとの手番とターン数を管理する
+pub struct TurnManager {
+    event_bus: EventBus,
+    faction_order: Vec<u32>,
+    current_index: usize,
+    turn_number: u32,
+    autosave: Option<AutosaveHook>,
+}
+
+impl TurnManager {
+    /// 手番順の勢力IDリストからTurnManagerを作成
+    pub fn new(event_bus: EventBus, faction_order: Vec<u32>) -> Result<Self> {
+        if faction_order.is_empty() {
+            return Err(anyhow::anyhow!("勢力が指定されていません"));
+        }
+        Ok(Self {
+            event_bus,
+            faction_order,
+            current_index: 0,
+            turn_number: 1,
+            autosave: None,
+        })
+    }
+
+    /// 保存した状態からTurnManagerを復元
+    pub fn from_state(event_bus: EventBus, state: TurnState) -> Result<Self> {
+        if state.current_index >= state.faction_order.len() {
+            return Err(anyhow::anyhow!(
+                "手番の位置{}が勢力数{}を超えています",
+                state.current_index,
+                state.faction_order.len()
+            ));
+        }
+        Ok(Self {
+            current_index: state.current_index,
+            turn_number: state.turn_number,
+            ..Self::new(event_bus, state.faction_order)?
+        })
+    }
+
+    /// 現在の手番の状態
+    pub fn state(&self) -> TurnState {
+        TurnState {
+            faction_order: self.faction_order.clone(),
+            current_index: self.current_index,
+            turn_number: self.turn_number,
+        }
+    }
+
+    /// 手番の終了ごとに呼ばれる自動保存フックを設定する（Noneで無効）
+    ///
+    /// フックはゲーム状態一式を受け取るend_turn_with_economyから呼ばれる。
+    pub fn set_autosave(&mut self, hook: Option<AutosaveHook>) {
+        self.autosave = hook;
+    }
+
+    /// 現在手番の勢力ID
+    pub fn current_faction(&self) -> u32 {
+        self.faction_order[self.current_index]
+    }
+
+    /// 現在のターン数（全勢力が一巡すると1増える）
+    pub fn turn_number(&self) -> u32 {
+        self.turn_number
+    }
+
+    /// 手番順の勢力IDリスト
+    pub fn faction_order(&self) -> &[u32] {
+        &self.faction_order
+    }
+
+    /// 現在の勢力の手番を終了し、次の勢力の手番を開始する
+    ///
+    /// TurnEndedイベントを発行し、次の勢力に属するユニットの行動力を回復した後、
+    /// TurnStartイベントを発行する。通知の配信失敗は警告にとどめ、手番は進める。
+    /// 次の手番の勢力IDを返す。ユニットしか受け取らないため自動保存は行わない。
+    pub fn end_turn<'a>(&mut self, units: impl IntoIterator<Item = &'a mut Unit>) -> Result<u32> {
+        let ended_faction = self.current_faction();
+        self.event_bus.notify(
+            "turn",
+            GameEvent::TurnEnded {
+                faction_id: ended_faction,
+            },
+        );
+
+        self.current_index += 1;
+        if self.current_index == self.faction_order.len() {
+            self.current_index = 0;
+            self.turn_number += 1;
+        }
+
+        let next_faction = self.current_faction();
+        units
+            .into_iter()
+            .filter(|unit| unit.faction_id == next_faction)
+            .for_each(|unit| unit.reset_for_new_turn());
+
+        self.event_bus.notify(
+            "turn",
+            GameEvent::TurnStart {
+                faction_id: next_faction,
+            },
+        );
+        Ok(next_faction)
+    }
+
+    /// 占領と収入を処理してから手番を終了する
+    ///
+    /// 手番を終える勢力のユニットが敵対勢力または無所属の都市・拠点にいれば占領し、
+    /// CellCapturedイベントを発行する。全勢力が一巡して新しいターンになったときは
+    /// 全勢力に所有セルからの収入を加算する。自動保存フックがあれば最後に
+    /// 手番終了後のゲーム状態を渡して呼び出す（保存の失敗は警告にとどめる）。
+    pub fn end_turn_with_economy(
+        &mut self,
+        map: &mut Map,
+        factions: &mut FactionManager,
+        units: &mut [Unit],
+    ) -> Result<u32> {
+        self.capture_cells(map, factions, units);
+
+        let turn_number = self.turn_number;
+        let next_faction = self.end_turn(units.iter_mut())?;
+        if self.turn_number != turn_number {
+            factions.apply_turn_income(map);
+        }
+
+        if let Some(mut hook) = self.autosave.take() {
+            let context = AutosaveContext {
+                map,
+                units,
+                factions,
+                turn: self.state(),
+            };
+            if let Err(e) = hook(&context) {
+                warn!("自動保存に失敗しました: {}", e);
+            }
+            self.autosave = Some(hook);
+        }
+        Ok(next_faction)
+    }
+
+    /// 現在手番の勢力の生産を1ターン進め、完成したユニットをMapGUIに追加する
+    ///
+    /// 手番開始時（end_turnの後）に呼び出す。追加したユニットのIDを返す。
+    pub fn tick_production(
+        &self,
+        production: &mut Production,
+        map_gui: &mut MapGUI,
+    ) -> Result<Vec<u32>> {
+        let Some(map) = map_gui.get_map() else {
+            return Ok(Vec::new());
+        };
+        let spawned = production.tick(map, self.current_faction(), |pos| {
+            map_gui.get_unit_at_position(pos).is_some()
+        });
+        let ids = spawned.iter().map(|unit| unit.id).collect();
+        map_gui.add_units(spawned)?;
+        Ok(ids)
+    }
+
+    /// 現在手番の勢力のユニットがいる都市・拠点を占領する
+    fn capture_cells(&self, map: &mut Map, factions: &FactionManager, units: &[Unit]) {
+        let faction_id = self.current_faction();
+        for unit in units
+            .iter()
+            .filter(|unit| unit.faction_id == faction_id && unit.health > 0)
+        {
+            let Some(cell) = map.get_cell(&unit.position) else {
+                continue;
+            };
+            if !cell.is_capturable() {
+                continue;
+            }
+            let previous_owner = cell.faction_id;
+            let capturable =
+                previous_owner.is_none_or(|owner| factions.are_hostile(faction_id, owner));
+            if capturable && map.capture_cell(unit.position, faction_id) {
+                self.event_bus.notify(
+                    "turn",
+                    GameEvent::CellCaptured {
+                        position: unit.position,
+                        faction_id,
+                        previous_owner,
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{BackpressurePolicy, TopicConfig};
+    use model::{
+        Cell, CellType, Faction, FactionType, MapPosition, Relationship, Resources, Structure,
+        UnitIdAllocator, UnitType,
+    };
+
+    fn create_units() -> Vec<Unit> {
+        (1..=3)
+            .map(|faction_id| {
+                Unit::new(
+                    faction_id,
+                    format!("ユニット{}", faction_id),
+                    UnitType::Infantry,
+                    faction_id,
+                    MapPosition::new(faction_id as i32, 0),
+                )
+            })
+            .collect()
+    }
+
+    fn exhaust_all(units: &mut [Unit]) {
+        for unit in units.iter_mut() {
+            unit.movement_points = 0;
+        }
+    }
+
+    #[test]
+    fn test_turn_manager_requires_factions() {
+        assert!(TurnManager::new(EventBus::new(), Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_turn_cycle() -> Result<()> {
+        let event_bus = EventBus::new();
+        let receiver = event_bus.subscribe("turn")?;
+        let mut turn_manager = TurnManager::new(event_bus, vec![1, 2, 3])?;
+        let mut units = create_units();
+
+        assert_eq!(turn_manager.current_faction(), 1);
+        assert_eq!(turn_manager.turn_number(), 1);
+
+        let expected = [(2, 1), (3, 1), (1, 2), (2, 2), (3, 2), (1, 3)];
+        for (next_faction, turn_number) in expected {
+            exhaust_all(&mut units);
+            assert_eq!(turn_manager.end_turn(units.iter_mut())?, next_faction);
+            assert_eq!(turn_manager.current_faction(), next_faction);
+            assert_eq!(turn_manager.turn_number(), turn_number);
+
+            // 手番になった勢力のユニットだけ行動力が回復する
+            for unit in &units {
+                let expected_points = if unit.faction_id == next_faction {
+                    UnitType::Infantry.base_movement()
+                } else {
+                    0
+                };
+                assert_eq!(unit.movement_points, expected_points);
+            }
+        }
+
+        // TurnEnded/TurnStartが交互に発行される
+        let events: Vec<_> = receiver.try_iter().map(|e| e.event).collect();
+        assert_eq!(events.len(), 12);
+        assert!(matches!(events[0], GameEvent::TurnEnded { faction_id: 1 }));
+        assert!(matches!(events[1], GameEvent::TurnStart { faction_id: 2 }));
+        assert!(matches!(events[4], GameEvent::TurnEnded { faction_id: 3 }));
+        assert!(matches!(events[5], GameEvent::TurnStart { faction_id: 1 }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_end_turn_with_economy() -> Result<()> {
+        let event_bus = EventBus::new();
+        let receiver = event_bus.subscribe("turn")?;
+        let mut turn_manager = TurnManager::new(event_bus, vec![1, 2])?;
+
+        let mut factions = FactionManager::new();
+        for id in 1..=2 {
+            let mut faction =
+                Faction::new(id, format!("勢力{}", id), FactionType::Player, (0, 0, 0));
+            faction.gold = 0;
+            factions.add_faction(faction);
+        }
+        factions.set_relationship(1, 2, Relationship::AtWar);
+
+        let city = MapPosition::new(2, 0);
+        let mut map = Map::new(5, 5);
+        map.set_cell(
+            city,
+            Cell::with_faction(CellType::Plain, 2).with_structure(Structure::City { level: 1 }),
+        );
+        map.set_cell(
+            MapPosition::new(0, 0),
+            Cell::with_faction(CellType::Plain, 1).with_structure(Structure::Base),
+        );
+        let mut units = vec![Unit::new(
+            1,
+            "歩兵".to_string(),
+            UnitType::Infantry,
+            1,
+            city,
+        )];
+
+        // 勢力1の手番終了時に敵の都市を占領する
+        turn_manager.end_turn_with_economy(&mut map, &mut factions, &mut units)?;
+        assert_eq!(map.get_cell(&city).unwrap().faction_id, Some(1));
+        let captured: Vec<_> = receiver
+            .try_iter()
+            .filter(|e| matches!(e.event, GameEvent::CellCaptured { .. }))
+            .collect();
+        assert_eq!(captured.len(), 1);
+        assert!(matches!(
+            captured[0].event,
+            GameEvent::CellCaptured {
+                faction_id: 1,
+                previous_owner: Some(2),
+                ..
+            }
+        ));
+        assert_eq!(factions.resources(1), Resources::default());
+
+        // 一巡すると収入が加算される（占領した都市の分も含む）
+        turn_manager.end_turn_with_economy(&mut map, &mut factions, &mut units)?;
+        assert_eq!(factions.resources(1), Resources::new(15, 0));
+        assert_eq!(factions.resources(2), Resources::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tick_production_adds_units() -> Result<()> {
+        let mut turn_manager = TurnManager::new(EventBus::new(), vec![1, 2])?;
+        let mut factions = FactionManager::new();
+        factions.add_faction(Faction::new(
+            2,
+            "勢力2".to_string(),
+            FactionType::Player,
+            (0, 0, 0),
+        ));
+
+        let base = MapPosition::new(1, 1);
+        let mut map = Map::new(3, 3);
+        map.set_cell(
+            base,
+            Cell::with_faction(CellType::Plain, 2).with_structure(Structure::Base),
+        );
+        let mut map_gui = MapGUI::new(EventBus::new());
+        map_gui.set_map(map.clone());
+        map_gui.add_unit(Unit::new(
+            1,
+            "歩兵".to_string(),
+            UnitType::Infantry,
+            2,
+            MapPosition::new(1, 0),
+        ))?;
+
+        let mut production = Production::new(UnitIdAllocator::new(2));
+        production.enqueue(&map, &mut factions, base, UnitType::Infantry)?;
+
+        // 勢力1の手番では勢力2の生産は進まない
+        assert!(turn_manager
+            .tick_production(&mut production, &mut map_gui)?
+            .is_empty());
+
+        turn_manager.end_turn(map_gui.units_mut())?;
+        assert_eq!(
+            turn_manager.tick_production(&mut production, &mut map_gui)?,
+            vec![2]
+        );
+        // 上は塞がっているので右に配置される
+        let spawned = map_gui.get_unit(2).unwrap();
+        assert_eq!(spawned.position, MapPosition::new(2, 1));
+        assert_eq!(spawned.faction_id, 2);
+        Ok(())
+    }
+
+    fn create_world() -> (Map, FactionManager) {
+        let mut factions = FactionManager::new();
+        for id in 1..=2 {
+            let mut faction =
+                Faction::new(id, format!("勢力{}", id), FactionType::Player, (0, 0, 0));
+            faction.gold = 0;
+            factions.add_faction(faction);
+        }
+        let mut map = Map::new(5, 5);
+        map.set_cell(
+            MapPosition::new(0, 0),
+            Cell::with_faction(CellType::Plain, 1).with_structure(Structure::Base),
+        );
+        (map, factions)
+    }
+
+    #[test]
+    fn test_autosave_hook_and_restore() -> Result<()> {
+        let mut turn_manager = TurnManager::new(EventBus::new(), vec![1, 2])?;
+        let saved = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = saved.clone();
+        turn_manager.set_autosave(Some(Box::new(move |context: &AutosaveContext<'_>| {
+            sink.lock()
+                .unwrap()
+                .push(context.to_save_game(MapViewOptions::default()));
+            Ok(())
+        })));
+        let (mut map, mut factions) = create_world();
+        let mut units = create_units();
+        turn_manager.end_turn_with_economy(&mut map, &mut factions, &mut units)?;
+        turn_manager.end_turn_with_economy(&mut map, &mut factions, &mut units)?;
+
+        // 保存の失敗は手番の進行を妨げない
+        turn_manager.set_autosave(Some(Box::new(|_: &AutosaveContext<'_>| {
+            Err(anyhow::anyhow!("書き込み失敗"))
+        })));
+        assert_eq!(
+            turn_manager.end_turn_with_economy(&mut map, &mut factions, &mut units)?,
+            2
+        );
+
+        // 手番終了後（収入の加算後）のゲーム状態一式が渡される
+        let saved = saved.lock().unwrap();
+        assert_eq!(saved.len(), 2);
+        assert_eq!(saved[1].turn.turn_number, 2);
+        assert_eq!(saved[1].turn.current_index, 0);
+        assert_eq!(saved[1].units.len(), units.len());
+        assert_eq!(
+            saved[1].map.get_cell(&MapPosition::new(0, 0)),
+            map.get_cell(&MapPosition::new(0, 0))
+        );
+        assert_eq!(saved[0].factions.resources(1), Resources::default());
+        assert_eq!(saved[1].factions.resources(1), factions.resources(1));
+        assert!(saved[1].factions.resources(1).gold > 0);
+
+        let restored = TurnManager::from_state(EventBus::new(), saved[0].turn.clone())?;
+        assert_eq!(restored.current_faction(), 2);
+        assert_eq!(restored.turn_number(), 1);
+        let broken = TurnState {
+            current_index: 2,
+            ..saved[0].turn.clone()
+        };
+        assert!(TurnManager::from_state(EventBus::new(), broken).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_full_subscriber_does_not_abort_end_turn() -> Result<()> {
+        let event_bus = EventBus::new();
+        event_bus.create_topic(
+            "turn",
+            TopicConfig {
+                capacity: 1,
+                policy: BackpressurePolicy::DropNewest,
+            },
+        )?;
+        let receiver = event_bus.subscribe("turn")?;
+        let mut turn_manager = TurnManager::new(event_bus.clone(), vec![1, 2])?;
+        let saved = std::sync::Arc::new(std::sync::Mutex::new(0));
+        let sink = saved.clone();
+        turn_manager.set_autosave(Some(Box::new(move |_: &AutosaveContext<'_>| {
+            *sink.lock().unwrap() += 1;
+            Ok(())
+        })));
+
+        // 購読者のキューが満杯でも手番は進み、自動保存も行われる
+        let (mut map, mut factions) = create_world();
+        let mut units = create_units();
+        assert_eq!(
+            turn_manager.end_turn_with_economy(&mut map, &mut factions, &mut units)?,
+            2
+        );
+        assert_eq!(
+            turn_manager.end_turn_with_economy(&mut map, &mut factions, &mut units)?,
+            1
+        );
+        assert_eq!(turn_manager.turn_number(), 2);
+        assert_eq!(*saved.lock().unwrap(), 2);
+        assert_eq!(receiver.try_iter().count(), 1);
+        assert_eq!(event_bus.stats().topic("turn").dropped, 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+//! forward_logsの結合テスト
+//!
+//! プロセス全体のロガーを登録するため、ライブラリのテストとは別のプロセスで実行する。
+use engine::{forward_logs, EventBus, EventRecorder, GameEvent, LogLevel, LOG_TOPIC};
+
+#[test]
+fn test_forward_logs() -> anyhow::Result<()> {
+    assert!(LogLevel::Info < LogLevel::Warning && LogLevel::Warning < LogLevel::Error);
+
+    let event_bus = EventBus::new();
+    let receiver = event_bus.subscribe(LOG_TOPIC)?;
+    forward_logs(&event_bus, LogLevel::Warning)?;
+    // ロガーは一度しか登録できない
+    assert!(forward_logs(&event_bus, LogLevel::Info).is_err());
+
+    log::warn!("転送テストの警告");
+    log::info!("転送テストの情報");
+    log::error!("転送テストのエラー");
+
+    // ライブラリ内部のログも届くため、このテストのメッセージだけを取り出す
+    let received: Vec<(String, LogLevel)> = receiver
+        .try_iter()
+        .filter_map(|event| match event.event {
+            GameEvent::Log { message, level } if message.starts_with("転送テスト") => {
+                Some((message, level))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        received,
+        vec![
+            ("転送テストの警告".to_string(), LogLevel::Warning),
+            ("転送テストのエラー".to_string(), LogLevel::Error),
+        ]
+    );
+
+    // 記録中に警告を出す観測フックがあってもデッドロックしない
+    // （書き込めない/dev/fullへ記録し、記録のたびに警告を出させる）
+    #[cfg(target_os = "linux")]
+    {
+        let recorder = EventRecorder::create("/dev/full")?;
+        recorder.attach(&event_bus);
+        event_bus.publish("turn", GameEvent::TurnStart { faction_id: 1 })?;
+        let warnings = receiver
+            .try_iter()
+            .filter(|event| {
+                matches!(&event.event, GameEvent::Log { message, .. }
+                    if message.starts_with("記録ファイルへの書き込みに失敗しました"))
+            })
+            .count();
+        assert_eq!(warnings, 1);
+    }
+    Ok(())
+}
@@ -1,43 +1,92 @@
+mod scenario;
+
 use anyhow::Result;
-use engine::gui::map_gui::{MapGUI, MapViewOptions};
-use engine::{Engine, GameEvent, LoopConfig};
-use log::{info, LevelFilter};
-use model::{Cell, CellType, Faction, FactionType, Map, MapPosition, Unit, UnitType};
+use crossbeam_channel::Receiver;
+use engine::gui::map_gui::{AsciiLod, MapGUI, MapViewOptions};
+use engine::input::{InputEvent, KeyAction, KeyBindings};
+use engine::{Engine, GameEvent, LoopConfig, PrioritizedEvent, UserSettings};
+use log::{info, warn, LevelFilter};
+use model::{
+    Cell, Faction, FactionType, Map, MapGenerator, MapPosition, Structure, Unit, UnitType,
+};
 use rand::{thread_rng, Rng};
+use scenario::Scenario;
+use std::cell::RefCell;
+use std::io::IsTerminal;
+use std::rc::Rc;
 use std::{thread, time::Duration};
 
+/// --restore-session指定時に読み書きする設定ファイル
+const SESSION_SETTINGS_PATH: &str = "sl_gem_settings.ron";
+
+/// ゲームループを自動で停止するまでの時間
+const DEMO_LOOP_DURATION: Duration = Duration::from_millis(500);
+
+/// ゲームパッドで操作できるときにゲームループを自動で停止するまでの時間
+#[cfg(feature = "gamepad")]
+const GAMEPAD_LOOP_DURATION: Duration = Duration::from_secs(60);
+
+/// ゲームパッドの入力を固定時間ステップごとに読み取ってMapGUIへ適用し、
+/// 表示が変わったフレームでマップを描画する
+///
+/// ゲームパッドを初期化できなければ警告を出してfalseを返す。
+#[cfg(feature = "gamepad")]
+fn attach_gamepad(game_loop: &mut engine::GameLoop, map_gui: Rc<RefCell<MapGUI>>) -> bool {
+    use engine::gamepad::{GamepadInput, GamepadPoller};
+
+    let mut poller = match GamepadPoller::new() {
+        Ok(poller) => poller,
+        Err(e) => {
+            warn!("ゲームパッドを使用できません: {}", e);
+            return false;
+        }
+    };
+    let mut input = GamepadInput::new();
+    let gui = map_gui.clone();
+    game_loop.set_on_update(move |delta| {
+        let mut map_gui = gui.borrow_mut();
+        let mut actions = poller.poll(&mut input);
+        let zoom = map_gui.get_view_options().zoom;
+        actions.extend(input.update(Duration::from_secs_f32(delta), zoom));
+        for action in actions {
+            if let Err(e) = map_gui.apply_input(action) {
+                warn!("ゲームパッドの入力を処理できません: {}", e);
+            }
+        }
+        Ok(())
+    });
+    let gui = map_gui.clone();
+    game_loop.set_render_gate(move || gui.borrow_mut().take_dirty());
+    game_loop.set_on_render(move || {
+        println!("{}", map_gui.borrow().render_ascii());
+        Ok(())
+    });
+    true
+}
+
 /// サンプルマップを作成
 fn create_demo_map() -> Map {
-    let width = 20;
-    let height = 15;
-    let mut map = Map::new(width, height);
     let mut rng = thread_rng();
+    let seed = rng.gen();
+    info!("マップシード: {}", seed);
 
-    // ランダムなマップを生成
-    for x in 0..width as i32 {
-        for y in 0..height as i32 {
-            let position = MapPosition::new(x, y);
-            let cell_type = match rng.gen_range(0..100) {
-                0..=60 => CellType::Plain,
-                61..=75 => CellType::Forest,
-                76..=85 => CellType::Mountain,
-                86..=90 => CellType::Road,
-                91..=95 => CellType::Water,
-                _ => CellType::City,
-            };
+    let mut map = MapGenerator::new(seed).with_size(40, 30).generate();
 
-            let cell = if cell_type == CellType::City {
-                // 都市は20%の確率で勢力に所属
-                if rng.gen_range(0..100) < 20 {
-                    Cell::with_faction(cell_type, rng.gen_range(1..=3))
-                } else {
-                    Cell::new(cell_type)
-                }
-            } else {
-                Cell::new(cell_type)
+    // 都市は20%の確率で勢力に所属
+    for x in 0..map.width as i32 {
+        for y in 0..map.height as i32 {
+            let position = MapPosition::new(x, y);
+            let Some(cell) = map.get_cell(&position) else {
+                continue;
             };
-
-            map.set_cell(position, cell);
+            if matches!(cell.structure, Some(Structure::City { .. })) && rng.gen_range(0..100) < 20
+            {
+                let owned = Cell {
+                    faction_id: Some(rng.gen_range(1..=3)),
+                    ..cell.clone()
+                };
+                map.set_cell(position, owned);
+            }
         }
     }
 
@@ -45,7 +94,6 @@ fn create_demo_map() -> Map {
 }
 
 /// サンプル勢力を作成
-#[allow(dead_code)]
 fn create_demo_factions() -> Vec<Faction> {
     vec![
         Faction::new(
@@ -111,8 +159,119 @@ fn create_demo_units() -> Vec<Unit> {
     units
 }
 
+/// `--name value`形式のコマンドライン引数の値を取得
+fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != name);
+    args.next()?;
+    args.next()
+}
+
+/// MapGUIのイベントを表示用の文字列に変換
+fn describe_map_event(event: &GameEvent) -> Option<String> {
+    match event {
+        GameEvent::UnitSelected { unit_id } => Some(format!("ユニット選択: ID {}", unit_id)),
+        GameEvent::PositionSelected { position } => {
+            Some(format!("セル選択: ({}, {})", position.x, position.y))
+        }
+        GameEvent::UnitMoved { unit_id, from, to } => Some(format!(
+            "ユニット移動: ID {} ({}, {}) -> ({}, {})",
+            unit_id, from.x, from.y, to.x, to.y
+        )),
+        GameEvent::UnitAttacked {
+            attacker,
+            defender,
+            damage,
+        } => Some(format!(
+            "攻撃: ID {} -> ID {} ({}ダメージ)",
+            attacker, defender, damage
+        )),
+        GameEvent::MoveRequested { unit_id, to } => {
+            Some(format!("移動要求: ID {} -> ({}, {})", unit_id, to.x, to.y))
+        }
+        GameEvent::TurnEnded { faction_id } => Some(format!("ターン終了: 勢力 {}", faction_id)),
+        _ => None,
+    }
+}
+
+/// キー割り当てを読み込む（--keys指定時はそのファイルで組み込みの配置を上書き）
+fn load_key_bindings() -> Result<KeyBindings> {
+    let bindings = match arg_value("--keys") {
+        Some(path) => KeyBindings::load(path)?,
+        None => KeyBindings::default(),
+    };
+    for (combo, actions) in bindings.conflicts() {
+        let names: Vec<String> = actions.iter().map(KeyAction::name).collect();
+        warn!(
+            "キー{:?}が複数の操作に割り当てられています: {}",
+            combo,
+            names.join(", ")
+        );
+    }
+    Ok(bindings)
+}
+
+/// マップ編集モードのデモ（--editor指定時）
+///
+/// 各操作に割り当てられたキーの入力とクリックを模擬して道路を引き、
+/// 取り消しとやり直しを行う。
+fn run_editor_demo(
+    engine: &mut Engine,
+    map_gui: &mut MapGUI,
+    map_events: &Receiver<PrioritizedEvent>,
+    bindings: &KeyBindings,
+) -> Result<()> {
+    let press = |map_gui: &mut MapGUI, event: InputEvent| -> Result<()> {
+        if let Some(action) = bindings.translate(&event) {
+            map_gui.apply_input(action)?;
+        }
+        Ok(())
+    };
+    // 操作に割り当てられた最初のキーを押す（割り当てがなければ何もしない）
+    let press_action = |map_gui: &mut MapGUI, action: KeyAction| -> Result<()> {
+        match bindings.combos(action).first() {
+            Some(combo) => press(
+                map_gui,
+                InputEvent::KeyPressedWith(combo.key, combo.modifiers),
+            ),
+            None => {
+                warn!("{}にキーが割り当てられていません", action.name());
+                Ok(())
+            }
+        }
+    };
+
+    // 編集モードにして道路を選択し、クリックした位置を塗る
+    press_action(map_gui, KeyAction::ToggleEditor)?;
+    press_action(map_gui, KeyAction::SelectTerrain(5))?;
+    for x in 1..=8 {
+        let (screen_x, screen_y) = map_gui.map_to_screen_position(x, 3);
+        press(
+            map_gui,
+            InputEvent::MouseClicked {
+                x: screen_x,
+                y: screen_y,
+            },
+        )?;
+    }
+    print_map_info(engine, map_gui, map_events);
+    println!("編集モード: 道路を引きました。1秒後に最後の1マスを取り消します...");
+    thread::sleep(Duration::from_secs(1));
+
+    press_action(map_gui, KeyAction::Undo)?;
+    print_map_info(engine, map_gui, map_events);
+    println!("取り消しました。1秒後にやり直します...");
+    thread::sleep(Duration::from_secs(1));
+
+    press_action(map_gui, KeyAction::Redo)?;
+    press_action(map_gui, KeyAction::ToggleEditor)?;
+    print_map_info(engine, map_gui, map_events);
+    println!("やり直しました。編集モードを終了します...");
+    thread::sleep(Duration::from_secs(1));
+    Ok(())
+}
+
 /// マップの状態をコンソールに表示（固定位置に表示）
-fn print_map_info(engine: &Engine, map_gui: &MapGUI) {
+fn print_map_info(engine: &mut Engine, map_gui: &MapGUI, map_events: &Receiver<PrioritizedEvent>) {
     // ANSIエスケープシーケンスを使用して画面をクリアし、カーソルを先頭に移動
     print!("\x1B[2J\x1B[H");
 
@@ -167,6 +326,16 @@ fn print_map_info(engine: &Engine, map_gui: &MapGUI) {
         view_options.scroll_x, view_options.scroll_y
     );
 
+    // MapGUIから届いたイベントを表示
+    for event in map_events.try_iter() {
+        if let Some(line) = describe_map_event(&event.event) {
+            println!("イベント: {}", line);
+        }
+    }
+
+    // --debug-metrics指定時はEventBusの統計をログ出力
+    engine.report_metrics();
+
     // 標準出力をフラッシュして即座に表示を反映
     std::io::Write::flush(&mut std::io::stdout()).unwrap();
 }
@@ -185,80 +354,120 @@ fn main() -> Result<()> {
     // エンジンの初期化
     let mut engine = Engine::new();
     let event_bus = engine.event_bus();
+    // --restore-session指定時は前回の表示設定を復元する
+    let restore_session = std::env::args().any(|arg| arg == "--restore-session");
+    let settings = if restore_session {
+        info!("前回の設定を読み込みます: {}", SESSION_SETTINGS_PATH);
+        Some(UserSettings::load(SESSION_SETTINGS_PATH))
+    } else {
+        None
+    };
+
+    // --debug-metrics指定時はイベントバスの統計を定期的にログ出力
+    let debug_metrics = settings.as_ref().is_some_and(|s| s.debug_metrics);
+    if debug_metrics || std::env::args().any(|arg| arg == "--debug-metrics") {
+        engine.set_debug_metrics(true);
+    }
 
     // システムイベントの購読
     let receiver = engine.subscribe("system")?;
 
+    // MapGUIイベントの購読（マップ更新通知は別トピックに発行される）
+    let map_events = event_bus.subscribe("map_gui")?;
+
     // MapGUIの初期化
     let mut map_gui = MapGUI::new(event_bus.clone());
     info!("MapGUIを初期化しました");
 
-    // サンプルマップとユニットを設定
-    let map = create_demo_map();
+    // --scenario指定時はシナリオファイルから、それ以外はサンプルのマップとユニットを設定
+    let (map, factions, units) = match arg_value("--scenario") {
+        Some(path) => {
+            let scenario = Scenario::load(&path)?;
+            info!("シナリオを読み込みました: {} ({})", scenario.name, path);
+            scenario.instantiate()
+        }
+        None => (
+            create_demo_map(),
+            create_demo_factions(),
+            create_demo_units(),
+        ),
+    };
     map_gui.set_map(map);
-    info!("サンプルマップを生成しました");
-
-    let units = create_demo_units();
-    for unit in units {
-        map_gui.add_unit(unit);
+    for faction in &factions {
+        info!("勢力{}: {}", faction.id, faction.name);
     }
-    info!("サンプルユニットを配置しました");
-
-    // マップの表示設定を調整
-    let view_options = MapViewOptions {
-        tile_size: 32,
-        scroll_x: 0,
-        scroll_y: 0,
-        zoom: 1.2,
-        show_grid: true,
-        viewport_width: 20,
-        viewport_height: 15,
+
+    // ランダム配置のため位置が重なったユニットは配置しない
+    map_gui.batch(|gui| {
+        for unit in units {
+            if let Err(e) = gui.add_unit(unit) {
+                warn!("ユニットを配置できませんでした: {}", e);
+            }
+        }
+    });
+    info!("マップとユニットを配置しました");
+
+    // マップの表示設定を調整（復元した設定があればそれを使う）
+    let view_options = match &settings {
+        Some(settings) => settings.view_options.clone(),
+        None => MapViewOptions {
+            tile_size: 32,
+            scroll_x: 0,
+            scroll_y: 0,
+            zoom: 1.2,
+            show_grid: true,
+            viewport_width: 20,
+            viewport_height: 15,
+            use_color: std::io::stdout().is_terminal(),
+            ascii_lod: AsciiLod::Auto,
+            show_ownership: true,
+            show_unit_panel: true,
+        },
     };
     map_gui.set_view_options(view_options);
 
     // ゲームループの設定
     let config = LoopConfig::default();
     let mut game_loop = engine::GameLoop::new(config, receiver);
+    // --debug-metrics指定時はフレームの処理時間もメトリクスに含める
+    engine.set_frame_stats(game_loop.frame_stats());
 
     // エンジンの起動
     engine.run()?;
 
     // 初期マップ情報を表示
-    print_map_info(&engine, &map_gui);
+    print_map_info(&mut engine, &map_gui, &map_events);
     println!("自動スクロールデモを開始します。1秒後に移動を開始します...");
     thread::sleep(Duration::from_secs(1));
 
     // マップのある位置を選択
     let pos = MapPosition::new(5, 5);
     if let Err(e) = map_gui.select_position(pos) {
-        println!("位置選択でエラー: {}", e);
+        warn!("位置選択でエラー: {}", e);
     } else {
         // 選択した位置の周囲をハイライト表示（移動可能範囲のシミュレーション）
-        let highlights = vec![
-            pos.moved(1, 0),
-            pos.moved(-1, 0),
-            pos.moved(0, 1),
-            pos.moved(0, -1),
-            pos.moved(1, 1),
-            pos.moved(-1, -1),
-            pos.moved(1, -1),
-            pos.moved(-1, 1),
-        ];
-        map_gui.highlight_positions(highlights);
+        map_gui.highlight_neighbors(pos, true);
     }
 
     // 選択状態を表示
-    print_map_info(&engine, &map_gui);
+    print_map_info(&mut engine, &map_gui, &map_events);
     println!("位置(5, 5)を選択しました。1秒後に自動スクロールを開始します...");
     thread::sleep(Duration::from_secs(1));
 
+    // --editor指定時はマップ編集モードのデモを行う
+    if std::env::args().any(|arg| arg == "--editor") {
+        let bindings = load_key_bindings()?;
+        map_gui.clear_selection();
+        run_editor_demo(&mut engine, &mut map_gui, &map_events, &bindings)?;
+    }
+
     // 自動スクロールデモ: 縦に5回、横に2回、上に3回
     println!("自動スクロールを開始します...");
 
     // 縦に5回スクロール（下方向）
     for i in 1..=5 {
         map_gui.scroll(0, 30);
-        print_map_info(&engine, &map_gui);
+        print_map_info(&mut engine, &map_gui, &map_events);
         println!("縦方向スクロール {}/5", i);
         thread::sleep(Duration::from_secs(1));
     }
@@ -266,7 +475,7 @@ fn main() -> Result<()> {
     // 横に2回スクロール（右方向）
     for i in 1..=2 {
         map_gui.scroll(30, 0);
-        print_map_info(&engine, &map_gui);
+        print_map_info(&mut engine, &map_gui, &map_events);
         println!("横方向スクロール {}/2", i);
         thread::sleep(Duration::from_secs(1));
     }
@@ -274,21 +483,36 @@ fn main() -> Result<()> {
     // 上に3回スクロール（上方向）
     for i in 1..=3 {
         map_gui.scroll(0, -30);
-        print_map_info(&engine, &map_gui);
+        print_map_info(&mut engine, &map_gui, &map_events);
         println!("上方向スクロール {}/3", i);
         thread::sleep(Duration::from_secs(1));
     }
 
     // ズームしてみる
     map_gui.zoom(1.5);
-    print_map_info(&engine, &map_gui);
+    print_map_info(&mut engine, &map_gui, &map_events);
     println!("マップをズームしました。デモを終了します...");
     thread::sleep(Duration::from_secs(1));
 
-    // 別スレッドでStopイベントを送信（5秒後）
+    // gamepadフィーチャー有効時はゲームループ中にゲームパッドでマップを操作できる
+    let map_gui = Rc::new(RefCell::new(map_gui));
+    #[cfg(feature = "gamepad")]
+    let loop_duration = if attach_gamepad(&mut game_loop, map_gui.clone()) {
+        println!(
+            "ゲームパッドでマップを操作できます（{}秒後に終了します）。",
+            GAMEPAD_LOOP_DURATION.as_secs()
+        );
+        GAMEPAD_LOOP_DURATION
+    } else {
+        DEMO_LOOP_DURATION
+    };
+    #[cfg(not(feature = "gamepad"))]
+    let loop_duration = DEMO_LOOP_DURATION;
+
+    // 別スレッドでStopイベントを送信
     let event_bus_clone = event_bus.clone();
     thread::spawn(move || {
-        thread::sleep(Duration::from_millis(500));
+        thread::sleep(loop_duration);
         event_bus_clone.publish("system", GameEvent::Stop).unwrap();
     });
 
@@ -297,6 +521,19 @@ fn main() -> Result<()> {
         Ok(_) => info!("ゲームループが正常に終了しました。"),
         Err(e) => log::error!("ゲームループでエラーが発生しました: {}", e),
     }
+    let map_gui = map_gui.borrow();
+
+    // 次回の起動のために表示設定を保存
+    if restore_session {
+        let settings = UserSettings {
+            view_options: map_gui.get_view_options().clone(),
+            debug_metrics: engine.is_debug_metrics(),
+            ..UserSettings::default()
+        };
+        if let Err(e) = settings.save(SESSION_SETTINGS_PATH) {
+            warn!("設定を保存できませんでした: {}", e);
+        }
+    }
 
     println!("\nゲーム終了。");
     Ok(())
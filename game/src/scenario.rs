@@ -0,0 +1,410 @@
+//! シナリオファイルの読み込み
+//!
+//! マップ（シード指定の自動生成または埋め込み）、勢力と勢力間の関係、
+//! 初期ユニットをRON形式で記述したシナリオを読み込む。
+use anyhow::{Context, Result};
+use model::{
+    Cell, CellType, Faction, FactionType, Map, MapGenerator, MapPosition, Relationship, Structure,
+    Unit, UnitType,
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+/// シナリオのマップ定義
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum MapSource {
+    /// シード指定で自動生成する
+    Generated { seed: u64, width: u32, height: u32 },
+    /// 1文字1セルで記述する
+    /// （`.`平地 `F`森 `M`山 `~`水域 `=`道路 `C`都市 `B`拠点 `x`廃墟）
+    Embedded { rows: Vec<String> },
+}
+
+/// シナリオの勢力定義
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FactionDef {
+    pub id: u32,
+    pub name: String,
+    pub faction_type: FactionType,
+    pub color: (u8, u8, u8),
+}
+
+/// シナリオの勢力間関係
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RelationshipDef {
+    pub a: u32,
+    pub b: u32,
+    pub relationship: Relationship,
+}
+
+/// シナリオの初期ユニット
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct UnitDef {
+    pub id: u32,
+    pub name: String,
+    pub unit_type: UnitType,
+    pub faction_id: u32,
+    pub position: MapPosition,
+}
+
+/// シナリオ開始時のセル所有
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OwnershipDef {
+    pub position: MapPosition,
+    pub faction_id: u32,
+}
+
+/// シナリオ
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub map: MapSource,
+    pub factions: Vec<FactionDef>,
+    #[serde(default)]
+    pub relationships: Vec<RelationshipDef>,
+    #[serde(default)]
+    pub ownership: Vec<OwnershipDef>,
+    pub units: Vec<UnitDef>,
+}
+
+/// シナリオの検証エラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScenarioError {
+    /// マップの幅または高さが0
+    EmptyMap,
+    /// 埋め込みマップの行の長さが揃っていない
+    RaggedMapRow { row: usize },
+    /// 埋め込みマップに未知の地形文字がある
+    UnknownTerrain { row: usize, symbol: char },
+    /// 勢力IDが重複している
+    DuplicateFactionId(u32),
+    /// 定義されていない勢力IDが参照されている
+    UnknownFaction(u32),
+    /// ユニットIDが重複している
+    DuplicateUnitId(u32),
+    /// ユニットの位置がマップ外
+    UnitOutOfBounds { unit_id: u32, position: MapPosition },
+    /// 所有セルの位置がマップ外
+    OwnershipOutOfBounds(MapPosition),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::EmptyMap => write!(f, "マップの大きさが0です"),
+            ScenarioError::RaggedMapRow { row } => {
+                write!(f, "マップの{}行目の長さが他の行と異なります", row + 1)
+            }
+            ScenarioError::UnknownTerrain { row, symbol } => {
+                write!(
+                    f,
+                    "マップの{}行目に未知の地形 '{}' があります",
+                    row + 1,
+                    symbol
+                )
+            }
+            ScenarioError::DuplicateFactionId(id) => write!(f, "勢力IDが重複しています: {}", id),
+            ScenarioError::UnknownFaction(id) => write!(f, "未定義の勢力IDです: {}", id),
+            ScenarioError::DuplicateUnitId(id) => write!(f, "ユニットIDが重複しています: {}", id),
+            ScenarioError::UnitOutOfBounds { unit_id, position } => {
+                write!(f, "ユニット{}の位置{:?}がマップ外です", unit_id, position)
+            }
+            ScenarioError::OwnershipOutOfBounds(position) => {
+                write!(f, "所有セルの位置{:?}がマップ外です", position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+/// 地形文字をセルに変換（都市・拠点・廃墟は平地の上の構造物）
+fn cell_from_symbol(symbol: char) -> Option<Cell> {
+    let cell_type = match symbol {
+        '.' | 'C' | 'B' | 'x' => CellType::Plain,
+        'F' => CellType::Forest,
+        'M' => CellType::Mountain,
+        '~' => CellType::Water,
+        '=' => CellType::Road,
+        _ => return None,
+    };
+    let cell = Cell::new(cell_type);
+    Some(match symbol {
+        'C' => cell.with_structure(Structure::City { level: 1 }),
+        'B' => cell.with_structure(Structure::Base),
+        'x' => cell.with_structure(Structure::Ruins),
+        _ => cell,
+    })
+}
+
+impl Scenario {
+    /// シナリオファイルを読み込んで検証する
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("シナリオを読み込めません: {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("不正なシナリオです: {}", path.display()))
+    }
+
+    /// RON文字列からシナリオを読み込んで検証する
+    pub fn parse(text: &str) -> Result<Self> {
+        let scenario: Scenario = ron::from_str(text)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// マップの大きさ
+    pub fn map_size(&self) -> (u32, u32) {
+        match &self.map {
+            MapSource::Generated { width, height, .. } => (*width, *height),
+            MapSource::Embedded { rows } => (
+                rows.first().map_or(0, |row| row.chars().count() as u32),
+                rows.len() as u32,
+            ),
+        }
+    }
+
+    /// シナリオの整合性を検証する
+    pub fn validate(&self) -> Result<(), ScenarioError> {
+        let (width, height) = self.map_size();
+        if width == 0 || height == 0 {
+            return Err(ScenarioError::EmptyMap);
+        }
+        if let MapSource::Embedded { rows } = &self.map {
+            for (row, line) in rows.iter().enumerate() {
+                if line.chars().count() as u32 != width {
+                    return Err(ScenarioError::RaggedMapRow { row });
+                }
+                if let Some(symbol) = line.chars().find(|&c| cell_from_symbol(c).is_none()) {
+                    return Err(ScenarioError::UnknownTerrain { row, symbol });
+                }
+            }
+        }
+        let in_bounds = |pos: &MapPosition| {
+            pos.x >= 0 && pos.y >= 0 && pos.x < width as i32 && pos.y < height as i32
+        };
+
+        let mut faction_ids = HashSet::new();
+        for faction in &self.factions {
+            if !faction_ids.insert(faction.id) {
+                return Err(ScenarioError::DuplicateFactionId(faction.id));
+            }
+        }
+        let check_faction = |id: u32| {
+            if faction_ids.contains(&id) {
+                Ok(())
+            } else {
+                Err(ScenarioError::UnknownFaction(id))
+            }
+        };
+        for relationship in &self.relationships {
+            check_faction(relationship.a)?;
+            check_faction(relationship.b)?;
+        }
+        for ownership in &self.ownership {
+            check_faction(ownership.faction_id)?;
+            if !in_bounds(&ownership.position) {
+                return Err(ScenarioError::OwnershipOutOfBounds(ownership.position));
+            }
+        }
+
+        let mut unit_ids = HashSet::new();
+        for unit in &self.units {
+            if !unit_ids.insert(unit.id) {
+                return Err(ScenarioError::DuplicateUnitId(unit.id));
+            }
+            check_faction(unit.faction_id)?;
+            if !in_bounds(&unit.position) {
+                return Err(ScenarioError::UnitOutOfBounds {
+                    unit_id: unit.id,
+                    position: unit.position,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// シナリオからマップ・勢力・ユニットを生成する（検証済みであること）
+    pub fn instantiate(&self) -> (Map, Vec<Faction>, Vec<Unit>) {
+        let mut map = match &self.map {
+            MapSource::Generated {
+                seed,
+                width,
+                height,
+            } => MapGenerator::new(*seed)
+                .with_size(*width, *height)
+                .generate(),
+            MapSource::Embedded { rows } => {
+                let (width, height) = self.map_size();
+                let mut map = Map::new(width, height);
+                for (y, line) in rows.iter().enumerate() {
+                    for (x, symbol) in line.chars().enumerate() {
+                        let cell =
+                            cell_from_symbol(symbol).unwrap_or_else(|| Cell::new(CellType::Plain));
+                        map.set_cell(MapPosition::new(x as i32, y as i32), cell);
+                    }
+                }
+                map
+            }
+        };
+        for ownership in &self.ownership {
+            let cell = map
+                .get_cell(&ownership.position)
+                .cloned()
+                .unwrap_or_else(|| Cell::new(CellType::Plain));
+            map.set_cell(
+                ownership.position,
+                Cell {
+                    faction_id: Some(ownership.faction_id),
+                    ..cell
+                },
+            );
+        }
+
+        let mut factions: Vec<Faction> = self
+            .factions
+            .iter()
+            .map(|def| Faction::new(def.id, def.name.clone(), def.faction_type, def.color))
+            .collect();
+        for def in &self.relationships {
+            for faction in &mut factions {
+                if faction.id == def.a {
+                    faction.set_relationship(def.b, def.relationship);
+                } else if faction.id == def.b {
+                    faction.set_relationship(def.a, def.relationship);
+                }
+            }
+        }
+
+        let units = self
+            .units
+            .iter()
+            .map(|def| {
+                Unit::new(
+                    def.id,
+                    def.name.clone(),
+                    def.unit_type,
+                    def.faction_id,
+                    def.position,
+                )
+            })
+            .collect();
+
+        (map, factions, units)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"Scenario(
+        name: "テスト",
+        map: Embedded(rows: ["..C", "F~."]),
+        factions: [
+            (id: 1, name: "勢力1", faction_type: Player, color: (0, 0, 255)),
+            (id: 2, name: "勢力2", faction_type: Rival, color: (255, 0, 0)),
+        ],
+        relationships: [(a: 1, b: 2, relationship: AtWar)],
+        ownership: [(position: (x: 2, y: 0), faction_id: 2)],
+        units: [
+            (id: 1, name: "歩兵", unit_type: Infantry, faction_id: 1, position: (x: 0, y: 0)),
+            (id: 2, name: "騎兵", unit_type: Cavalry, faction_id: 2, position: (x: 2, y: 1)),
+        ],
+    )"#;
+
+    /// 基本のシナリオの一部を置き換えて検証エラーを取得する
+    fn error_for(from: &str, to: &str) -> ScenarioError {
+        assert!(BASE.contains(from), "{}", from);
+        let error = Scenario::parse(&BASE.replace(from, to)).unwrap_err();
+        error
+            .downcast_ref::<ScenarioError>()
+            .cloned()
+            .unwrap_or_else(|| panic!("検証エラーではありません: {}", error))
+    }
+
+    #[test]
+    fn test_instantiate() -> Result<()> {
+        let scenario = Scenario::parse(BASE)?;
+        let (map, factions, units) = scenario.instantiate();
+
+        assert_eq!((map.width, map.height), (3, 2));
+        let city = map.get_cell(&MapPosition::new(2, 0)).unwrap();
+        assert_eq!(city.cell_type, CellType::Plain);
+        assert_eq!(city.structure, Some(Structure::City { level: 1 }));
+        assert_eq!(city.faction_id, Some(2));
+        assert_eq!(
+            map.get_cell(&MapPosition::new(1, 1)).unwrap().cell_type,
+            CellType::Water
+        );
+
+        assert_eq!(factions.len(), 2);
+        assert_eq!(factions[0].get_relationship(2), Relationship::AtWar);
+        assert_eq!(factions[1].get_relationship(1), Relationship::AtWar);
+        assert_eq!(units.len(), 2);
+        assert_eq!(units[1].unit_type, UnitType::Cavalry);
+        assert_eq!(units[1].position, MapPosition::new(2, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        assert_eq!(
+            error_for(r#"rows: ["..C", "F~."]"#, "rows: []"),
+            ScenarioError::EmptyMap
+        );
+        assert_eq!(
+            error_for(r#""F~.""#, r#""F~""#),
+            ScenarioError::RaggedMapRow { row: 1 }
+        );
+        assert_eq!(
+            error_for(r#""F~.""#, r#""F?.""#),
+            ScenarioError::UnknownTerrain {
+                row: 1,
+                symbol: '?'
+            }
+        );
+        assert_eq!(
+            error_for("(id: 2, name: \"勢力2\"", "(id: 1, name: \"勢力2\""),
+            ScenarioError::DuplicateFactionId(1)
+        );
+        assert_eq!(
+            error_for("b: 2, relationship", "b: 5, relationship"),
+            ScenarioError::UnknownFaction(5)
+        );
+        assert_eq!(
+            error_for("(id: 2, name: \"騎兵\"", "(id: 1, name: \"騎兵\""),
+            ScenarioError::DuplicateUnitId(1)
+        );
+        assert_eq!(
+            error_for("faction_id: 2, position", "faction_id: 9, position"),
+            ScenarioError::UnknownFaction(9)
+        );
+        assert_eq!(
+            error_for("position: (x: 2, y: 1)", "position: (x: 3, y: 1)"),
+            ScenarioError::UnitOutOfBounds {
+                unit_id: 2,
+                position: MapPosition::new(3, 1)
+            }
+        );
+        assert_eq!(
+            error_for("(x: 2, y: 0), faction_id", "(x: 0, y: 2), faction_id"),
+            ScenarioError::OwnershipOutOfBounds(MapPosition::new(0, 2))
+        );
+    }
+
+    #[test]
+    fn test_sample_scenario() -> Result<()> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenarios/demo.ron");
+        let scenario = Scenario::load(path)?;
+        let (map, factions, units) = scenario.instantiate();
+        assert!(units
+            .iter()
+            .all(|unit| map.is_valid_position(&unit.position)));
+        assert_eq!(factions.len(), 3);
+        assert!(Scenario::load("存在しないファイル.ron").is_err());
+        Ok(())
+    }
+}
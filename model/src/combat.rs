@@ -0,0 +1,405 @@
+use crate::map::{Cell, CellType, Structure};
+use crate::unit::{LevelUp, Unit, UnitType};
+
+/// 攻撃側が獲得する経験値
+const ATTACK_EXPERIENCE: u32 = 10;
+/// 撃破時に追加で獲得する経験値
+const KILL_EXPERIENCE: u32 = 30;
+/// 防御側が生き残った場合に獲得する経験値
+const DEFEND_EXPERIENCE: u32 = 5;
+
+/// 戦闘結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CombatResult {
+    pub damage_dealt: u32,                // 攻撃側が与えたダメージ
+    pub counter_damage: u32,              // 反撃で攻撃側が受けたダメージ
+    pub defender_destroyed: bool,         // 防御側が倒されたか
+    pub attacker_destroyed: bool,         // 反撃で攻撃側が倒されたか
+    pub attacker_experience: u32,         // 攻撃側が獲得した経験値
+    pub defender_experience: u32,         // 防御側が獲得した経験値
+    pub attacker_level_ups: Vec<LevelUp>, // 戦闘の経験値による攻撃側のレベルアップ
+    pub defender_level_ups: Vec<LevelUp>, // 戦闘の経験値による防御側のレベルアップ
+}
+
+/// 攻撃前に見積もった戦闘結果（ユニットの状態は変更しない）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CombatForecast {
+    pub damage_dealt: u32,        // 攻撃側が与えるダメージ
+    pub counter_damage: u32,      // 反撃で攻撃側が受けるダメージ（反撃がなければ0）
+    pub defender_destroyed: bool, // 防御側が倒されるか
+    pub attacker_destroyed: bool, // 反撃で攻撃側が倒されるか
+}
+
+/// 地形と構造物による防御ボーナスを返す
+pub fn terrain_defense_bonus(cell: &Cell) -> u32 {
+    let terrain = match cell.cell_type {
+        CellType::Forest => 2,
+        CellType::Mountain => 4,
+        _ => 0,
+    };
+    let structure = match cell.structure {
+        Some(Structure::City { .. }) => 5,
+        _ => 0,
+    };
+    terrain + structure
+}
+
+/// 攻撃力と防御力からダメージを計算（最低1）
+fn calculate_damage(attack: u32, defense: u32) -> u32 {
+    (attack * 2).saturating_sub(defense).max(1)
+}
+
+/// 攻撃側が与えるダメージ（防御側のセルの防御ボーナスを含む）
+fn strike_damage(attacker: &Unit, defender: &Unit, defender_cell: &Cell) -> u32 {
+    let defense = defender.defense_power() + terrain_defense_bonus(defender_cell);
+    calculate_damage(attacker.attack_power(), defense)
+}
+
+/// 反撃のダメージ（damaged_defenderは攻撃を受けた後の防御側）
+///
+/// 攻撃側のセルの防御ボーナスを含む。遠距離ユニットの攻撃には反撃しないためNoneを返す。
+fn counter_damage(attacker: &Unit, damaged_defender: &Unit, attacker_cell: &Cell) -> Option<u32> {
+    if attacker.unit_type == UnitType::Ranged {
+        return None;
+    }
+    let defense = attacker.defense_power() + terrain_defense_bonus(attacker_cell);
+    Some(calculate_damage(damaged_defender.attack_power(), defense))
+}
+
+/// 攻撃した場合の戦闘結果を見積もる
+///
+/// resolve_attackと同じ計算でダメージを求めるため、見積もりと実際の結果は一致する。
+pub fn forecast(
+    attacker: &Unit,
+    defender: &Unit,
+    attacker_cell: &Cell,
+    defender_cell: &Cell,
+) -> CombatForecast {
+    let damage_dealt = strike_damage(attacker, defender, defender_cell);
+    let mut damaged_defender = defender.clone();
+    let defender_destroyed = !damaged_defender.take_damage(damage_dealt);
+
+    let counter_damage = if defender_destroyed {
+        0
+    } else {
+        counter_damage(attacker, &damaged_defender, attacker_cell).unwrap_or(0)
+    };
+    CombatForecast {
+        damage_dealt,
+        counter_damage,
+        defender_destroyed,
+        attacker_destroyed: counter_damage > 0 && counter_damage >= attacker.health,
+    }
+}
+
+/// 攻撃を解決する
+///
+/// 防御側が生き残り、攻撃側が遠距離ユニットでない場合は反撃が発生する。
+/// 獲得した経験値によるレベルアップは結果に含めて返す（通知は呼び出し側で行う）。
+/// 与えるダメージには防御側のセル、反撃のダメージには攻撃側のセルの防御ボーナスを使う。
+pub fn resolve_attack(
+    attacker: &mut Unit,
+    defender: &mut Unit,
+    attacker_cell: &Cell,
+    defender_cell: &Cell,
+) -> CombatResult {
+    let damage_dealt = strike_damage(attacker, defender, defender_cell);
+    let defender_destroyed = !defender.take_damage(damage_dealt);
+
+    let mut counter_damage = 0;
+    let mut attacker_destroyed = false;
+    if !defender_destroyed {
+        if let Some(damage) = self::counter_damage(attacker, defender, attacker_cell) {
+            counter_damage = damage;
+            attacker_destroyed = !attacker.take_damage(counter_damage);
+        }
+    }
+
+    // 倒されたユニットは経験値を得ない
+    let attacker_experience = match (attacker_destroyed, defender_destroyed) {
+        (true, _) => 0,
+        (false, true) => ATTACK_EXPERIENCE + KILL_EXPERIENCE,
+        (false, false) => ATTACK_EXPERIENCE,
+    };
+    let defender_experience = if defender_destroyed {
+        0
+    } else {
+        DEFEND_EXPERIENCE
+    };
+    let attacker_level_ups = attacker.gain_experience(attacker_experience);
+    let defender_level_ups = defender.gain_experience(defender_experience);
+
+    CombatResult {
+        damage_dealt,
+        counter_damage,
+        defender_destroyed,
+        attacker_destroyed,
+        attacker_experience,
+        defender_experience,
+        attacker_level_ups,
+        defender_level_ups,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::MapPosition;
+
+    fn create_unit(id: u32, unit_type: UnitType) -> Unit {
+        Unit::new(
+            id,
+            format!("ユニット{}", id),
+            unit_type,
+            id,
+            MapPosition::new(0, id as i32),
+        )
+    }
+
+    fn plain() -> Cell {
+        Cell::new(CellType::Plain)
+    }
+
+    #[test]
+    fn test_terrain_defense_bonus() {
+        assert_eq!(terrain_defense_bonus(&Cell::new(CellType::Plain)), 0);
+        assert_eq!(terrain_defense_bonus(&Cell::new(CellType::Forest)), 2);
+        assert_eq!(terrain_defense_bonus(&Cell::new(CellType::Mountain)), 4);
+        assert_eq!(
+            terrain_defense_bonus(
+                &Cell::new(CellType::Plain).with_structure(Structure::City { level: 1 })
+            ),
+            5
+        );
+    }
+
+    #[test]
+    fn test_attack_with_counter() {
+        let mut attacker = create_unit(1, UnitType::Infantry);
+        let mut defender = create_unit(2, UnitType::Infantry);
+
+        let result = resolve_attack(&mut attacker, &mut defender, &plain(), &plain());
+
+        // 10 * 2 - 10 = 10
+        assert_eq!(result.damage_dealt, 10);
+        assert_eq!(defender.health, 90);
+        assert!(!result.defender_destroyed);
+
+        // 反撃: 体力90%で攻撃力9 → 9 * 2 - 10 = 8
+        assert_eq!(result.counter_damage, 8);
+        assert_eq!(attacker.health, 92);
+        assert!(!result.attacker_destroyed);
+
+        assert_eq!(attacker.experience, ATTACK_EXPERIENCE);
+        assert_eq!(defender.experience, DEFEND_EXPERIENCE);
+    }
+
+    #[test]
+    fn test_terrain_reduces_damage() {
+        let mut attacker = create_unit(1, UnitType::Infantry);
+        let mut defender = create_unit(2, UnitType::Infantry);
+
+        let result = resolve_attack(
+            &mut attacker,
+            &mut defender,
+            &plain(),
+            &Cell::new(CellType::Mountain),
+        );
+
+        // 10 * 2 - (10 + 4) = 6
+        assert_eq!(result.damage_dealt, 6);
+        assert_eq!(defender.health, 94);
+    }
+
+    #[test]
+    fn test_attack_kills_defender() {
+        let mut attacker = create_unit(1, UnitType::Siege);
+        let mut defender = create_unit(2, UnitType::Infantry);
+        defender.health = 10;
+
+        let result = resolve_attack(&mut attacker, &mut defender, &plain(), &plain());
+
+        assert!(result.defender_destroyed);
+        assert_eq!(defender.health, 0);
+        // 倒された防御側は反撃しない
+        assert_eq!(result.counter_damage, 0);
+        assert_eq!(attacker.health, 100);
+        assert_eq!(
+            result.attacker_experience,
+            ATTACK_EXPERIENCE + KILL_EXPERIENCE
+        );
+        assert_eq!(result.defender_experience, 0);
+    }
+
+    #[test]
+    fn test_combat_reports_level_ups() {
+        let mut attacker = create_unit(1, UnitType::Siege);
+        let mut defender = create_unit(2, UnitType::Infantry);
+        attacker.experience = 70;
+        defender.health = 10;
+
+        let result = resolve_attack(&mut attacker, &mut defender, &plain(), &plain());
+
+        assert!(result.defender_destroyed);
+        assert_eq!(result.attacker_level_ups.len(), 1);
+        assert_eq!(result.attacker_level_ups[0].new_level, 2);
+        assert_eq!(attacker.level, 2);
+        assert!(result.defender_level_ups.is_empty());
+    }
+
+    #[test]
+    fn test_attacker_destroyed_by_counter_stays_destroyed() {
+        let mut attacker = create_unit(1, UnitType::Infantry);
+        let mut defender = create_unit(2, UnitType::Siege);
+        attacker.health = 5;
+        attacker.experience = 95;
+        defender.experience = 97;
+
+        let result = resolve_attack(&mut attacker, &mut defender, &plain(), &plain());
+
+        // 反撃で倒された攻撃側は経験値を得ず、レベルアップで復活しない
+        assert!(result.attacker_destroyed);
+        assert_eq!(attacker.health, 0);
+        assert_eq!(result.attacker_experience, 0);
+        assert!(result.attacker_level_ups.is_empty());
+        assert_eq!((attacker.experience, attacker.level), (95, 1));
+
+        // 生き残った防御側はレベルアップする
+        assert_eq!(result.defender_level_ups.len(), 1);
+        assert_eq!(defender.level, 2);
+    }
+
+    #[test]
+    fn test_ranged_attack_receives_no_counter() {
+        let mut attacker = create_unit(1, UnitType::Ranged);
+        let mut defender = create_unit(2, UnitType::Cavalry);
+
+        let result = resolve_attack(&mut attacker, &mut defender, &plain(), &plain());
+
+        // 8 * 2 - 8 = 8
+        assert_eq!(result.damage_dealt, 8);
+        assert!(!result.defender_destroyed);
+        assert_eq!(result.counter_damage, 0);
+        assert_eq!(attacker.health, 100);
+    }
+
+    #[test]
+    fn test_forecast_matches_resolve_attack() {
+        let plain = plain();
+        let forest = Cell::new(CellType::Forest);
+        let mountain = Cell::new(CellType::Mountain);
+        let city = Cell::new(CellType::Forest).with_structure(Structure::City { level: 1 });
+        let cases = [
+            (
+                UnitType::Infantry,
+                UnitType::Infantry,
+                100,
+                100,
+                &plain,
+                &plain,
+            ),
+            (
+                UnitType::Cavalry,
+                UnitType::Infantry,
+                100,
+                60,
+                &plain,
+                &city,
+            ),
+            (
+                UnitType::Ranged,
+                UnitType::Cavalry,
+                100,
+                100,
+                &forest,
+                &plain,
+            ),
+            (UnitType::Siege, UnitType::Infantry, 100, 10, &plain, &plain),
+            (UnitType::Infantry, UnitType::Siege, 5, 100, &plain, &plain),
+            (
+                UnitType::Infantry,
+                UnitType::Infantry,
+                100,
+                100,
+                &forest,
+                &plain,
+            ),
+            (
+                UnitType::Cavalry,
+                UnitType::Siege,
+                100,
+                100,
+                &mountain,
+                &forest,
+            ),
+        ];
+        for (
+            attacker_type,
+            defender_type,
+            attacker_health,
+            defender_health,
+            attacker_cell,
+            defender_cell,
+        ) in cases
+        {
+            let mut attacker = create_unit(1, attacker_type);
+            let mut defender = create_unit(2, defender_type);
+            attacker.health = attacker_health;
+            defender.health = defender_health;
+
+            let expected = forecast(&attacker, &defender, attacker_cell, defender_cell);
+            // 見積もりではユニットは変化しない
+            assert_eq!(attacker.health, attacker_health);
+            assert_eq!(defender.health, defender_health);
+
+            let result = resolve_attack(&mut attacker, &mut defender, attacker_cell, defender_cell);
+            assert_eq!(
+                expected,
+                CombatForecast {
+                    damage_dealt: result.damage_dealt,
+                    counter_damage: result.counter_damage,
+                    defender_destroyed: result.defender_destroyed,
+                    attacker_destroyed: result.attacker_destroyed,
+                },
+                "{:?} -> {:?}",
+                attacker_type,
+                defender_type
+            );
+        }
+
+        // 反撃で倒されるケースも見積もれる
+        let mut attacker = create_unit(1, UnitType::Infantry);
+        attacker.health = 5;
+        let defender = create_unit(2, UnitType::Siege);
+        let expected = forecast(&attacker, &defender, &plain, &plain);
+        assert!(expected.attacker_destroyed);
+    }
+
+    #[test]
+    fn test_attacker_terrain_reduces_counter() {
+        let attacker = create_unit(1, UnitType::Infantry);
+        let defender = create_unit(2, UnitType::Infantry);
+        let plain = plain();
+
+        // 反撃: 体力90%で攻撃力9 → 平地 9 * 2 - 10 = 8、森 - 2、山 - 4
+        let on_plain = forecast(&attacker, &defender, &plain, &plain);
+        let on_forest = forecast(&attacker, &defender, &Cell::new(CellType::Forest), &plain);
+        let on_mountain = forecast(&attacker, &defender, &Cell::new(CellType::Mountain), &plain);
+        assert_eq!(on_plain.counter_damage, 8);
+        assert_eq!(on_forest.counter_damage, 6);
+        assert_eq!(on_mountain.counter_damage, 4);
+        // 与えるダメージは攻撃側のセルに影響されない
+        assert_eq!(on_mountain.damage_dealt, on_plain.damage_dealt);
+
+        let mut attacker = attacker;
+        let mut defender = defender;
+        let result = resolve_attack(
+            &mut attacker,
+            &mut defender,
+            &Cell::new(CellType::Mountain),
+            &plain,
+        );
+        assert_eq!(result.counter_damage, 4);
+        assert_eq!(attacker.health, 96);
+    }
+}
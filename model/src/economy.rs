@@ -0,0 +1,161 @@
+use crate::faction::FactionManager;
+use crate::map::{Cell, Map, Structure};
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, AddAssign};
+
+/// 勢力が保有する資源
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Resources {
+    pub gold: i64,     // 資金
+    pub supplies: i64, // 物資
+}
+
+impl Resources {
+    pub fn new(gold: i64, supplies: i64) -> Self {
+        Self { gold, supplies }
+    }
+
+    /// 指定した資源を支払えるかどうか
+    pub fn can_afford(&self, cost: &Resources) -> bool {
+        self.gold >= cost.gold && self.supplies >= cost.supplies
+    }
+
+    /// 資源を支払う（不足している場合は何もせずfalseを返す）
+    pub fn spend(&mut self, cost: &Resources) -> bool {
+        if !self.can_afford(cost) {
+            return false;
+        }
+        self.gold -= cost.gold;
+        self.supplies -= cost.supplies;
+        true
+    }
+}
+
+impl Add for Resources {
+    type Output = Resources;
+
+    fn add(self, other: Resources) -> Resources {
+        Resources::new(self.gold + other.gold, self.supplies + other.supplies)
+    }
+}
+
+impl AddAssign for Resources {
+    fn add_assign(&mut self, other: Resources) {
+        *self = *self + other;
+    }
+}
+
+/// セルの構造物から得られる1ターンあたりの収入（都市はレベルに比例）
+pub fn cell_yield(cell: &Cell) -> Resources {
+    match cell.structure {
+        Some(Structure::City { level }) => Resources::new(10 * level as i64, 0),
+        Some(Structure::Base) => Resources::new(5, 0),
+        Some(Structure::Ruins) | None => Resources::default(),
+    }
+}
+
+/// 勢力が所有するセルから得られる1ターンあたりの収入
+pub fn income_for(map: &Map, faction_id: u32) -> Resources {
+    map.cells()
+        .filter(|(_, cell)| cell.faction_id == Some(faction_id))
+        .fold(Resources::default(), |total, (_, cell)| {
+            total + cell_yield(cell)
+        })
+}
+
+impl FactionManager {
+    /// 全勢力に所有セルからの収入を加算する
+    pub fn apply_turn_income(&mut self, map: &Map) {
+        let ids: Vec<u32> = self.factions().iter().map(|faction| faction.id).collect();
+        for faction_id in ids {
+            if let Some(faction) = self.get_mut(faction_id) {
+                faction.add_resources(income_for(map, faction_id));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::faction::{Faction, FactionType};
+    use crate::map::{CellType, MapPosition};
+
+    fn create_map() -> Map {
+        let mut map = Map::new(5, 5);
+        map.set_cell(
+            MapPosition::new(0, 0),
+            Cell::with_faction(CellType::Plain, 1).with_structure(Structure::City { level: 1 }),
+        );
+        map.set_cell(
+            MapPosition::new(1, 0),
+            Cell::with_faction(CellType::Plain, 1).with_structure(Structure::City { level: 1 }),
+        );
+        map.set_cell(
+            MapPosition::new(2, 0),
+            Cell::with_faction(CellType::Plain, 1).with_structure(Structure::Base),
+        );
+        map.set_cell(
+            MapPosition::new(3, 0),
+            Cell::with_faction(CellType::Plain, 1),
+        );
+        map.set_cell(
+            MapPosition::new(4, 4),
+            Cell::with_faction(CellType::Plain, 2).with_structure(Structure::Base),
+        );
+        map.set_cell(
+            MapPosition::new(4, 0),
+            Cell::new(CellType::Plain).with_structure(Structure::City { level: 1 }),
+        );
+        map
+    }
+
+    fn create_manager() -> FactionManager {
+        let mut manager = FactionManager::new();
+        for id in 1..=2 {
+            let mut faction =
+                Faction::new(id, format!("勢力{}", id), FactionType::Player, (0, 0, 0));
+            faction.gold = 0;
+            manager.add_faction(faction);
+        }
+        manager
+    }
+
+    #[test]
+    fn test_income_for() {
+        let map = create_map();
+        assert_eq!(income_for(&map, 1), Resources::new(25, 0));
+        assert_eq!(income_for(&map, 2), Resources::new(5, 0));
+        assert_eq!(income_for(&map, 3), Resources::default());
+
+        let mut manager = create_manager();
+        manager.apply_turn_income(&map);
+        manager.apply_turn_income(&map);
+        assert_eq!(manager.resources(1), Resources::new(50, 0));
+        assert_eq!(manager.resources(2), Resources::new(10, 0));
+
+        let mut resources = manager.resources(2);
+        assert!(!resources.spend(&Resources::new(20, 0)));
+        assert!(resources.spend(&Resources::new(10, 0)));
+        assert_eq!(resources, Resources::default());
+    }
+
+    #[test]
+    fn test_capture_changes_income() {
+        let mut map = create_map();
+
+        // 都市を奪うと収入が移る
+        assert!(map.capture_cell(MapPosition::new(0, 0), 2));
+        assert_eq!(income_for(&map, 1), Resources::new(15, 0));
+        assert_eq!(income_for(&map, 2), Resources::new(15, 0));
+
+        // 既に所有しているセルや都市・拠点以外は占領できない
+        assert!(!map.capture_cell(MapPosition::new(0, 0), 2));
+        assert!(!map.capture_cell(MapPosition::new(3, 0), 2));
+        assert!(!map.capture_cell(MapPosition::new(2, 2), 2));
+
+        // 無所属の都市も占領できる
+        assert!(map.capture_cell(MapPosition::new(4, 0), 1));
+        assert_eq!(income_for(&map, 1), Resources::new(25, 0));
+    }
+}
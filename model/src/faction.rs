@@ -1,7 +1,9 @@
+use crate::economy::Resources;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// 勢力の種類
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FactionType {
     Player,      // プレイヤー
     Ally,        // 同盟
@@ -11,7 +13,7 @@ pub enum FactionType {
 }
 
 /// 勢力間の関係性
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Relationship {
     Friendly, // 友好
     Neutral,  // 中立
@@ -44,13 +46,15 @@ impl Relationship {
 }
 
 /// ゲーム内の勢力
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Faction {
     pub id: u32,
     pub name: String,
     pub faction_type: FactionType,
     pub color: (u8, u8, u8), // RGB
-    pub gold: u32,
+    pub gold: i64,           // 資金
+    #[serde(default)]
+    pub supplies: i64, // 物資
     pub diplomatic_points: u32,
     pub relationships: HashMap<u32, Relationship>, // 他の勢力IDとの関係
 }
@@ -63,11 +67,18 @@ impl Faction {
             faction_type,
             color,
             gold: 100,
+            supplies: 0,
             diplomatic_points: 0,
             relationships: HashMap::new(),
         }
     }
 
+    /// 勢力の色を0.0〜1.0のRGBで返す（描画用）
+    pub fn color_f32(&self) -> [f32; 3] {
+        let (r, g, b) = self.color;
+        [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0]
+    }
+
     /// 別の勢力との関係を設定
     pub fn set_relationship(&mut self, other_id: u32, relationship: Relationship) {
         self.relationships.insert(other_id, relationship);
@@ -93,17 +104,34 @@ impl Faction {
 
     /// ゴールドを追加
     pub fn add_gold(&mut self, amount: u32) {
-        self.gold += amount;
+        self.gold += amount as i64;
     }
 
     /// ゴールドを支払う
     pub fn spend_gold(&mut self, amount: u32) -> bool {
-        if self.gold >= amount {
-            self.gold -= amount;
-            true
-        } else {
-            false
+        self.spend_resources(&Resources::new(amount as i64, 0))
+    }
+
+    /// 保有している資源（資金と物資）
+    pub fn resources(&self) -> Resources {
+        Resources::new(self.gold, self.supplies)
+    }
+
+    /// 資源を加算する
+    pub fn add_resources(&mut self, amount: Resources) {
+        self.gold += amount.gold;
+        self.supplies += amount.supplies;
+    }
+
+    /// 資源を支払う（不足している場合は何もせずfalseを返す）
+    pub fn spend_resources(&mut self, cost: &Resources) -> bool {
+        let mut resources = self.resources();
+        if !resources.spend(cost) {
+            return false;
         }
+        self.gold = resources.gold;
+        self.supplies = resources.supplies;
+        true
     }
 
     /// 外交ポイントを追加
@@ -118,6 +146,79 @@ impl Faction {
     }
 }
 
+/// 全勢力と勢力間の関係を管理する
+///
+/// 関係と資源は各勢力（Faction）が持ち、マネージャーは双方の勢力に同じ関係を
+/// 設定することで対称に保つ。未設定の組み合わせは中立として扱う。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FactionManager {
+    factions: HashMap<u32, Faction>,
+}
+
+impl FactionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 勢力を追加（同じIDの勢力は置き換える）
+    pub fn add_faction(&mut self, faction: Faction) {
+        self.factions.insert(faction.id, faction);
+    }
+
+    /// IDで勢力を取得
+    pub fn get(&self, faction_id: u32) -> Option<&Faction> {
+        self.factions.get(&faction_id)
+    }
+
+    /// IDで勢力を可変参照で取得
+    pub fn get_mut(&mut self, faction_id: u32) -> Option<&mut Faction> {
+        self.factions.get_mut(&faction_id)
+    }
+
+    /// 全勢力をID順で取得
+    pub fn factions(&self) -> Vec<&Faction> {
+        let mut factions: Vec<&Faction> = self.factions.values().collect();
+        factions.sort_by_key(|faction| faction.id);
+        factions
+    }
+
+    /// 指定した種類の勢力をID順で取得
+    pub fn factions_of_type(&self, faction_type: FactionType) -> Vec<&Faction> {
+        self.factions()
+            .into_iter()
+            .filter(|faction| faction.faction_type == faction_type)
+            .collect()
+    }
+
+    /// 勢力の資源（存在しない勢力は0）
+    pub fn resources(&self, faction_id: u32) -> Resources {
+        self.get(faction_id)
+            .map(|faction| faction.resources())
+            .unwrap_or_default()
+    }
+
+    /// 2勢力間の関係を双方の勢力に設定する
+    pub fn set_relationship(&mut self, a: u32, b: u32, relationship: Relationship) {
+        if let Some(faction) = self.factions.get_mut(&a) {
+            faction.set_relationship(b, relationship);
+        }
+        if let Some(faction) = self.factions.get_mut(&b) {
+            faction.set_relationship(a, relationship);
+        }
+    }
+
+    /// 2勢力間の関係を取得（デフォルトは中立）
+    pub fn relationship(&self, a: u32, b: u32) -> Relationship {
+        self.get(a)
+            .map_or(Relationship::Neutral, |faction| faction.get_relationship(b))
+    }
+
+    /// 2勢力が敵対しているかどうか
+    pub fn are_hostile(&self, a: u32, b: u32) -> bool {
+        a != b && self.relationship(a, b).allows_attack()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,6 +236,7 @@ mod tests {
         assert_eq!(faction.name, "テスト勢力");
         assert_eq!(faction.faction_type, FactionType::Player);
         assert_eq!(faction.color, (255, 0, 0));
+        assert_eq!(faction.color_f32(), [1.0, 0.0, 0.0]);
         assert_eq!(faction.gold, 100);
         assert_eq!(faction.diplomatic_points, 0);
         assert!(faction.relationships.is_empty());
@@ -251,4 +353,107 @@ mod tests {
         assert!(Relationship::Hostile.allows_attack());
         assert!(!Relationship::Friendly.allows_attack());
     }
+
+    fn create_manager() -> FactionManager {
+        let mut manager = FactionManager::new();
+        manager.add_faction(Faction::new(
+            1,
+            "プレイヤー勢力".to_string(),
+            FactionType::Player,
+            (0, 0, 255),
+        ));
+        manager.add_faction(Faction::new(
+            2,
+            "同盟勢力".to_string(),
+            FactionType::Ally,
+            (0, 255, 0),
+        ));
+        manager.add_faction(Faction::new(
+            3,
+            "敵対勢力".to_string(),
+            FactionType::Rival,
+            (255, 0, 0),
+        ));
+        manager
+    }
+
+    #[test]
+    fn test_faction_manager_default_relationship() {
+        let manager = create_manager();
+        assert_eq!(manager.relationship(1, 3), Relationship::Neutral);
+        assert!(!manager.are_hostile(1, 3));
+        // 存在しない勢力も中立
+        assert_eq!(manager.relationship(1, 99), Relationship::Neutral);
+    }
+
+    #[test]
+    fn test_faction_manager_symmetry() {
+        let mut manager = create_manager();
+        manager.set_relationship(3, 1, Relationship::AtWar);
+
+        assert_eq!(manager.relationship(1, 3), Relationship::AtWar);
+        assert_eq!(manager.relationship(3, 1), Relationship::AtWar);
+        assert!(manager.are_hostile(1, 3));
+        assert!(manager.are_hostile(3, 1));
+
+        // 各勢力の関係にも反映される
+        assert!(manager.get(1).unwrap().can_attack(3));
+        assert!(manager.get(3).unwrap().can_attack(1));
+    }
+
+    #[test]
+    fn test_faction_manager_reads_faction_state() {
+        // 追加前に勢力へ設定した関係や資源もそのまま参照される
+        let mut faction = Faction::new(1, "勢力1".to_string(), FactionType::Player, (0, 0, 0));
+        faction.set_relationship(2, Relationship::Hostile);
+        faction.add_resources(Resources::new(20, 5));
+        let mut manager = FactionManager::new();
+        manager.add_faction(faction);
+        assert_eq!(manager.relationship(1, 2), Relationship::Hostile);
+        assert_eq!(manager.resources(1), Resources::new(120, 5));
+
+        // マネージャー経由の変更は勢力に反映される
+        manager.add_faction(Faction::new(
+            2,
+            "勢力2".to_string(),
+            FactionType::Rival,
+            (0, 0, 0),
+        ));
+        manager.set_relationship(1, 2, Relationship::Allied);
+        assert_eq!(
+            manager.get(2).unwrap().get_relationship(1),
+            Relationship::Allied
+        );
+        assert!(manager
+            .get_mut(1)
+            .unwrap()
+            .spend_resources(&Resources::new(100, 5)));
+        assert!(!manager.get_mut(1).unwrap().spend_gold(30));
+        assert_eq!(manager.resources(1), Resources::new(20, 0));
+        assert_eq!(manager.resources(99), Resources::default());
+    }
+
+    #[test]
+    fn test_factions_of_type() {
+        let manager = create_manager();
+        let rivals = manager.factions_of_type(FactionType::Rival);
+        assert_eq!(rivals.len(), 1);
+        assert_eq!(rivals[0].id, 3);
+        assert!(manager.factions_of_type(FactionType::Neutral).is_empty());
+
+        let ids: Vec<u32> = manager.factions().iter().map(|f| f.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_faction_manager_serde() {
+        let mut manager = create_manager();
+        manager.set_relationship(1, 2, Relationship::Allied);
+        manager.set_relationship(1, 3, Relationship::Hostile);
+
+        let serialized = ron::to_string(&manager).unwrap();
+        let restored: FactionManager = ron::from_str(&serialized).unwrap();
+        assert_eq!(restored, manager);
+        assert_eq!(restored.relationship(2, 1), Relationship::Allied);
+    }
 }
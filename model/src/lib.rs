@@ -1,10 +1,22 @@
+pub mod combat;
+pub mod economy;
 pub mod faction;
 pub mod map;
+pub mod orders;
+pub mod production;
 pub mod unit;
+pub mod visibility;
 
-pub use crate::faction::{Faction, FactionType, Relationship};
-pub use crate::map::{Cell, CellType, Map, MapPosition};
-pub use crate::unit::{Unit, UnitStatus, UnitType};
+pub use crate::combat::{forecast, resolve_attack, CombatForecast, CombatResult};
+pub use crate::economy::{income_for, Resources};
+pub use crate::faction::{Faction, FactionManager, FactionType, Relationship};
+pub use crate::map::edit::CellPatch;
+pub use crate::map::generator::MapGenerator;
+pub use crate::map::{Cell, CellType, Direction, GridKind, Map, MapPosition, Structure};
+pub use crate::orders::{DropReason, Order, OrderEvent, OrderExecutor, UnitOrders};
+pub use crate::production::{Production, ProductionError, ProductionQueue, UnitIdAllocator};
+pub use crate::unit::{ExperienceCurve, LevelUp, Unit, UnitStatus, UnitType};
+pub use crate::visibility::{SightRule, Visibility, VisibilityMap};
 
 pub fn greet() {
     println!("Model library loaded.");
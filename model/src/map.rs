@@ -1,7 +1,13 @@
-use std::collections::HashMap;
+pub mod edit;
+pub mod generator;
+
+use crate::unit::UnitType;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
 
 /// 2D座標を表す構造体
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct MapPosition {
     pub x: i32,
     pub y: i32,
@@ -13,6 +19,9 @@ impl MapPosition {
     }
 
     /// 指定された方向に移動した新しい位置を返す
+    ///
+    /// 座標がi32の範囲を超える場合はパニックする（デバッグビルド）。
+    /// 範囲外の可能性がある場合はchecked_movedまたはstepを使う。
     pub fn moved(&self, dx: i32, dy: i32) -> Self {
         Self {
             x: self.x + dx,
@@ -20,24 +29,120 @@ impl MapPosition {
         }
     }
 
+    /// 指定された量だけ移動した位置を返す（座標がi32の範囲を超える場合はNone）
+    pub fn checked_moved(&self, dx: i32, dy: i32) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add(dx)?,
+            y: self.y.checked_add(dy)?,
+        })
+    }
+
+    /// 指定した向きに1歩進んだ位置を返す（座標はi32の範囲で飽和する）
+    pub fn step(&self, direction: Direction) -> Self {
+        let (dx, dy) = direction.offset();
+        Self {
+            x: self.x.saturating_add(dx),
+            y: self.y.saturating_add(dy),
+        }
+    }
+
     /// 2点間のマンハッタン距離を計算
     pub fn manhattan_distance(&self, other: &MapPosition) -> u32 {
         ((self.x - other.x).abs() + (self.y - other.y).abs()) as u32
     }
+
+    /// 2点間のチェビシェフ距離（斜め移動を1歩と数えた距離）を計算
+    pub fn chebyshev_distance(&self, other: &MapPosition) -> u32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs()) as u32
+    }
+}
+
+/// 向き（上が北）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Direction {
+    N,  // 北（上）
+    NE, // 北東（右上）
+    E,  // 東（右）
+    SE, // 南東（右下）
+    #[default]
+    S, // 南（下）
+    SW, // 南西（左下）
+    W,  // 西（左）
+    NW, // 北西（左上）
 }
 
-/// マップのセルタイプ
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl Direction {
+    /// 上下左右の向き（上、右、下、左の順）
+    pub const CARDINAL: [Direction; 4] = [Direction::N, Direction::E, Direction::S, Direction::W];
+    /// 斜めの向き（右上、右下、左下、左上の順）
+    pub const DIAGONAL: [Direction; 4] =
+        [Direction::NE, Direction::SE, Direction::SW, Direction::NW];
+    /// 8方向すべて（北から時計回り）
+    pub const ALL: [Direction; 8] = [
+        Direction::N,
+        Direction::NE,
+        Direction::E,
+        Direction::SE,
+        Direction::S,
+        Direction::SW,
+        Direction::W,
+        Direction::NW,
+    ];
+
+    /// この向きに1歩進むときの移動量（x, y）
+    pub fn offset(&self) -> (i32, i32) {
+        match self {
+            Direction::N => (0, -1),
+            Direction::NE => (1, -1),
+            Direction::E => (1, 0),
+            Direction::SE => (1, 1),
+            Direction::S => (0, 1),
+            Direction::SW => (-1, 1),
+            Direction::W => (-1, 0),
+            Direction::NW => (-1, -1),
+        }
+    }
+
+    /// 隣接する（斜めを含む）2点について、aからbへの向きを求める（隣接していなければNone）
+    pub fn between(a: &MapPosition, b: &MapPosition) -> Option<Direction> {
+        let dx = b.x.checked_sub(a.x)?;
+        let dy = b.y.checked_sub(a.y)?;
+        Self::ALL
+            .into_iter()
+            .find(|direction| direction.offset() == (dx, dy))
+    }
+
+    /// 移動量の主な軸から上下左右の向きを求める（横と縦が同じ量なら横を優先、移動なしはNone）
+    pub fn cardinal_from_delta(dx: i32, dy: i32) -> Option<Direction> {
+        if dx == 0 && dy == 0 {
+            None
+        } else if dx.unsigned_abs() >= dy.unsigned_abs() {
+            Some(if dx > 0 { Direction::E } else { Direction::W })
+        } else {
+            Some(if dy > 0 { Direction::S } else { Direction::N })
+        }
+    }
+}
+
+/// マップのセルタイプ（地形）
+///
+/// 都市と拠点は構造物（Structure）として地形とは別に保持する。
+/// `CellType::City`と`CellType::Base`は旧データとの互換のために残しており、
+/// Cell::newなどで平地と構造物の組に変換される。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CellType {
     Plain,    // 平地
     Forest,   // 森
     Mountain, // 山
     Water,    // 水域
     Road,     // 道路
-    City,     // 都市
-    Base,     // 拠点
+    #[deprecated(note = "平地とStructure::Cityの組に変換される。Cell::with_structureを使う")]
+    City, // 都市
+    #[deprecated(note = "平地とStructure::Baseの組に変換される。Cell::with_structureを使う")]
+    Base, // 拠点
 }
 
+#[allow(deprecated)]
 impl CellType {
     /// セルタイプの移動コストを返す
     pub fn movement_cost(&self) -> u32 {
@@ -64,37 +169,313 @@ impl CellType {
             CellType::Base => 50,
         }
     }
+
+    /// 地形としてのセルタイプ（旧来の都市・拠点は平地）
+    pub fn terrain(&self) -> CellType {
+        match self {
+            CellType::City | CellType::Base => CellType::Plain,
+            terrain => *terrain,
+        }
+    }
+
+    /// 旧来の都市・拠点に対応する構造物
+    pub fn structure(&self) -> Option<Structure> {
+        match self {
+            CellType::City => Some(Structure::City { level: 1 }),
+            CellType::Base => Some(Structure::Base),
+            _ => None,
+        }
+    }
+}
+
+/// セル上の構造物（地形とは別に保持する）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Structure {
+    City { level: u8 }, // 都市（レベルが高いほど耐久値が高い）
+    Base,               // 拠点
+    Ruins,              // 廃墟（破壊された都市・拠点）
+}
+
+impl Structure {
+    /// 最大耐久値
+    pub fn max_hit_points(&self) -> u32 {
+        match self {
+            Structure::City { level } => 50 + 25 * *level as u32,
+            Structure::Base => 80,
+            Structure::Ruins => 0,
+        }
+    }
+
+    /// 防御修正値 (%)（地形の修正値に加算する）
+    pub fn defense_modifier(&self) -> i32 {
+        match self {
+            Structure::City { .. } => 30,
+            Structure::Base => 50,
+            Structure::Ruins => 10,
+        }
+    }
+
+    /// 占領できる構造物か
+    pub fn is_capturable(&self) -> bool {
+        matches!(self, Structure::City { .. } | Structure::Base)
+    }
 }
 
 /// マップのセル
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "CellData")]
 pub struct Cell {
     pub cell_type: CellType,
-    pub faction_id: Option<u32>, // 所有勢力ID（ある場合）
+    pub faction_id: Option<u32>,            // 所有勢力ID（ある場合）
+    pub elevation: i8,                      // 標高（0が基準）
+    pub movement_cost_override: Option<u8>, // セル固有の移動コスト（地形の既定値より優先）
+    pub structure: Option<Structure>,       // 地形の上の構造物
+    pub structure_hp: u32,                  // 構造物の耐久値
+}
+
+/// 読み込み用のセル（構造物のない旧データや旧来の都市・拠点を変換する）
+#[derive(Deserialize)]
+struct CellData {
+    cell_type: CellType,
+    faction_id: Option<u32>,
+    elevation: i8,
+    movement_cost_override: Option<u8>,
+    #[serde(default)]
+    structure: Option<Structure>,
+    #[serde(default)]
+    structure_hp: u32,
+}
+
+impl From<CellData> for Cell {
+    fn from(data: CellData) -> Self {
+        let mut cell = Cell::new(data.cell_type);
+        cell.faction_id = data.faction_id;
+        cell.elevation = data.elevation;
+        cell.movement_cost_override = data.movement_cost_override;
+        if data.structure.is_some() {
+            cell.structure = data.structure;
+            cell.structure_hp = data.structure_hp;
+        }
+        cell
+    }
 }
 
 impl Cell {
+    /// セルを作成（旧来の都市・拠点は平地と構造物の組に変換する）
     pub fn new(cell_type: CellType) -> Self {
-        Self {
-            cell_type,
+        let cell = Self {
+            cell_type: cell_type.terrain(),
             faction_id: None,
+            elevation: 0,
+            movement_cost_override: None,
+            structure: None,
+            structure_hp: 0,
+        };
+        match cell_type.structure() {
+            Some(structure) => cell.with_structure(structure),
+            None => cell,
         }
     }
 
     pub fn with_faction(cell_type: CellType, faction_id: u32) -> Self {
         Self {
-            cell_type,
             faction_id: Some(faction_id),
+            ..Self::new(cell_type)
+        }
+    }
+
+    /// 構造物を設定（耐久値は最大値）
+    pub fn with_structure(mut self, structure: Structure) -> Self {
+        self.structure = Some(structure);
+        self.structure_hp = structure.max_hit_points();
+        self
+    }
+
+    /// 標高を設定
+    pub fn with_elevation(mut self, elevation: i8) -> Self {
+        self.elevation = elevation;
+        self
+    }
+
+    /// 移動コストの上書き値を設定
+    pub fn with_movement_cost(mut self, cost: u8) -> Self {
+        self.movement_cost_override = Some(cost);
+        self
+    }
+
+    /// 占領できる構造物（都市・拠点）があるか
+    pub fn is_capturable(&self) -> bool {
+        self.structure
+            .is_some_and(|structure| structure.is_capturable())
+    }
+
+    /// 地形と構造物を合わせた防御修正値 (%)
+    pub fn defense_modifier(&self) -> i32 {
+        self.cell_type.defense_modifier()
+            + self
+                .structure
+                .map_or(0, |structure| structure.defense_modifier())
+    }
+
+    /// 構造物に損害を与え、破壊された場合はtrueを返す
+    ///
+    /// 耐久値が0になった都市・拠点は廃墟になり、所有勢力を失う。
+    pub fn damage_structure(&mut self, damage: u32) -> bool {
+        if !self.is_capturable() {
+            return false;
+        }
+        self.structure_hp = self.structure_hp.saturating_sub(damage);
+        if self.structure_hp > 0 {
+            return false;
+        }
+        self.structure = Some(Structure::Ruins);
+        self.faction_id = None;
+        true
+    }
+
+    /// 指定したユニット種別がこのセルに進入するコストを返す（Noneは進入不可）
+    ///
+    /// 上書き値があればそれを優先する。なければ地形ごとの既定値を使い、
+    /// 騎兵は森と山で1多くかかる。水域は進入不可。
+    pub fn movement_cost(&self, unit_type: UnitType) -> Option<u32> {
+        if let Some(cost) = self.movement_cost_override {
+            return Some(cost as u32);
+        }
+        if self.cell_type == CellType::Water {
+            return None;
+        }
+        let base = self.cell_type.movement_cost();
+        match (unit_type, self.cell_type) {
+            (UnitType::Cavalry, CellType::Forest | CellType::Mountain) => Some(base + 1),
+            _ => Some(base),
+        }
+    }
+}
+
+/// マップの格子の種類
+///
+/// 六角形の格子は奇数行を半マス右にずらしたオフセット座標で表す。
+/// ワールド座標はタイル(0, 0)の外接矩形の左上を原点とし、tile_sizeは1マスの横幅。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GridKind {
+    #[default]
+    Square, // 正方形
+    HexPointyTop, // 頂点が上の六角形
+}
+
+/// 偶数行の六角形の隣接位置への移動量（右から反時計回り）
+const HEX_EVEN_ROW_OFFSETS: [(i32, i32); 6] = [(1, 0), (0, -1), (-1, -1), (-1, 0), (-1, 1), (0, 1)];
+/// 奇数行の六角形の隣接位置への移動量（右から反時計回り）
+const HEX_ODD_ROW_OFFSETS: [(i32, i32); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (0, 1), (1, 1)];
+
+impl GridKind {
+    /// 2点間の距離（正方形はマンハッタン距離、六角形は最短の歩数）
+    pub fn distance(&self, a: &MapPosition, b: &MapPosition) -> u32 {
+        match self {
+            GridKind::Square => a.manhattan_distance(b),
+            GridKind::HexPointyTop => {
+                let (aq, ar) = hex_axial(a);
+                let (bq, br) = hex_axial(b);
+                let (dq, dr) = (aq - bq, ar - br);
+                ((dq.abs() + dr.abs() + (dq + dr).abs()) / 2) as u32
+            }
         }
     }
+
+    /// 隣接位置への移動量（正方形でdiagonalがtrueなら斜めも含む。六角形は常に6方向）
+    pub fn neighbor_offsets(&self, pos: &MapPosition, diagonal: bool) -> Vec<(i32, i32)> {
+        match self {
+            GridKind::Square => {
+                let diagonals: &[Direction] = if diagonal { &Direction::DIAGONAL } else { &[] };
+                Direction::CARDINAL
+                    .iter()
+                    .chain(diagonals)
+                    .map(|direction| direction.offset())
+                    .collect()
+            }
+            GridKind::HexPointyTop if pos.y & 1 == 0 => HEX_EVEN_ROW_OFFSETS.to_vec(),
+            GridKind::HexPointyTop => HEX_ODD_ROW_OFFSETS.to_vec(),
+        }
+    }
+
+    /// 行の間隔（六角形は行が重なるため横幅の√3/2倍）
+    pub fn row_height(&self, tile_size: f32) -> f32 {
+        match self {
+            GridKind::Square => tile_size,
+            GridKind::HexPointyTop => tile_size * 3f32.sqrt() / 2.0,
+        }
+    }
+
+    /// タイルの外接矩形の左上のワールド座標
+    pub fn tile_origin(&self, pos: &MapPosition, tile_size: f32) -> (f32, f32) {
+        let offset = match self {
+            GridKind::HexPointyTop if pos.y & 1 == 1 => tile_size / 2.0,
+            _ => 0.0,
+        };
+        (
+            pos.x as f32 * tile_size + offset,
+            pos.y as f32 * self.row_height(tile_size),
+        )
+    }
+
+    /// タイルの中心のワールド座標
+    pub fn tile_center(&self, pos: &MapPosition, tile_size: f32) -> (f32, f32) {
+        let (x, y) = self.tile_origin(pos, tile_size);
+        let half_height = match self {
+            GridKind::Square => tile_size / 2.0,
+            GridKind::HexPointyTop => tile_size / 3f32.sqrt(), // 外接円の半径
+        };
+        (x + tile_size / 2.0, y + half_height)
+    }
+
+    /// ワールド座標を含むタイルの位置（六角形は最も近い中心のタイル）
+    pub fn pick(&self, world_x: f32, world_y: f32, tile_size: f32) -> MapPosition {
+        match self {
+            GridKind::Square => MapPosition::new(
+                (world_x / tile_size).floor() as i32,
+                (world_y / tile_size).floor() as i32,
+            ),
+            GridKind::HexPointyTop => {
+                // タイル(0, 0)の中心を原点とした軸座標に変換して丸める
+                let radius = tile_size / 3f32.sqrt();
+                let x = world_x - tile_size / 2.0;
+                let y = world_y - radius;
+                let q = (3f32.sqrt() / 3.0 * x - y / 3.0) / radius;
+                let r = (2.0 / 3.0 * y) / radius;
+                let (q, r) = hex_round(q, r);
+                MapPosition::new(q + (r - (r & 1)) / 2, r)
+            }
+        }
+    }
+}
+
+/// オフセット座標を六角形の軸座標(q, r)に変換
+fn hex_axial(pos: &MapPosition) -> (i32, i32) {
+    (pos.x - (pos.y - (pos.y & 1)) / 2, pos.y)
+}
+
+/// 小数の軸座標を最も近い六角形の軸座標に丸める
+fn hex_round(q: f32, r: f32) -> (i32, i32) {
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    // 誤差の最も大きい成分を他の2成分から求め直す
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+    (rq as i32, rr as i32)
 }
 
 /// ゲームマップ
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Map {
     pub width: u32,
     pub height: u32,
     cells: HashMap<MapPosition, Cell>,
+    #[serde(default)]
+    pub grid: GridKind, // 格子の種類
 }
 
 impl Map {
@@ -103,9 +484,21 @@ impl Map {
             width,
             height,
             cells: HashMap::new(),
+            grid: GridKind::Square,
         }
     }
 
+    /// 格子の種類を指定したマップを返す
+    pub fn with_grid(mut self, grid: GridKind) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    /// 格子の種類に応じた2点間の距離
+    pub fn distance(&self, a: &MapPosition, b: &MapPosition) -> u32 {
+        self.grid.distance(a, b)
+    }
+
     /// 指定された位置にセルを設定
     pub fn set_cell(&mut self, pos: MapPosition, cell: Cell) {
         if self.is_valid_position(&pos) {
@@ -122,6 +515,39 @@ impl Map {
         }
     }
 
+    /// 設定済みの全セルとその位置（順序は不定）
+    pub fn cells(&self) -> impl Iterator<Item = (&MapPosition, &Cell)> {
+        self.cells.iter()
+    }
+
+    /// 都市・拠点の所有勢力を変更する
+    ///
+    /// 占領できるのは都市と拠点のみ。所有勢力が変わった場合にtrueを返す。
+    pub fn capture_cell(&mut self, pos: MapPosition, faction_id: u32) -> bool {
+        match self.cells.get_mut(&pos) {
+            Some(cell) if cell.is_capturable() && cell.faction_id != Some(faction_id) => {
+                cell.faction_id = Some(faction_id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 指定位置の所有勢力（セルがなければNone）
+    pub fn owner_at(&self, pos: &MapPosition) -> Option<u32> {
+        self.get_cell(pos).and_then(|cell| cell.faction_id)
+    }
+
+    /// 所有されたセルが領土の境界にあるか（上下左右に所有勢力の異なるセルがあればtrue）
+    pub fn is_territory_border(&self, pos: &MapPosition) -> bool {
+        let Some(owner) = self.owner_at(pos) else {
+            return false;
+        };
+        self.neighbors(*pos, false)
+            .iter()
+            .any(|neighbor| self.owner_at(neighbor) != Some(owner))
+    }
+
     /// 指定された位置が有効かどうかを検証
     pub fn is_valid_position(&self, pos: &MapPosition) -> bool {
         pos.x >= 0 && pos.y >= 0 && pos.x < self.width as i32 && pos.y < self.height as i32
@@ -129,14 +555,142 @@ impl Map {
 
     /// 指定された位置の隣接セルの位置を取得
     pub fn get_adjacent_positions(&self, pos: &MapPosition) -> Vec<MapPosition> {
-        let directions = [(0, -1), (1, 0), (0, 1), (-1, 0)]; // 上、右、下、左
+        self.neighbors(*pos, false)
+    }
 
-        directions
-            .iter()
-            .map(|(dx, dy)| pos.moved(*dx, *dy))
+    /// 隣接する有効な位置を取得（diagonalがtrueなら斜めも含む8方向、六角形の格子は常に6方向）
+    pub fn neighbors(&self, pos: MapPosition, diagonal: bool) -> Vec<MapPosition> {
+        self.grid
+            .neighbor_offsets(&pos, diagonal)
+            .into_iter()
+            .filter_map(|(dx, dy)| pos.checked_moved(dx, dy))
             .filter(|new_pos| self.is_valid_position(new_pos))
             .collect()
     }
+
+    /// 指定した向きの隣接位置（マップ外ならNone、向きは正方形の格子として扱う）
+    pub fn neighbor(&self, pos: MapPosition, direction: Direction) -> Option<MapPosition> {
+        let (dx, dy) = direction.offset();
+        pos.checked_moved(dx, dy)
+            .filter(|new_pos| self.is_valid_position(new_pos))
+    }
+
+    /// 指定位置から距離radius以内の有効な位置を取得（原点を含む）
+    ///
+    /// 距離は格子の種類に従う（正方形はマンハッタン距離）。
+    pub fn positions_within(&self, pos: MapPosition, radius: u32) -> Vec<MapPosition> {
+        let r = radius as i32;
+        let mut positions = Vec::new();
+        for y in (pos.y - r).max(0)..=(pos.y + r).min(self.height as i32 - 1) {
+            for x in (pos.x - r).max(0)..=(pos.x + r).min(self.width as i32 - 1) {
+                let candidate = MapPosition::new(x, y);
+                if self.distance(&pos, &candidate) <= radius {
+                    positions.push(candidate);
+                }
+            }
+        }
+        positions
+    }
+
+    /// 指定したユニット種別が位置に進入するコスト（Noneは進入不可、未設定のセルは平地扱い）
+    pub fn movement_cost_at(&self, pos: &MapPosition, unit_type: UnitType) -> Option<u32> {
+        if !self.is_valid_position(pos) {
+            return None;
+        }
+        match self.get_cell(pos) {
+            Some(cell) => cell.movement_cost(unit_type),
+            None => Cell::new(CellType::Plain).movement_cost(unit_type),
+        }
+    }
+
+    /// 移動コストが最小となる経路を探す
+    ///
+    /// 経路は始点を含まず終点を含む。経路がなければNoneを返す。
+    pub fn find_path(
+        &self,
+        from: MapPosition,
+        to: MapPosition,
+        unit_type: UnitType,
+    ) -> Option<Vec<MapPosition>> {
+        self.find_path_with(from, to, unit_type, |_| false)
+    }
+
+    /// 進入できない位置を指定して経路を探す（他ユニットの回避などに使う）
+    pub fn find_path_with(
+        &self,
+        from: MapPosition,
+        to: MapPosition,
+        unit_type: UnitType,
+        is_blocked: impl Fn(&MapPosition) -> bool,
+    ) -> Option<Vec<MapPosition>> {
+        if !self.is_valid_position(&from) || !self.is_valid_position(&to) {
+            return None;
+        }
+
+        // ダイクストラ法（同コストの場合は座標順で決定的に選ぶ）
+        let mut costs: HashMap<MapPosition, u32> = HashMap::from([(from, 0)]);
+        let mut came_from: HashMap<MapPosition, MapPosition> = HashMap::new();
+        let mut queue = BinaryHeap::from([Reverse((0, from.y, from.x))]);
+
+        while let Some(Reverse((cost, y, x))) = queue.pop() {
+            let current = MapPosition::new(x, y);
+            if current == to {
+                break;
+            }
+            if costs.get(&current).is_some_and(|&best| cost > best) {
+                continue;
+            }
+            for next in self.neighbors(current, false) {
+                if is_blocked(&next) {
+                    continue;
+                }
+                let Some(step_cost) = self.movement_cost_at(&next, unit_type) else {
+                    continue;
+                };
+                let next_cost = cost.saturating_add(step_cost);
+                if costs.get(&next).is_none_or(|&best| next_cost < best) {
+                    costs.insert(next, next_cost);
+                    came_from.insert(next, current);
+                    queue.push(Reverse((next_cost, next.y, next.x)));
+                }
+            }
+        }
+
+        if from == to {
+            return Some(Vec::new());
+        }
+        came_from.get(&to)?;
+        let mut path = vec![to];
+        let mut current = to;
+        while let Some(&previous) = came_from.get(&current) {
+            if previous == from {
+                break;
+            }
+            path.push(previous);
+            current = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// 2つの位置が隣接しているかどうか
+    ///
+    /// 正方形の格子でdiagonalがtrueなら斜めも隣接とみなす。六角形の格子では
+    /// neighborsと同じ6方向を隣接とし、diagonalは使わない。
+    pub fn is_adjacent(&self, a: MapPosition, b: MapPosition, diagonal: bool) -> bool {
+        match self.grid {
+            GridKind::Square => {
+                let dx = (a.x - b.x).abs();
+                let dy = (a.y - b.y).abs();
+                if diagonal {
+                    dx.max(dy) == 1
+                } else {
+                    dx + dy == 1
+                }
+            }
+            GridKind::HexPointyTop => self.distance(&a, &b) == 1,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -155,6 +709,73 @@ mod tests {
 
         let distance = pos.manhattan_distance(&moved);
         assert_eq!(distance, 5);
+        assert_eq!(pos.chebyshev_distance(&moved), 3);
+        assert_eq!(moved.chebyshev_distance(&pos), 3);
+        assert_eq!(pos.chebyshev_distance(&pos), 0);
+    }
+
+    #[test]
+    fn test_cardinal_from_delta() {
+        assert_eq!(Direction::cardinal_from_delta(0, -1), Some(Direction::N));
+        assert_eq!(Direction::cardinal_from_delta(3, 1), Some(Direction::E));
+        assert_eq!(Direction::cardinal_from_delta(-1, 2), Some(Direction::S));
+        assert_eq!(Direction::cardinal_from_delta(-2, -2), Some(Direction::W));
+        assert_eq!(Direction::cardinal_from_delta(0, 0), None);
+    }
+
+    #[test]
+    fn test_direction_step_and_between() {
+        let center = MapPosition::new(3, 3);
+        let expected = [
+            (3, 2),
+            (4, 2),
+            (4, 3),
+            (4, 4),
+            (3, 4),
+            (2, 4),
+            (2, 3),
+            (2, 2),
+        ];
+        for (direction, (x, y)) in Direction::ALL.into_iter().zip(expected) {
+            let next = center.step(direction);
+            assert_eq!(next, MapPosition::new(x, y), "{:?}", direction);
+            assert_eq!(Direction::between(&center, &next), Some(direction));
+        }
+        assert_eq!(Direction::between(&center, &center), None);
+        assert_eq!(Direction::between(&center, &MapPosition::new(5, 3)), None);
+    }
+
+    #[test]
+    fn test_position_near_limits() {
+        let corner = MapPosition::new(i32::MAX, i32::MIN);
+        assert_eq!(corner.checked_moved(1, 0), None);
+        assert_eq!(corner.checked_moved(0, -1), None);
+        assert_eq!(
+            corner.checked_moved(-1, 1),
+            Some(MapPosition::new(i32::MAX - 1, i32::MIN + 1))
+        );
+
+        // stepは範囲の端で飽和する
+        assert_eq!(corner.step(Direction::NE), corner);
+        assert_eq!(
+            corner.step(Direction::SW),
+            MapPosition::new(i32::MAX - 1, i32::MIN + 1)
+        );
+        assert_eq!(
+            Direction::between(
+                &MapPosition::new(i32::MIN, 0),
+                &MapPosition::new(i32::MAX, 0)
+            ),
+            None
+        );
+
+        // マップの端の隣接位置はマップ外
+        let map = Map::new(3, 3);
+        assert_eq!(map.neighbor(MapPosition::new(0, 0), Direction::NW), None);
+        assert_eq!(
+            map.neighbor(MapPosition::new(0, 0), Direction::SE),
+            Some(MapPosition::new(1, 1))
+        );
     }
 
     #[test]
@@ -167,6 +788,119 @@ mod tests {
         assert_eq!(CellType::Mountain.defense_modifier(), 40);
     }
 
+    #[test]
+    #[allow(deprecated)]
+    fn test_legacy_structure_migration() {
+        let city = Cell::with_faction(CellType::City, 2);
+        assert_eq!(city.cell_type, CellType::Plain);
+        assert_eq!(city.structure, Some(Structure::City { level: 1 }));
+        assert_eq!(city.structure_hp, 75);
+        assert_eq!(city.faction_id, Some(2));
+        assert_eq!(city.defense_modifier(), 30);
+        let base = Cell::new(CellType::Base);
+        assert_eq!(
+            (base.cell_type, base.structure),
+            (CellType::Plain, Some(Structure::Base))
+        );
+
+        // 構造物のない旧データも変換して読み込む
+        let old: Cell = ron::from_str(
+            "(cell_type: City, faction_id: Some(1), elevation: 2, movement_cost_override: None)",
+        )
+        .unwrap();
+        assert_eq!(old.cell_type, CellType::Plain);
+        assert_eq!(old.structure, Some(Structure::City { level: 1 }));
+        assert_eq!((old.faction_id, old.elevation), (Some(1), 2));
+
+        // 新しいデータは構造物と耐久値をそのまま保持する
+        let mut forest_base = Cell::new(CellType::Forest).with_structure(Structure::Base);
+        forest_base.structure_hp = 7;
+        let text = ron::to_string(&forest_base).unwrap();
+        assert_eq!(ron::from_str::<Cell>(&text).unwrap(), forest_base);
+    }
+
+    #[test]
+    fn test_structure_damage_leaves_ruins() {
+        let mut cell =
+            Cell::with_faction(CellType::Forest, 1).with_structure(Structure::City { level: 2 });
+        assert_eq!(cell.structure_hp, 100);
+        assert!(cell.is_capturable());
+        assert!(!cell.damage_structure(60));
+        assert_eq!(cell.structure_hp, 40);
+
+        assert!(cell.damage_structure(60));
+        assert_eq!(cell.structure, Some(Structure::Ruins));
+        assert_eq!(cell.cell_type, CellType::Forest);
+        assert_eq!(cell.faction_id, None);
+        assert!(!cell.is_capturable());
+        assert!(!cell.damage_structure(10));
+        assert_eq!(cell.defense_modifier(), 30);
+
+        // 廃墟は占領できない
+        let mut map = Map::new(2, 2);
+        map.set_cell(MapPosition::new(0, 0), cell);
+        assert!(!map.capture_cell(MapPosition::new(0, 0), 2));
+    }
+
+    #[test]
+    fn test_cell_movement_cost() {
+        let plain = Cell::new(CellType::Plain);
+        assert_eq!(plain.movement_cost(UnitType::Infantry), Some(1));
+        assert_eq!(
+            Cell::new(CellType::Forest).movement_cost(UnitType::Cavalry),
+            Some(3)
+        );
+
+        // 水域は進入不可
+        let water = Cell::new(CellType::Water);
+        assert_eq!(water.movement_cost(UnitType::Infantry), None);
+        assert_eq!(water.movement_cost(UnitType::Cavalry), None);
+
+        // 上書き値は地形と種別の既定値より優先される
+        let muddy = Cell::new(CellType::Plain).with_movement_cost(4);
+        assert_eq!(muddy.movement_cost(UnitType::Infantry), Some(4));
+        let bridge = Cell::new(CellType::Water).with_movement_cost(1);
+        assert_eq!(bridge.movement_cost(UnitType::Cavalry), Some(1));
+
+        let hill = Cell::with_faction(CellType::Plain, 2).with_elevation(3);
+        assert_eq!(hill.elevation, 3);
+        assert_eq!(hill.faction_id, Some(2));
+        assert_eq!(hill.movement_cost(UnitType::Infantry), Some(1));
+    }
+
+    #[test]
+    fn test_find_path() {
+        let mut map = Map::new(5, 5);
+        // 中央の列を水域で塞ぎ、一番下だけ通れるようにする
+        for y in 0..4 {
+            map.set_cell(MapPosition::new(2, y), Cell::new(CellType::Water));
+        }
+
+        let from = MapPosition::new(0, 0);
+        let to = MapPosition::new(4, 0);
+        let path = map.find_path(from, to, UnitType::Infantry).unwrap();
+        assert_eq!(path.last(), Some(&to));
+        assert!(path.contains(&MapPosition::new(2, 4)));
+        assert_eq!(path.len(), 12);
+
+        // 経路上の各ステップは隣接している
+        let mut previous = from;
+        for &step in &path {
+            assert!(map.is_adjacent(previous, step, false));
+            previous = step;
+        }
+
+        // 通り道を塞ぐと経路がなくなる
+        let blocked = MapPosition::new(2, 4);
+        assert!(map
+            .find_path_with(from, to, UnitType::Infantry, |pos| *pos == blocked)
+            .is_none());
+        assert_eq!(
+            map.find_path(from, from, UnitType::Infantry),
+            Some(Vec::new())
+        );
+    }
+
     #[test]
     fn test_map_basic() {
         let mut map = Map::new(10, 10);
@@ -200,4 +934,167 @@ mod tests {
         let edge_adjacent = map.get_adjacent_positions(&edge);
         assert_eq!(edge_adjacent.len(), 2); // 右と下のみ有効
     }
+
+    #[test]
+    fn test_territory_border() {
+        // 左2列が勢力1、右2列が勢力2、(1, 2)のみ所有者なし
+        let mut map = Map::new(4, 3);
+        for x in 0..4 {
+            for y in 0..3 {
+                let owner = if x < 2 { 1 } else { 2 };
+                map.set_cell(
+                    MapPosition::new(x, y),
+                    Cell::with_faction(CellType::Plain, owner),
+                );
+            }
+        }
+        map.set_cell(MapPosition::new(1, 2), Cell::new(CellType::Plain));
+
+        let borders: Vec<(i32, i32)> = (0..3)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .filter(|&(x, y)| map.is_territory_border(&MapPosition::new(x, y)))
+            .collect();
+        assert_eq!(
+            borders,
+            vec![(1, 0), (2, 0), (1, 1), (2, 1), (0, 2), (2, 2)]
+        );
+        assert_eq!(map.owner_at(&MapPosition::new(3, 1)), Some(2));
+        assert_eq!(map.owner_at(&MapPosition::new(1, 2)), None);
+        // マップの端は境界として扱わない
+        assert!(!map.is_territory_border(&MapPosition::new(0, 0)));
+    }
+
+    #[test]
+    fn test_hex_neighbors_and_distance() {
+        let map = Map::new(6, 6).with_grid(GridKind::HexPointyTop);
+
+        // 内側のセルは偶数行・奇数行とも6方向に隣接する
+        let mut even = map.neighbors(MapPosition::new(2, 2), true);
+        even.sort_by_key(|pos| (pos.y, pos.x));
+        let expected: Vec<MapPosition> = [(1, 1), (2, 1), (1, 2), (3, 2), (1, 3), (2, 3)]
+            .iter()
+            .map(|&(x, y)| MapPosition::new(x, y))
+            .collect();
+        assert_eq!(even, expected);
+        let odd = map.neighbors(MapPosition::new(2, 3), false);
+        assert_eq!(odd.len(), 6);
+        assert!(odd.contains(&MapPosition::new(3, 2)));
+        assert!(odd.contains(&MapPosition::new(3, 4)));
+        assert!(!odd.contains(&MapPosition::new(1, 2)));
+        // 角のセルは隣接位置が減る
+        assert_eq!(map.neighbors(MapPosition::new(0, 0), false).len(), 2);
+        assert_eq!(map.neighbors(MapPosition::new(5, 1), false).len(), 3);
+
+        // 隣接位置は距離1、斜めに2行進むと横に1マス進む
+        for neighbor in &even {
+            assert_eq!(map.distance(&MapPosition::new(2, 2), neighbor), 1);
+        }
+        assert_eq!(
+            map.distance(&MapPosition::new(0, 0), &MapPosition::new(1, 2)),
+            2
+        );
+        assert_eq!(
+            map.distance(&MapPosition::new(0, 0), &MapPosition::new(3, 0)),
+            3
+        );
+        assert_eq!(
+            map.distance(&MapPosition::new(0, 0), &MapPosition::new(0, 4)),
+            4
+        );
+        assert_eq!(map.positions_within(MapPosition::new(2, 2), 1).len(), 7);
+        // 正方形の格子の距離は従来どおりマンハッタン距離
+        assert_eq!(
+            Map::new(6, 6).distance(&MapPosition::new(0, 0), &MapPosition::new(1, 2)),
+            3
+        );
+    }
+
+    #[test]
+    fn test_hex_pick() {
+        let grid = GridKind::HexPointyTop;
+        let tile_size = 32.0;
+        let inner_radius = tile_size / 2.0;
+        for y in 0..6 {
+            for x in 0..6 {
+                let pos = MapPosition::new(x, y);
+                let (cx, cy) = grid.tile_center(&pos, tile_size);
+                assert_eq!(grid.pick(cx, cy, tile_size), pos);
+                // 内接円の内側の点は同じタイルになる
+                for angle in 0..12 {
+                    let angle = angle as f32 * std::f32::consts::PI / 6.0;
+                    let px = cx + angle.cos() * inner_radius * 0.95;
+                    let py = cy + angle.sin() * inner_radius * 0.95;
+                    assert_eq!(grid.pick(px, py, tile_size), pos);
+                }
+            }
+        }
+
+        // 奇数行は半マス右にずれる
+        assert_eq!(grid.tile_origin(&MapPosition::new(0, 1), tile_size).0, 16.0);
+        // 奇数行の外接矩形の左上の角は上の行のタイルに含まれる
+        let (ox, oy) = grid.tile_origin(&MapPosition::new(1, 1), tile_size);
+        assert_eq!(
+            grid.pick(ox + 1.0, oy + 1.0, tile_size),
+            MapPosition::new(1, 0)
+        );
+        // 斜めの辺の両側で別のタイルになる（タイル(0, 0)の下の頂点は(16, 約36.95)）
+        assert_eq!(grid.pick(1.0, 28.0, tile_size), MapPosition::new(0, 0));
+        assert_eq!(grid.pick(1.0, 35.0, tile_size), MapPosition::new(-1, 1));
+        assert_eq!(grid.pick(20.0, 34.0, tile_size), MapPosition::new(0, 0));
+        assert_eq!(grid.pick(20.0, 36.0, tile_size), MapPosition::new(0, 1));
+    }
+
+    #[test]
+    fn test_map_neighbors() {
+        let map = Map::new(5, 5);
+
+        assert_eq!(map.neighbors(MapPosition::new(2, 2), false).len(), 4);
+        assert_eq!(map.neighbors(MapPosition::new(2, 2), true).len(), 8);
+
+        // 角のセル
+        assert_eq!(map.neighbors(MapPosition::new(0, 0), false).len(), 2);
+        assert_eq!(map.neighbors(MapPosition::new(4, 4), true).len(), 3);
+
+        // 辺のセル
+        assert_eq!(map.neighbors(MapPosition::new(0, 2), false).len(), 3);
+        assert_eq!(map.neighbors(MapPosition::new(0, 2), true).len(), 5);
+    }
+
+    #[test]
+    fn test_positions_within() {
+        let map = Map::new(5, 5);
+
+        // 半径0は原点のみ
+        let origin = MapPosition::new(2, 2);
+        assert_eq!(map.positions_within(origin, 0), vec![origin]);
+
+        // 中央から半径1は原点+4方向
+        assert_eq!(map.positions_within(origin, 1).len(), 5);
+        assert_eq!(map.positions_within(origin, 2).len(), 13);
+
+        // 角から半径2はマップ内に制限される
+        let corner = map.positions_within(MapPosition::new(0, 0), 2);
+        assert_eq!(corner.len(), 6);
+        assert!(corner.iter().all(|pos| map.is_valid_position(pos)));
+    }
+
+    #[test]
+    fn test_is_adjacent() {
+        let map = Map::new(6, 6);
+        let a = MapPosition::new(2, 2);
+        assert!(map.is_adjacent(a, MapPosition::new(2, 3), false));
+        assert!(!map.is_adjacent(a, MapPosition::new(3, 3), false));
+        assert!(map.is_adjacent(a, MapPosition::new(3, 3), true));
+        assert!(!map.is_adjacent(a, a, true));
+        assert!(!map.is_adjacent(a, MapPosition::new(4, 2), true));
+
+        // 六角形では隣接する6方向だけが隣接になる
+        let hex = Map::new(6, 6).with_grid(GridKind::HexPointyTop);
+        for neighbor in hex.neighbors(a, false) {
+            assert!(hex.is_adjacent(a, neighbor, false), "{:?}", neighbor);
+        }
+        assert!(hex.is_adjacent(a, MapPosition::new(1, 1), false));
+        assert!(!hex.is_adjacent(a, MapPosition::new(3, 1), true));
+        assert!(!hex.is_adjacent(a, a, false));
+    }
 }
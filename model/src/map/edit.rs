@@ -0,0 +1,257 @@
+//! マップの地形編集
+//!
+//! 編集操作は変更前のセルを記録した逆パッチを返す。逆パッチを適用すると
+//! 編集前の状態に戻り、その戻り値を再度適用すると編集をやり直せる。
+use super::{Cell, CellType, Map, MapPosition};
+use std::collections::{HashSet, VecDeque};
+
+/// セルの変更を元に戻すためのパッチ（位置と変更前のセル、未設定はNone）
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CellPatch {
+    changes: Vec<(MapPosition, Option<Cell>)>,
+}
+
+impl CellPatch {
+    /// パッチが変更するセル数
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// 変更がないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// 変更するセルの位置
+    pub fn positions(&self) -> impl Iterator<Item = &MapPosition> {
+        self.changes.iter().map(|(pos, _)| pos)
+    }
+}
+
+impl Map {
+    /// 位置の地形（未設定のセルは平地扱い）
+    fn terrain_at(&self, pos: &MapPosition) -> CellType {
+        self.cells
+            .get(pos)
+            .map_or(CellType::Plain, |cell| cell.cell_type)
+    }
+
+    /// 指定した位置の地形を変更し、逆パッチを返す
+    ///
+    /// 所有勢力や標高、構造物などの地形以外の属性は保持する。
+    /// マップ外の位置や変更がない場合はNoneを返す。
+    pub fn paint_cell(&mut self, pos: MapPosition, cell_type: CellType) -> Option<CellPatch> {
+        self.paint_cells([pos], cell_type)
+    }
+
+    /// 2点を対角とする矩形の地形を変更し、逆パッチを返す（マップ外の部分は無視する）
+    pub fn fill_rect(
+        &mut self,
+        from: MapPosition,
+        to: MapPosition,
+        cell_type: CellType,
+    ) -> Option<CellPatch> {
+        // マップ外の範囲を先に切り落とし、極端に大きな矩形でも走査する量をマップ内に抑える
+        let xs = from.x.min(to.x).max(0)..=from.x.max(to.x).min(self.width as i32 - 1);
+        let ys = from.y.min(to.y).max(0)..=from.y.max(to.y).min(self.height as i32 - 1);
+        let positions = ys.flat_map(move |y| xs.clone().map(move |x| MapPosition::new(x, y)));
+        self.paint_cells(positions, cell_type)
+    }
+
+    /// 指定位置から上下左右に連続する同じ地形の領域を塗りつぶし、逆パッチを返す
+    pub fn flood_fill(&mut self, pos: MapPosition, cell_type: CellType) -> Option<CellPatch> {
+        if !self.is_valid_position(&pos) {
+            return None;
+        }
+        let target = self.terrain_at(&pos);
+        if target == cell_type {
+            return None;
+        }
+
+        let mut region = Vec::new();
+        let mut visited = HashSet::from([pos]);
+        let mut queue = VecDeque::from([pos]);
+        while let Some(current) = queue.pop_front() {
+            region.push(current);
+            for next in self.neighbors(current, false) {
+                if self.terrain_at(&next) == target && visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        self.paint_cells(region, cell_type)
+    }
+
+    /// パッチを適用し、適用前の状態に戻すための逆パッチを返す
+    pub fn apply_patch(&mut self, patch: &CellPatch) -> CellPatch {
+        let mut inverse = Vec::with_capacity(patch.changes.len());
+        for (pos, cell) in patch.changes.iter().rev() {
+            let previous = match cell {
+                Some(cell) => self.cells.insert(*pos, cell.clone()),
+                None => self.cells.remove(pos),
+            };
+            inverse.push((*pos, previous));
+        }
+        CellPatch { changes: inverse }
+    }
+
+    /// 複数の位置の地形を変更し、逆パッチを返す
+    fn paint_cells(
+        &mut self,
+        positions: impl IntoIterator<Item = MapPosition>,
+        cell_type: CellType,
+    ) -> Option<CellPatch> {
+        let mut changes = Vec::new();
+        for pos in positions {
+            if !self.is_valid_position(&pos) {
+                continue;
+            }
+            let previous = self.cells.get(&pos).cloned();
+            let current = previous
+                .clone()
+                .unwrap_or_else(|| Cell::new(CellType::Plain));
+            let cell = painted(&current, cell_type);
+            if cell == current {
+                continue;
+            }
+            self.cells.insert(pos, cell);
+            changes.push((pos, previous));
+        }
+        if changes.is_empty() {
+            None
+        } else {
+            Some(CellPatch { changes })
+        }
+    }
+}
+
+/// セルを塗った結果（旧来の都市・拠点は地形を保ったまま構造物として置く）
+///
+/// 地形を塗っても構造物は残る。
+fn painted(cell: &Cell, cell_type: CellType) -> Cell {
+    match cell_type.structure() {
+        Some(structure) if cell.structure == Some(structure) => cell.clone(),
+        Some(structure) => cell.clone().with_structure(structure),
+        None => Cell {
+            cell_type,
+            ..cell.clone()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 各行の地形を1文字で表したマップ
+    fn terrain_rows(map: &Map) -> Vec<String> {
+        (0..map.height as i32)
+            .map(|y| {
+                (0..map.width as i32)
+                    .map(|x| match map.terrain_at(&MapPosition::new(x, y)) {
+                        CellType::Plain => '.',
+                        CellType::Forest => 'F',
+                        CellType::Water => '~',
+                        CellType::Road => '=',
+                        _ => '?',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_flood_fill_stops_at_other_terrain() {
+        let mut map = Map::new(5, 4);
+        map.fill_rect(
+            MapPosition::new(2, 0),
+            MapPosition::new(2, 3),
+            CellType::Forest,
+        )
+        .unwrap();
+        map.paint_cell(MapPosition::new(0, 0), CellType::Forest)
+            .unwrap();
+
+        let patch = map
+            .flood_fill(MapPosition::new(1, 1), CellType::Water)
+            .unwrap();
+        assert_eq!(patch.len(), 7);
+        assert_eq!(terrain_rows(&map), vec!["F~F..", "~~F..", "~~F..", "~~F.."]);
+
+        // 同じ地形での塗りつぶしやマップ外は何もしない
+        assert!(map
+            .flood_fill(MapPosition::new(1, 1), CellType::Water)
+            .is_none());
+        assert!(map
+            .flood_fill(MapPosition::new(9, 9), CellType::Road)
+            .is_none());
+        assert!(map
+            .paint_cell(MapPosition::new(-1, 0), CellType::Road)
+            .is_none());
+    }
+
+    #[test]
+    fn test_fill_rect_clamps_to_map() {
+        let mut map = Map::new(4, 3);
+        // 対角がマップから大きく外れていても、マップ内の部分だけを塗る
+        let patch = map
+            .fill_rect(
+                MapPosition::new(-1_000_000, 1),
+                MapPosition::new(1_000_000, 1_000_000),
+                CellType::Water,
+            )
+            .unwrap();
+        assert_eq!(patch.len(), 8);
+        assert_eq!(terrain_rows(&map), vec!["....", "~~~~", "~~~~"]);
+
+        // 全体がマップ外なら何もしない
+        assert!(map
+            .fill_rect(
+                MapPosition::new(-1_000_000, -1_000_000),
+                MapPosition::new(-1, 1_000_000),
+                CellType::Road,
+            )
+            .is_none());
+        assert!(map
+            .fill_rect(
+                MapPosition::new(i32::MIN, i32::MIN),
+                MapPosition::new(i32::MAX, -1),
+                CellType::Road,
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn test_patch_undo_and_redo() {
+        let mut map = Map::new(4, 3);
+        map.set_cell(
+            MapPosition::new(1, 1),
+            Cell::with_faction(CellType::Forest, 2).with_elevation(3),
+        );
+        let original = map.clone();
+
+        let patch = map
+            .fill_rect(
+                MapPosition::new(3, 2),
+                MapPosition::new(0, 0),
+                CellType::Road,
+            )
+            .unwrap();
+        assert_eq!(patch.len(), 12);
+        // 地形以外の属性は保持される
+        let painted = map.get_cell(&MapPosition::new(1, 1)).unwrap();
+        assert_eq!(painted.cell_type, CellType::Road);
+        assert_eq!(painted.faction_id, Some(2));
+        assert_eq!(painted.elevation, 3);
+        let edited = map.clone();
+
+        // 逆パッチで元の状態（未設定のセルを含む）に戻る
+        let redo = map.apply_patch(&patch);
+        assert_eq!(map, original);
+        assert!(map.get_cell(&MapPosition::new(0, 0)).is_none());
+
+        // 逆パッチの逆パッチで編集をやり直せる
+        map.apply_patch(&redo);
+        assert_eq!(map, edited);
+    }
+}
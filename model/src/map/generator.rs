@@ -0,0 +1,355 @@
+//! シード付き手続き型マップ生成
+use super::{Cell, CellType, Map, MapPosition, Structure};
+use std::collections::{HashMap, VecDeque};
+
+/// 決定的な疑似乱数生成器（SplitMix64）
+struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// [0, 1) の浮動小数点数
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// [0, upper) の整数
+    fn next_below(&mut self, upper: u32) -> u32 {
+        (self.next_u64() % upper as u64) as u32
+    }
+}
+
+/// マップ生成器
+///
+/// 同じシードと設定からは常に同じマップを生成する。
+#[derive(Debug, Clone)]
+pub struct MapGenerator {
+    seed: u64,
+    width: u32,
+    height: u32,
+    water_ratio: f32,
+    mountain_ratio: f32,
+    forest_ratio: f32,
+    city_count: u32,
+    smoothing_passes: u32,
+}
+
+impl MapGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            width: 20,
+            height: 15,
+            water_ratio: 0.1,
+            mountain_ratio: 0.1,
+            forest_ratio: 0.2,
+            city_count: 4,
+            smoothing_passes: 2,
+        }
+    }
+
+    /// マップサイズを設定
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// 水域の割合を設定（0.0〜1.0）
+    pub fn with_water_ratio(mut self, ratio: f32) -> Self {
+        self.water_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 山岳の割合を設定（0.0〜1.0）
+    pub fn with_mountain_ratio(mut self, ratio: f32) -> Self {
+        self.mountain_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 森の割合を設定（0.0〜1.0）
+    pub fn with_forest_ratio(mut self, ratio: f32) -> Self {
+        self.forest_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 都市の数を設定
+    pub fn with_city_count(mut self, count: u32) -> Self {
+        self.city_count = count;
+        self
+    }
+
+    /// 平滑化の回数を設定（多いほど地形がまとまる）
+    pub fn with_smoothing(mut self, passes: u32) -> Self {
+        self.smoothing_passes = passes;
+        self
+    }
+
+    /// マップを生成
+    pub fn generate(&self) -> Map {
+        let mut rng = SeededRng::new(self.seed);
+        let elevation = self.noise_field(&mut rng);
+        let moisture = self.noise_field(&mut rng);
+
+        let water_level = quantile(&elevation, self.water_ratio);
+        let mountain_level = quantile(&elevation, 1.0 - self.mountain_ratio);
+        let forest_level = quantile(&moisture, 1.0 - self.forest_ratio);
+
+        let mut map = Map::new(self.width, self.height);
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                let index = self.index(x, y);
+                let cell_type = if elevation[index] < water_level {
+                    CellType::Water
+                } else if elevation[index] >= mountain_level {
+                    CellType::Mountain
+                } else if moisture[index] >= forest_level {
+                    CellType::Forest
+                } else {
+                    CellType::Plain
+                };
+                let cell = Cell::new(cell_type).with_elevation(to_elevation(elevation[index]));
+                map.set_cell(MapPosition::new(x, y), cell);
+            }
+        }
+
+        let cities = self.place_cities(&mut map, &mut rng);
+        for pair in cities.windows(2) {
+            self.build_road(&mut map, pair[0], pair[1]);
+        }
+        map
+    }
+
+    fn index(&self, x: i32, y: i32) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+
+    /// 平滑化したバリューノイズを生成
+    fn noise_field(&self, rng: &mut SeededRng) -> Vec<f32> {
+        let mut field: Vec<f32> = (0..self.width * self.height)
+            .map(|_| rng.next_f32())
+            .collect();
+
+        for _ in 0..self.smoothing_passes {
+            let mut smoothed = vec![0.0; field.len()];
+            for y in 0..self.height as i32 {
+                for x in 0..self.width as i32 {
+                    let mut sum = 0.0;
+                    let mut count = 0.0;
+                    for ny in (y - 1).max(0)..=(y + 1).min(self.height as i32 - 1) {
+                        for nx in (x - 1).max(0)..=(x + 1).min(self.width as i32 - 1) {
+                            sum += field[self.index(nx, ny)];
+                            count += 1.0;
+                        }
+                    }
+                    smoothed[self.index(x, y)] = sum / count;
+                }
+            }
+            field = smoothed;
+        }
+        field
+    }
+
+    /// 平地・森に都市を配置（都市同士は隣接させない）
+    fn place_cities(&self, map: &mut Map, rng: &mut SeededRng) -> Vec<MapPosition> {
+        let mut cities: Vec<MapPosition> = Vec::new();
+        let max_attempts = self.city_count * 50;
+        let mut attempts = 0;
+
+        while (cities.len() as u32) < self.city_count && attempts < max_attempts {
+            attempts += 1;
+            let pos = MapPosition::new(
+                rng.next_below(self.width) as i32,
+                rng.next_below(self.height) as i32,
+            );
+            let buildable = matches!(
+                map.get_cell(&pos).map(|cell| cell.cell_type),
+                Some(CellType::Plain | CellType::Forest)
+            );
+            let isolated = cities.iter().all(|city| city.manhattan_distance(&pos) > 2);
+            if buildable && isolated {
+                let cell = map.get_cell(&pos).cloned().expect("地形は配置済み");
+                map.set_cell(pos, cell.with_structure(Structure::City { level: 1 }));
+                cities.push(pos);
+            }
+        }
+        cities
+    }
+
+    /// 2都市間を水域を避けた最短経路の道路で結ぶ（経路がなければ何もしない）
+    fn build_road(&self, map: &mut Map, from: MapPosition, to: MapPosition) {
+        let mut came_from: HashMap<MapPosition, MapPosition> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        came_from.insert(from, from);
+
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                break;
+            }
+            for next in map.neighbors(current, false) {
+                let passable = map
+                    .get_cell(&next)
+                    .is_some_and(|cell| cell.cell_type != CellType::Water);
+                if passable && !came_from.contains_key(&next) {
+                    came_from.insert(next, current);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if !came_from.contains_key(&to) {
+            return;
+        }
+        let mut current = came_from[&to];
+        while current != from {
+            // 経路上の別の都市はそのまま残す
+            if map
+                .get_cell(&current)
+                .is_some_and(|cell| cell.structure.is_none())
+            {
+                replace_terrain(map, current, CellType::Road);
+            }
+            current = came_from[&current];
+        }
+    }
+}
+
+/// ノイズ値(0.0〜1.0)を標高(-10〜10)に変換
+fn to_elevation(value: f32) -> i8 {
+    ((value - 0.5) * 20.0).round().clamp(-10.0, 10.0) as i8
+}
+
+/// 標高を保ったままセルの地形を置き換える
+fn replace_terrain(map: &mut Map, pos: MapPosition, cell_type: CellType) {
+    let elevation = map.get_cell(&pos).map_or(0, |cell| cell.elevation);
+    map.set_cell(pos, Cell::new(cell_type).with_elevation(elevation));
+}
+
+/// 値の分布からratio分位点を求める
+fn quantile(values: &[f32], ratio: f32) -> f32 {
+    if ratio <= 0.0 {
+        return f32::NEG_INFINITY;
+    }
+    if ratio >= 1.0 {
+        return f32::INFINITY;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let index = ((sorted.len() as f32 * ratio) as usize).min(sorted.len() - 1);
+    sorted[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_cells(map: &Map, cell_type: CellType) -> usize {
+        (0..map.height as i32)
+            .flat_map(|y| (0..map.width as i32).map(move |x| MapPosition::new(x, y)))
+            .filter(|pos| map.get_cell(pos).map(|cell| cell.cell_type) == Some(cell_type))
+            .count()
+    }
+
+    #[test]
+    fn test_generation_is_deterministic() {
+        let first = MapGenerator::new(42).generate();
+        let second = MapGenerator::new(42).generate();
+        assert_eq!(first, second);
+
+        let other = MapGenerator::new(43).generate();
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_generated_map_is_fully_populated() {
+        let map = MapGenerator::new(7).with_size(30, 20).generate();
+        assert_eq!(map.width, 30);
+        assert_eq!(map.height, 20);
+        for y in 0..20 {
+            for x in 0..30 {
+                assert!(map.get_cell(&MapPosition::new(x, y)).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_water_ratio() {
+        let map = MapGenerator::new(1)
+            .with_size(20, 20)
+            .with_water_ratio(0.25)
+            .with_city_count(0)
+            .generate();
+        // 分位点で決まるため概ね指定の割合になる
+        assert_eq!(count_cells(&map, CellType::Water), 100);
+
+        let dry = MapGenerator::new(1)
+            .with_water_ratio(0.0)
+            .with_city_count(0)
+            .generate();
+        assert_eq!(count_cells(&dry, CellType::Water), 0);
+    }
+
+    #[test]
+    fn test_roads_connect_cities() {
+        let map = MapGenerator::new(3)
+            .with_size(20, 20)
+            .with_water_ratio(0.0)
+            .with_city_count(3)
+            .generate();
+        assert!(count_cells(&map, CellType::Road) > 0);
+
+        // 都市と道路だけをたどって全都市に到達できる
+        let is_city = |pos: &MapPosition| {
+            matches!(
+                map.get_cell(pos).unwrap().structure,
+                Some(Structure::City { .. })
+            )
+        };
+        let cities: Vec<MapPosition> = (0..20)
+            .flat_map(|y| (0..20).map(move |x| MapPosition::new(x, y)))
+            .filter(is_city)
+            .collect();
+        assert_eq!(cities.len(), 3);
+        let mut visited = vec![cities[0]];
+        let mut queue = VecDeque::from([cities[0]]);
+        while let Some(current) = queue.pop_front() {
+            for next in map.neighbors(current, false) {
+                let is_road = map.get_cell(&next).unwrap().cell_type == CellType::Road;
+                if (is_road || is_city(&next)) && !visited.contains(&next) {
+                    visited.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        assert!(cities.iter().all(|city| visited.contains(city)));
+    }
+
+    #[test]
+    fn test_elevation_follows_terrain() {
+        let map = MapGenerator::new(5).with_city_count(0).generate();
+        let elevations = |cell_type: CellType| -> Vec<i8> {
+            (0..map.height as i32)
+                .flat_map(|y| (0..map.width as i32).map(move |x| MapPosition::new(x, y)))
+                .filter_map(|pos| map.get_cell(&pos))
+                .filter(|cell| cell.cell_type == cell_type)
+                .map(|cell| cell.elevation)
+                .collect()
+        };
+        // 水域は山より高くならない
+        let highest_water = elevations(CellType::Water).into_iter().max().unwrap();
+        let lowest_mountain = elevations(CellType::Mountain).into_iter().min().unwrap();
+        assert!(highest_water <= lowest_mountain);
+    }
+}
@@ -0,0 +1,565 @@
+use crate::combat::{resolve_attack, CombatResult};
+use crate::faction::FactionManager;
+use crate::map::{Cell, CellType, Map, MapPosition};
+use crate::unit::{LevelUp, Unit};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// ユニットへの命令
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Order {
+    MoveTo(MapPosition),      // 指定位置へ移動
+    Attack(u32),              // 指定ユニットを攻撃（射程に入るまで接近する）
+    Hold,                     // その場で待機（取り消されるまで維持）
+    Patrol(Vec<MapPosition>), // 巡回地点を順に移動し続ける
+}
+
+/// 命令を破棄した理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropReason {
+    NoPath,        // 目的地への経路がない
+    UnknownTarget, // 攻撃対象が存在しない、または既に倒されている
+    NotHostile,    // 攻撃対象が敵対勢力ではない
+    EmptyPatrol,   // 巡回地点が指定されていない
+}
+
+/// 命令の実行結果として通知されるイベント
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderEvent {
+    Moved {
+        unit_id: u32,
+        from: MapPosition,
+        to: MapPosition,
+    },
+    Attacked {
+        attacker_id: u32,
+        defender_id: u32,
+        result: CombatResult,
+    },
+    LeveledUp {
+        unit_id: u32,
+        level_up: LevelUp,
+    },
+    Completed {
+        unit_id: u32,
+        order: Order,
+    },
+    Dropped {
+        unit_id: u32,
+        order: Order,
+        reason: DropReason,
+    },
+}
+
+/// ユニットごとの命令キュー（Unitの外で管理する）
+#[derive(Debug, Clone, Default)]
+pub struct UnitOrders {
+    queues: HashMap<u32, VecDeque<Order>>,
+}
+
+impl UnitOrders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 命令をキューの末尾に追加
+    pub fn push(&mut self, unit_id: u32, order: Order) {
+        self.queues.entry(unit_id).or_default().push_back(order);
+    }
+
+    /// ユニットの命令キュー
+    pub fn get(&self, unit_id: u32) -> Option<&VecDeque<Order>> {
+        self.queues.get(&unit_id)
+    }
+
+    /// ユニットの現在の命令
+    pub fn current(&self, unit_id: u32) -> Option<&Order> {
+        self.queues.get(&unit_id).and_then(|queue| queue.front())
+    }
+
+    /// ユニットの命令をすべて取り消す
+    pub fn clear(&mut self, unit_id: u32) {
+        self.queues.remove(&unit_id);
+    }
+
+    /// 命令が残っていないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.queues.values().all(|queue| queue.is_empty())
+    }
+}
+
+/// 命令実行時のイベント通知コールバック
+pub type OrderCallback = Box<dyn FnMut(&OrderEvent) + Send>;
+
+/// 1ティックで命令を処理した結果
+enum Step {
+    Continue,         // 命令を継続
+    Complete,         // 命令を完了
+    Drop(DropReason), // 命令を破棄
+}
+
+/// ユニットの命令を1ティックずつ実行する
+#[derive(Default)]
+pub struct OrderExecutor {
+    orders: UnitOrders,
+    on_event: Option<OrderCallback>,
+}
+
+impl OrderExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 命令をキューに追加
+    pub fn queue_order(&mut self, unit_id: u32, order: Order) {
+        self.orders.push(unit_id, order);
+    }
+
+    /// 命令キュー
+    pub fn orders(&self) -> &UnitOrders {
+        &self.orders
+    }
+
+    /// 命令キュー（変更可能）
+    pub fn orders_mut(&mut self) -> &mut UnitOrders {
+        &mut self.orders
+    }
+
+    /// 移動・攻撃などのイベント通知先を設定
+    pub fn set_on_event<F>(&mut self, callback: F)
+    where
+        F: FnMut(&OrderEvent) + Send + 'static,
+    {
+        self.on_event = Some(Box::new(callback));
+    }
+
+    /// 全ユニットの命令を1ティック分進める
+    ///
+    /// ユニットはID順に処理され、1ティックにつき経路を1ステップだけ進む。
+    /// 経路は毎ティック他ユニットを避けて再計算するため、道を塞がれても迂回する。
+    /// 完了した移動命令はその場で取り除かれ、同じティックで次の命令に進む。
+    pub fn tick(&mut self, map: &Map, units: &mut [Unit], factions: &FactionManager) {
+        let mut unit_ids: Vec<u32> = units.iter().map(|unit| unit.id).collect();
+        unit_ids.sort_unstable();
+
+        for unit_id in unit_ids {
+            let Some(index) = units.iter().position(|u| u.id == unit_id) else {
+                continue;
+            };
+            // 完了した命令の後は次の命令を続けて処理する（行動は1ティック1回）
+            loop {
+                if units[index].health == 0 {
+                    self.orders.clear(unit_id);
+                    break;
+                }
+                let Some(order) = self.orders.current(unit_id).cloned() else {
+                    break;
+                };
+
+                let (step, acted) = match &order {
+                    Order::MoveTo(target) => self.step_move(map, units, index, *target),
+                    Order::Attack(target_id) => {
+                        self.step_attack(map, units, factions, index, *target_id)
+                    }
+                    Order::Hold => (Step::Continue, true),
+                    Order::Patrol(waypoints) => {
+                        let Some(&next) = waypoints.first() else {
+                            self.finish(unit_id, Step::Drop(DropReason::EmptyPatrol));
+                            continue;
+                        };
+                        let (step, acted) = self.step_move(map, units, index, next);
+                        if matches!(step, Step::Complete) {
+                            // 到着した地点を末尾に回して巡回を続ける
+                            if let Some(Order::Patrol(waypoints)) = self
+                                .orders
+                                .queues
+                                .get_mut(&unit_id)
+                                .and_then(|queue| queue.front_mut())
+                            {
+                                waypoints.rotate_left(1);
+                            }
+                            (Step::Continue, acted)
+                        } else {
+                            (step, acted)
+                        }
+                    }
+                };
+
+                let done = !matches!(step, Step::Continue);
+                self.finish(unit_id, step);
+                if acted || !done {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// 移動命令を1ステップ進める（戻り値は処理結果と行動したかどうか）
+    fn step_move(
+        &mut self,
+        map: &Map,
+        units: &mut [Unit],
+        index: usize,
+        target: MapPosition,
+    ) -> (Step, bool) {
+        let from = units[index].position;
+        if from == target {
+            return (Step::Complete, false);
+        }
+
+        let occupied = occupied_positions(units, index);
+        let path = map.find_path_with(from, target, units[index].unit_type, |pos| {
+            occupied.contains(pos)
+        });
+        match path.as_deref() {
+            Some([next, ..]) => {
+                let next = *next;
+                units[index].face_towards(next);
+                units[index].position = next;
+                self.emit(OrderEvent::Moved {
+                    unit_id: units[index].id,
+                    from,
+                    to: next,
+                });
+                let step = if next == target {
+                    Step::Complete
+                } else {
+                    Step::Continue
+                };
+                (step, true)
+            }
+            _ => (Step::Drop(DropReason::NoPath), false),
+        }
+    }
+
+    /// 攻撃命令を1ステップ進める（射程内なら攻撃、そうでなければ接近）
+    fn step_attack(
+        &mut self,
+        map: &Map,
+        units: &mut [Unit],
+        factions: &FactionManager,
+        index: usize,
+        target_id: u32,
+    ) -> (Step, bool) {
+        let Some(target_index) = units
+            .iter()
+            .position(|unit| unit.id == target_id && unit.health > 0)
+        else {
+            return (Step::Drop(DropReason::UnknownTarget), false);
+        };
+        if !factions.are_hostile(units[index].faction_id, units[target_index].faction_id) {
+            return (Step::Drop(DropReason::NotHostile), false);
+        }
+
+        let from = units[index].position;
+        let target_position = units[target_index].position;
+        let range = units[index].unit_type.attack_range();
+        if map.distance(&from, &target_position) <= range {
+            let cell_at = |position| {
+                map.get_cell(&position)
+                    .cloned()
+                    .unwrap_or_else(|| Cell::new(CellType::Plain))
+            };
+            let (attacker_cell, defender_cell) = (cell_at(from), cell_at(target_position));
+            let (attacker, defender) = pair_mut(units, index, target_index);
+            let result = resolve_attack(attacker, defender, &attacker_cell, &defender_cell);
+            // 戦闘の経験値によるレベルアップは攻撃の通知に続けて1回ずつ通知する
+            let level_ups: Vec<(u32, LevelUp)> = result
+                .attacker_level_ups
+                .iter()
+                .map(|level_up| (attacker.id, *level_up))
+                .chain(
+                    result
+                        .defender_level_ups
+                        .iter()
+                        .map(|level_up| (defender.id, *level_up)),
+                )
+                .collect();
+            self.emit(OrderEvent::Attacked {
+                attacker_id: attacker.id,
+                defender_id: defender.id,
+                result,
+            });
+            for (unit_id, level_up) in level_ups {
+                self.emit(OrderEvent::LeveledUp { unit_id, level_up });
+            }
+            return (Step::Complete, true);
+        }
+
+        // 対象を射程に収めるマスまでの経路を探して1ステップ接近する
+        let occupied = occupied_positions(units, index);
+        let unit_type = units[index].unit_type;
+        let path = map
+            .positions_within(target_position, range)
+            .into_iter()
+            .filter(|pos| *pos != target_position && !occupied.contains(pos))
+            .filter_map(|goal| {
+                map.find_path_with(from, goal, unit_type, |pos| occupied.contains(pos))
+            })
+            .min_by_key(|path| path.len());
+        match path.as_deref() {
+            Some([next, ..]) => {
+                let next = *next;
+                units[index].face_towards(next);
+                units[index].position = next;
+                self.emit(OrderEvent::Moved {
+                    unit_id: units[index].id,
+                    from,
+                    to: next,
+                });
+                (Step::Continue, true)
+            }
+            _ => (Step::Drop(DropReason::NoPath), false),
+        }
+    }
+
+    /// 処理結果に応じて現在の命令を取り除き、イベントを通知する
+    fn finish(&mut self, unit_id: u32, step: Step) {
+        let reason = match step {
+            Step::Continue => return,
+            Step::Complete => None,
+            Step::Drop(reason) => Some(reason),
+        };
+        let Some(order) = self.pop(unit_id) else {
+            return;
+        };
+        let event = match reason {
+            None => OrderEvent::Completed { unit_id, order },
+            Some(reason) => OrderEvent::Dropped {
+                unit_id,
+                order,
+                reason,
+            },
+        };
+        self.emit(event);
+    }
+
+    fn pop(&mut self, unit_id: u32) -> Option<Order> {
+        self.orders
+            .queues
+            .get_mut(&unit_id)
+            .and_then(|queue| queue.pop_front())
+    }
+
+    fn emit(&mut self, event: OrderEvent) {
+        if let Some(callback) = &mut self.on_event {
+            callback(&event);
+        }
+    }
+}
+
+/// 指定したユニット以外の生存ユニットがいる位置
+fn occupied_positions(units: &[Unit], except: usize) -> HashSet<MapPosition> {
+    units
+        .iter()
+        .enumerate()
+        .filter(|(i, unit)| *i != except && unit.health > 0)
+        .map(|(_, unit)| unit.position)
+        .collect()
+}
+
+/// 異なる2つのユニットへの可変参照を取得
+fn pair_mut(units: &mut [Unit], a: usize, b: usize) -> (&mut Unit, &mut Unit) {
+    if a < b {
+        let (left, right) = units.split_at_mut(b);
+        (&mut left[a], &mut right[0])
+    } else {
+        let (left, right) = units.split_at_mut(a);
+        (&mut right[0], &mut left[b])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::faction::{Faction, FactionType, Relationship};
+    use crate::map::GridKind;
+    use crate::unit::UnitType;
+    use std::sync::{Arc, Mutex};
+
+    fn create_unit(id: u32, faction_id: u32, x: i32, y: i32) -> Unit {
+        Unit::new(
+            id,
+            format!("ユニット{}", id),
+            UnitType::Infantry,
+            faction_id,
+            MapPosition::new(x, y),
+        )
+    }
+
+    fn create_factions() -> FactionManager {
+        let mut factions = FactionManager::new();
+        for id in 1..=2 {
+            factions.add_faction(Faction::new(
+                id,
+                format!("勢力{}", id),
+                FactionType::Player,
+                (0, 0, 0),
+            ));
+        }
+        factions.set_relationship(1, 2, Relationship::AtWar);
+        factions
+    }
+
+    #[test]
+    fn test_order_sequence_completes_over_ticks() {
+        let map = Map::new(10, 10);
+        let factions = create_factions();
+        let mut units = vec![create_unit(1, 1, 0, 0), create_unit(2, 2, 4, 0)];
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut executor = OrderExecutor::new();
+        let sink = events.clone();
+        executor.set_on_event(move |event| sink.lock().unwrap().push(event.clone()));
+
+        executor.queue_order(1, Order::MoveTo(MapPosition::new(2, 0)));
+        executor.queue_order(1, Order::Attack(2));
+        // 攻撃の経験値でレベルアップする
+        units[0].experience = 95;
+
+        // 1ティック目・2ティック目で移動、3ティック目で接近、4ティック目で攻撃
+        for _ in 0..3 {
+            executor.tick(&map, &mut units, &factions);
+        }
+        assert_eq!(units[0].position, MapPosition::new(3, 0));
+        assert_eq!(executor.orders().current(1), Some(&Order::Attack(2)));
+
+        executor.tick(&map, &mut units, &factions);
+        assert!(executor.orders().is_empty());
+        assert!(units[1].health < units[1].max_health);
+
+        let events = events.lock().unwrap();
+        let moves = events
+            .iter()
+            .filter(|e| matches!(e, OrderEvent::Moved { unit_id: 1, .. }))
+            .count();
+        assert_eq!(moves, 3);
+        assert!(events.contains(&OrderEvent::Completed {
+            unit_id: 1,
+            order: Order::MoveTo(MapPosition::new(2, 0)),
+        }));
+        assert!(matches!(
+            events.last(),
+            Some(OrderEvent::Completed {
+                unit_id: 1,
+                order: Order::Attack(2)
+            })
+        ));
+        let attacked = events
+            .iter()
+            .position(|e| matches!(e, OrderEvent::Attacked { .. }))
+            .unwrap();
+        assert!(matches!(
+            events[attacked + 1],
+            OrderEvent::LeveledUp {
+                unit_id: 1,
+                level_up: LevelUp { new_level: 2, .. }
+            }
+        ));
+        assert_eq!(units[0].level, 2);
+    }
+
+    #[test]
+    fn test_attack_on_hex_grid() {
+        // 偶数行の(2, 2)から見て(1, 1)は六角形の斜めの隣接
+        let map = Map::new(6, 6).with_grid(GridKind::HexPointyTop);
+        let factions = create_factions();
+        let mut units = vec![create_unit(1, 1, 1, 1), create_unit(2, 2, 2, 2)];
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut executor = OrderExecutor::new();
+        let sink = events.clone();
+        executor.set_on_event(move |event| sink.lock().unwrap().push(event.clone()));
+
+        executor.queue_order(1, Order::Attack(2));
+        executor.tick(&map, &mut units, &factions);
+
+        assert!(executor.orders().is_empty());
+        assert_eq!(units[0].position, MapPosition::new(1, 1));
+        assert!(units[1].health < units[1].max_health);
+        assert!(events.lock().unwrap().iter().any(|e| matches!(
+            e,
+            OrderEvent::Attacked {
+                attacker_id: 1,
+                defender_id: 2,
+                ..
+            }
+        )));
+
+        // 離れていれば六角形の隣接まで接近してから攻撃する
+        units[0].position = MapPosition::new(5, 5);
+        executor.queue_order(1, Order::Attack(2));
+        for _ in 0..5 {
+            executor.tick(&map, &mut units, &factions);
+        }
+        assert!(executor.orders().is_empty());
+        assert_eq!(map.distance(&units[0].position, &units[1].position), 1);
+    }
+
+    #[test]
+    fn test_ranged_attack_within_attack_range() {
+        let map = Map::new(10, 10);
+        let factions = create_factions();
+        let mut archer = create_unit(1, 1, 0, 0);
+        archer.unit_type = UnitType::Ranged;
+        let mut units = vec![archer, create_unit(2, 2, 2, 0)];
+        let mut executor = OrderExecutor::new();
+
+        // 射程2の遠隔ユニットは距離2からその場で攻撃する
+        executor.queue_order(1, Order::Attack(2));
+        executor.tick(&map, &mut units, &factions);
+        assert!(executor.orders().is_empty());
+        assert_eq!(units[0].position, MapPosition::new(0, 0));
+        assert!(units[1].health < units[1].max_health);
+
+        // 射程外なら射程に入るマスまでだけ接近する
+        units[1].position = MapPosition::new(5, 0);
+        executor.queue_order(1, Order::Attack(2));
+        for _ in 0..5 {
+            executor.tick(&map, &mut units, &factions);
+        }
+        assert!(executor.orders().is_empty());
+        assert_eq!(units[0].position, MapPosition::new(3, 0));
+    }
+
+    #[test]
+    fn test_blocked_path_replans() {
+        // 幅3の通路で、中央に他ユニットが立ち塞がる
+        let mut map = Map::new(5, 3);
+        for x in 0..5 {
+            map.set_cell(MapPosition::new(x, 0), Cell::new(CellType::Water));
+        }
+        let factions = create_factions();
+        let mut units = vec![create_unit(1, 1, 0, 1), create_unit(2, 1, 2, 1)];
+        let mut executor = OrderExecutor::new();
+        executor.queue_order(1, Order::MoveTo(MapPosition::new(4, 1)));
+
+        let mut visited = Vec::new();
+        for _ in 0..10 {
+            executor.tick(&map, &mut units, &factions);
+            visited.push(units[0].position);
+            if executor.orders().is_empty() {
+                break;
+            }
+        }
+        assert_eq!(units[0].position, MapPosition::new(4, 1));
+        assert!(!visited.contains(&MapPosition::new(2, 1)));
+        assert!(visited.contains(&MapPosition::new(2, 2)));
+
+        // 経路が完全に塞がれると命令は破棄される
+        units[0].position = MapPosition::new(0, 1);
+        units.push(create_unit(3, 1, 2, 2));
+        executor.queue_order(1, Order::MoveTo(MapPosition::new(4, 1)));
+        executor.queue_order(1, Order::Attack(3));
+        let dropped = Arc::new(Mutex::new(Vec::new()));
+        let sink = dropped.clone();
+        executor.set_on_event(move |event| {
+            if let OrderEvent::Dropped { reason, .. } = event {
+                sink.lock().unwrap().push(*reason);
+            }
+        });
+        executor.tick(&map, &mut units, &factions);
+        assert_eq!(
+            *dropped.lock().unwrap(),
+            vec![DropReason::NoPath, DropReason::NotHostile]
+        );
+        assert_eq!(units[0].position, MapPosition::new(0, 1));
+    }
+}
@@ -0,0 +1,402 @@
+use crate::economy::Resources;
+use crate::faction::FactionManager;
+use crate::map::{Map, MapPosition};
+use crate::unit::{Unit, UnitType};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+impl UnitType {
+    /// ユニットの生産コストを返す
+    pub fn production_cost(&self) -> Resources {
+        match self {
+            UnitType::Infantry => Resources::new(10, 0),
+            UnitType::Cavalry => Resources::new(20, 0),
+            UnitType::Ranged => Resources::new(15, 0),
+            UnitType::Siege => Resources::new(30, 0),
+            UnitType::Support => Resources::new(12, 0),
+        }
+    }
+
+    /// ユニットの生産に必要なターン数を返す
+    pub fn build_turns(&self) -> u32 {
+        match self {
+            UnitType::Infantry => 1,
+            UnitType::Cavalry => 2,
+            UnitType::Ranged => 2,
+            UnitType::Siege => 3,
+            UnitType::Support => 1,
+        }
+    }
+
+    /// 生産したユニットの表示名
+    fn display_name(&self) -> &'static str {
+        match self {
+            UnitType::Infantry => "歩兵",
+            UnitType::Cavalry => "騎兵",
+            UnitType::Ranged => "弓兵",
+            UnitType::Siege => "攻城兵器",
+            UnitType::Support => "支援兵",
+        }
+    }
+}
+
+/// 生産の登録に失敗した理由
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProductionError {
+    /// 勢力が所有する都市・拠点ではない
+    InvalidSite(MapPosition),
+    /// 資源が足りない
+    InsufficientFunds {
+        required: Resources,
+        available: Resources,
+    },
+}
+
+impl fmt::Display for ProductionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProductionError::InvalidSite(position) => {
+                write!(f, "位置{:?}は生産できる都市・拠点ではありません", position)
+            }
+            ProductionError::InsufficientFunds {
+                required,
+                available,
+            } => write!(
+                f,
+                "資源が不足しています（必要: 資金{} 物資{}、所持: 資金{} 物資{}）",
+                required.gold, required.supplies, available.gold, available.supplies
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProductionError {}
+
+/// 新しいユニットIDを払い出す
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnitIdAllocator {
+    next_id: u32,
+}
+
+impl UnitIdAllocator {
+    /// 指定したIDから払い出しを始める
+    pub fn new(first_id: u32) -> Self {
+        Self { next_id: first_id }
+    }
+
+    /// 既存ユニットのIDと重複しないように払い出しを始める
+    pub fn after<'a>(units: impl IntoIterator<Item = &'a Unit>) -> Self {
+        let max_id = units.into_iter().map(|unit| unit.id).max().unwrap_or(0);
+        Self::new(max_id + 1)
+    }
+
+    /// 次のIDを払い出す
+    pub fn allocate(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+impl Default for UnitIdAllocator {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+/// 生産中のユニット
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProductionOrder {
+    unit_type: UnitType,
+    remaining_turns: u32,
+}
+
+/// 都市・拠点ごとの生産キュー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductionQueue {
+    position: MapPosition,
+    faction_id: u32,
+    orders: VecDeque<ProductionOrder>,
+}
+
+impl ProductionQueue {
+    pub fn new(position: MapPosition, faction_id: u32) -> Self {
+        Self {
+            position,
+            faction_id,
+            orders: VecDeque::new(),
+        }
+    }
+
+    /// 生産拠点の位置
+    pub fn position(&self) -> MapPosition {
+        self.position
+    }
+
+    /// 生産している勢力のID
+    pub fn faction_id(&self) -> u32 {
+        self.faction_id
+    }
+
+    /// 生産待ちのユニット種別（先頭が生産中）
+    pub fn pending(&self) -> Vec<UnitType> {
+        self.orders.iter().map(|order| order.unit_type).collect()
+    }
+
+    /// 生産中のユニットが完成するまでの残りターン数
+    pub fn remaining_turns(&self) -> Option<u32> {
+        self.orders.front().map(|order| order.remaining_turns)
+    }
+
+    /// 生産を登録し、勢力の資源からコストを支払う
+    pub fn enqueue(
+        &mut self,
+        factions: &mut FactionManager,
+        unit_type: UnitType,
+    ) -> Result<(), ProductionError> {
+        let required = unit_type.production_cost();
+        let available = factions.resources(self.faction_id);
+        if !factions
+            .get_mut(self.faction_id)
+            .is_some_and(|faction| faction.spend_resources(&required))
+        {
+            return Err(ProductionError::InsufficientFunds {
+                required,
+                available,
+            });
+        }
+        self.orders.push_back(ProductionOrder {
+            unit_type,
+            remaining_turns: unit_type.build_turns(),
+        });
+        Ok(())
+    }
+
+    /// 生産を1ターン進め、完成したユニットを拠点の隣に配置して返す
+    ///
+    /// 隣接セルがすべて塞がっている場合は完成したまま次のターンまで待つ。
+    pub fn tick(
+        &mut self,
+        map: &Map,
+        is_occupied: impl Fn(&MapPosition) -> bool,
+        ids: &mut UnitIdAllocator,
+    ) -> Option<Unit> {
+        let order = self.orders.front_mut()?;
+        order.remaining_turns = order.remaining_turns.saturating_sub(1);
+        if order.remaining_turns > 0 {
+            return None;
+        }
+
+        let unit_type = order.unit_type;
+        let position = map
+            .neighbors(self.position, false)
+            .into_iter()
+            .find(|pos| !is_occupied(pos) && map.movement_cost_at(pos, unit_type).is_some())?;
+        self.orders.pop_front();
+        let id = ids.allocate();
+        Some(Unit::new(
+            id,
+            format!("{}{}", unit_type.display_name(), id),
+            unit_type,
+            self.faction_id,
+            position,
+        ))
+    }
+}
+
+/// 全拠点の生産キューとユニットIDの払い出しを管理する
+#[derive(Debug, Clone, Default)]
+pub struct Production {
+    queues: HashMap<MapPosition, ProductionQueue>,
+    ids: UnitIdAllocator,
+}
+
+impl Production {
+    pub fn new(ids: UnitIdAllocator) -> Self {
+        Self {
+            queues: HashMap::new(),
+            ids,
+        }
+    }
+
+    /// 指定位置の生産キュー
+    pub fn queue(&self, position: &MapPosition) -> Option<&ProductionQueue> {
+        self.queues.get(position)
+    }
+
+    /// 都市・拠点でユニットの生産を登録する
+    ///
+    /// セルを所有する勢力の資源からコストを支払う。
+    pub fn enqueue(
+        &mut self,
+        map: &Map,
+        factions: &mut FactionManager,
+        position: MapPosition,
+        unit_type: UnitType,
+    ) -> Result<(), ProductionError> {
+        let faction_id = map
+            .get_cell(&position)
+            .filter(|cell| cell.is_capturable())
+            .and_then(|cell| cell.faction_id)
+            .ok_or(ProductionError::InvalidSite(position))?;
+
+        let queue = self
+            .queues
+            .entry(position)
+            .or_insert_with(|| ProductionQueue::new(position, faction_id));
+        if queue.faction_id != faction_id {
+            // 占領された拠点の生産は引き継がない
+            *queue = ProductionQueue::new(position, faction_id);
+        }
+        queue.enqueue(factions, unit_type)
+    }
+
+    /// 勢力の全拠点の生産を1ターン進め、完成したユニットを返す
+    ///
+    /// 所有者が変わった拠点の生産キューは破棄する。
+    pub fn tick(
+        &mut self,
+        map: &Map,
+        faction_id: u32,
+        is_occupied: impl Fn(&MapPosition) -> bool,
+    ) -> Vec<Unit> {
+        self.queues.retain(|position, queue| {
+            map.get_cell(position)
+                .is_some_and(|cell| cell.faction_id == Some(queue.faction_id))
+        });
+
+        let mut positions: Vec<MapPosition> = self
+            .queues
+            .values()
+            .filter(|queue| queue.faction_id == faction_id)
+            .map(|queue| queue.position)
+            .collect();
+        positions.sort_by_key(|pos| (pos.y, pos.x));
+
+        let mut spawned: Vec<Unit> = Vec::new();
+        for position in positions {
+            let queue = self.queues.get_mut(&position).expect("収集済みの位置");
+            let occupied =
+                |pos: &MapPosition| is_occupied(pos) || spawned.iter().any(|u| u.position == *pos);
+            if let Some(unit) = queue.tick(map, occupied, &mut self.ids) {
+                spawned.push(unit);
+            }
+        }
+        spawned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::faction::{Faction, FactionType};
+    use crate::map::{Cell, CellType, Structure};
+
+    fn create_factions(gold: i64) -> FactionManager {
+        let mut factions = FactionManager::new();
+        factions.add_faction(Faction::new(
+            1,
+            "勢力1".to_string(),
+            FactionType::Player,
+            (0, 0, 0),
+        ));
+        factions.get_mut(1).unwrap().gold = gold;
+        factions
+    }
+
+    #[test]
+    fn test_enqueue_deducts_cost() {
+        let base = MapPosition::new(2, 2);
+        let mut map = Map::new(5, 5);
+        map.set_cell(
+            base,
+            Cell::with_faction(CellType::Plain, 1).with_structure(Structure::Base),
+        );
+        let mut factions = create_factions(25);
+        let mut production = Production::default();
+
+        production
+            .enqueue(&map, &mut factions, base, UnitType::Infantry)
+            .unwrap();
+        assert_eq!(factions.resources(1).gold, 15);
+
+        let error = production
+            .enqueue(&map, &mut factions, base, UnitType::Cavalry)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            ProductionError::InsufficientFunds {
+                required: Resources::new(20, 0),
+                available: Resources::new(15, 0),
+            }
+        );
+        assert_eq!(factions.resources(1).gold, 15);
+
+        // 所有されていないセルでは生産できない
+        assert_eq!(
+            production.enqueue(
+                &map,
+                &mut factions,
+                MapPosition::new(0, 0),
+                UnitType::Infantry
+            ),
+            Err(ProductionError::InvalidSite(MapPosition::new(0, 0)))
+        );
+        assert_eq!(
+            production.queue(&base).unwrap().pending(),
+            vec![UnitType::Infantry]
+        );
+    }
+
+    #[test]
+    fn test_spawn_after_build_turns() {
+        let base = MapPosition::new(2, 2);
+        let mut map = Map::new(5, 5);
+        map.set_cell(
+            base,
+            Cell::with_faction(CellType::Plain, 1).with_structure(Structure::City { level: 1 }),
+        );
+        let mut factions = create_factions(100);
+        let mut production = Production::new(UnitIdAllocator::new(10));
+
+        production
+            .enqueue(&map, &mut factions, base, UnitType::Cavalry)
+            .unwrap();
+        assert!(production.tick(&map, 1, |_| false).is_empty());
+        assert_eq!(production.queue(&base).unwrap().remaining_turns(), Some(1));
+
+        let spawned = production.tick(&map, 1, |_| false);
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].id, 10);
+        assert_eq!(spawned[0].unit_type, UnitType::Cavalry);
+        assert_eq!(spawned[0].faction_id, 1);
+        // 最初の隣接セル（上）に配置される
+        assert_eq!(spawned[0].position, MapPosition::new(2, 1));
+        assert!(production.queue(&base).unwrap().pending().is_empty());
+    }
+
+    #[test]
+    fn test_spawn_waits_for_free_neighbor() {
+        let base = MapPosition::new(0, 0);
+        let mut map = Map::new(5, 5);
+        map.set_cell(
+            base,
+            Cell::with_faction(CellType::Plain, 1).with_structure(Structure::Base),
+        );
+        map.set_cell(MapPosition::new(0, 1), Cell::new(CellType::Water));
+        let mut factions = create_factions(100);
+        let mut production = Production::default();
+        production
+            .enqueue(&map, &mut factions, base, UnitType::Infantry)
+            .unwrap();
+
+        // 右は他ユニット、下は水域で塞がっている
+        let blocker = MapPosition::new(1, 0);
+        assert!(production.tick(&map, 1, |pos| *pos == blocker).is_empty());
+        assert_eq!(production.queue(&base).unwrap().remaining_turns(), Some(0));
+
+        let spawned = production.tick(&map, 1, |_| false);
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].position, blocker);
+    }
+}
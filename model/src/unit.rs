@@ -1,7 +1,8 @@
-use crate::map::MapPosition;
+use crate::map::{Direction, MapPosition};
+use serde::{Deserialize, Serialize};
 
 /// ユニットの種類
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum UnitType {
     Infantry, // 歩兵
     Cavalry,  // 騎兵
@@ -43,10 +44,30 @@ impl UnitType {
             UnitType::Support => 7,
         }
     }
+
+    /// ユニットの視界半径を返す
+    pub fn sight_radius(&self) -> u32 {
+        match self {
+            UnitType::Infantry => 2,
+            UnitType::Cavalry => 3,
+            UnitType::Ranged => 3,
+            UnitType::Siege => 1,
+            UnitType::Support => 2,
+        }
+    }
+
+    /// ユニットの攻撃射程を返す（マップの距離で数える）
+    pub fn attack_range(&self) -> u32 {
+        match self {
+            UnitType::Ranged => 2,
+            UnitType::Siege => 3,
+            UnitType::Infantry | UnitType::Cavalry | UnitType::Support => 1,
+        }
+    }
 }
 
 /// ユニットの状態
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum UnitStatus {
     Idle,      // 待機
     Moving,    // 移動中
@@ -56,8 +77,45 @@ pub enum UnitStatus {
     Wounded,   // 負傷
 }
 
+/// 経験値曲線
+///
+/// レベルLからL+1へ上がるには累計で `xp_per_level * L` の経験値が必要。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExperienceCurve {
+    pub xp_per_level: u32,
+}
+
+impl ExperienceCurve {
+    /// 指定レベルに到達するのに必要な累計経験値
+    pub fn required_for(&self, level: u32) -> u32 {
+        self.xp_per_level * level.saturating_sub(1)
+    }
+}
+
+impl Default for ExperienceCurve {
+    fn default() -> Self {
+        Self { xp_per_level: 100 }
+    }
+}
+
+/// レベルアップ1回分の成長内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelUp {
+    pub new_level: u32,
+    pub attack_increase: i32,
+    pub defense_increase: i32,
+    pub max_health_increase: u32,
+}
+
+/// レベルアップごとの攻撃力上昇
+const LEVEL_UP_ATTACK: i32 = 1;
+/// レベルアップごとの防御力上昇
+const LEVEL_UP_DEFENSE: i32 = 1;
+/// レベルアップごとの最大体力上昇
+const LEVEL_UP_MAX_HEALTH: u32 = 5;
+
 /// ゲーム内のユニット
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Unit {
     pub id: u32,
     pub name: String,
@@ -65,8 +123,11 @@ pub struct Unit {
     pub faction_id: u32,
     pub position: MapPosition,
     pub health: u32,
+    pub max_health: u32,
     pub experience: u32,
+    pub level: u32,
     pub status: UnitStatus,
+    pub facing: Direction, // 向き（移動方向に合わせて変わる）
     // 追加の属性
     pub movement_points: u32,
     pub attack_bonus: i32,
@@ -90,8 +151,11 @@ impl Unit {
             faction_id,
             position,
             health: 100,
+            max_health: 100,
             experience: 0,
+            level: 1,
             status: UnitStatus::Idle,
+            facing: Direction::default(),
             movement_points,
             attack_bonus: 0,
             defense_bonus: 0,
@@ -101,26 +165,34 @@ impl Unit {
     /// ユニットの現在の攻撃力を計算
     pub fn attack_power(&self) -> u32 {
         let base = self.unit_type.base_attack();
-        let exp_bonus = (self.experience / 100) as i32; // 経験値ごとに攻撃力ボーナス
-        let health_factor = self.health as f32 / 100.0; // 体力による減衰
+        let health_factor = self.health_factor(); // 体力による減衰
 
-        let total = (base as i32 + self.attack_bonus + exp_bonus) as f32 * health_factor;
+        let total = (base as i32 + self.attack_bonus) as f32 * health_factor;
         total.max(1.0) as u32 // 最低でも1の攻撃力を確保
     }
 
     /// ユニットの現在の防御力を計算
     pub fn defense_power(&self) -> u32 {
         let base = self.unit_type.base_defense();
-        let exp_bonus = (self.experience / 150) as i32; // 経験値ごとに防御力ボーナス
-        let health_factor = self.health as f32 / 100.0; // 体力による減衰
+        let health_factor = self.health_factor(); // 体力による減衰
 
-        let total = (base as i32 + self.defense_bonus + exp_bonus) as f32 * health_factor;
+        let total = (base as i32 + self.defense_bonus) as f32 * health_factor;
         total.max(1.0) as u32 // 最低でも1の防御力を確保
     }
 
+    /// 最大体力に対する現在体力の割合
+    fn health_factor(&self) -> f32 {
+        if self.max_health == 0 {
+            0.0
+        } else {
+            self.health as f32 / self.max_health as f32
+        }
+    }
+
     /// ユニットの移動
     pub fn move_to(&mut self, new_position: MapPosition, cost: u32) -> bool {
         if self.movement_points >= cost {
+            self.face_towards(new_position);
             self.position = new_position;
             self.movement_points -= cost;
             self.status = if self.movement_points == 0 {
@@ -134,6 +206,14 @@ impl Unit {
         }
     }
 
+    /// 指定位置の方向を向く（同じ位置なら向きを変えない）
+    pub fn face_towards(&mut self, target: MapPosition) {
+        let (dx, dy) = (target.x - self.position.x, target.y - self.position.y);
+        if let Some(facing) = Direction::cardinal_from_delta(dx, dy) {
+            self.facing = facing;
+        }
+    }
+
     /// ターン開始時のリセット
     pub fn reset_for_new_turn(&mut self) {
         self.movement_points = self.unit_type.base_movement();
@@ -159,10 +239,40 @@ impl Unit {
         }
     }
 
-    /// 経験値を獲得
-    pub fn gain_experience(&mut self, amount: u32) {
+    /// 経験値を獲得（デフォルトの経験値曲線でレベルアップを判定）
+    pub fn gain_experience(&mut self, amount: u32) -> Vec<LevelUp> {
+        self.gain_experience_with_curve(amount, &ExperienceCurve::default())
+    }
+
+    /// 経験値を獲得し、指定した経験値曲線に従ってレベルアップする
+    ///
+    /// レベルアップごとに攻撃・防御ボーナスと最大体力が上昇し、上昇分だけ体力も回復する。
+    /// 倒されたユニット（体力0）は経験値を得ず、レベルアップで復活することもない。
+    pub fn gain_experience_with_curve(
+        &mut self,
+        amount: u32,
+        curve: &ExperienceCurve,
+    ) -> Vec<LevelUp> {
+        if self.health == 0 {
+            return Vec::new();
+        }
         self.experience += amount;
-        // レベルアップのロジックは別途実装
+
+        let mut level_ups = Vec::new();
+        while curve.xp_per_level > 0 && self.experience >= curve.required_for(self.level + 1) {
+            self.level += 1;
+            self.attack_bonus += LEVEL_UP_ATTACK;
+            self.defense_bonus += LEVEL_UP_DEFENSE;
+            self.max_health += LEVEL_UP_MAX_HEALTH;
+            self.health += LEVEL_UP_MAX_HEALTH;
+            level_ups.push(LevelUp {
+                new_level: self.level,
+                attack_increase: LEVEL_UP_ATTACK,
+                defense_increase: LEVEL_UP_DEFENSE,
+                max_health_increase: LEVEL_UP_MAX_HEALTH,
+            });
+        }
+        level_ups
     }
 }
 
@@ -180,6 +290,10 @@ mod tests {
 
         assert_eq!(UnitType::Infantry.base_defense(), 10);
         assert_eq!(UnitType::Ranged.base_defense(), 6);
+
+        assert_eq!(UnitType::Cavalry.attack_range(), 1);
+        assert_eq!(UnitType::Ranged.attack_range(), 2);
+        assert_eq!(UnitType::Siege.attack_range(), 3);
     }
 
     #[test]
@@ -229,6 +343,31 @@ mod tests {
         assert_eq!(unit.position.y, 4);
     }
 
+    #[test]
+    fn test_unit_facing() {
+        let mut unit = Unit::new(
+            1,
+            "テスト騎兵".to_string(),
+            UnitType::Cavalry,
+            1,
+            MapPosition::new(5, 5),
+        );
+        assert_eq!(unit.facing, Direction::S);
+
+        assert!(unit.move_to(MapPosition::new(6, 5), 1));
+        assert_eq!(unit.facing, Direction::E);
+        assert!(unit.move_to(MapPosition::new(6, 3), 1));
+        assert_eq!(unit.facing, Direction::N);
+        assert!(unit.move_to(MapPosition::new(4, 3), 1));
+        assert_eq!(unit.facing, Direction::W);
+
+        // 移動できなかった場合や同じ位置では向きは変わらない
+        assert!(!unit.move_to(MapPosition::new(4, 9), 5));
+        assert_eq!(unit.facing, Direction::W);
+        unit.face_towards(unit.position);
+        assert_eq!(unit.facing, Direction::W);
+    }
+
     #[test]
     fn test_unit_damage() {
         let position = MapPosition::new(0, 0);
@@ -285,10 +424,61 @@ mod tests {
         assert_eq!(unit.attack_power(), 7); // (10 + 5) * 0.5 = 7.5 → 7
         assert_eq!(unit.defense_power(), 6); // (10 + 3) * 0.5 = 6.5 → 6
 
-        // 経験値の影響
+        // 経験値の影響（レベルアップによるボーナス）
         unit.health = 100; // 体力を戻す
-        unit.experience = 300;
+        unit.gain_experience(300); // レベル4
         assert_eq!(unit.attack_power(), 18); // 10 + 5 + 3 = 18
-        assert_eq!(unit.defense_power(), 15); // 10 + 3 + 2 = 15
+        assert_eq!(unit.defense_power(), 16); // 10 + 3 + 3 = 16
+    }
+
+    #[test]
+    fn test_unit_level_up() {
+        let position = MapPosition::new(0, 0);
+        let mut unit = Unit::new(1, "テスト歩兵".to_string(), UnitType::Infantry, 1, position);
+        assert_eq!(unit.level, 1);
+
+        // レベルアップに届かない経験値
+        assert!(unit.gain_experience(50).is_empty());
+        assert_eq!(unit.level, 1);
+
+        // 合計250でレベル3
+        let level_ups = unit.gain_experience(200);
+        assert_eq!(level_ups.len(), 2);
+        assert_eq!(level_ups[0].new_level, 2);
+        assert_eq!(level_ups[1].new_level, 3);
+        assert_eq!(unit.level, 3);
+        assert_eq!(unit.attack_bonus, 2);
+        assert_eq!(unit.defense_bonus, 2);
+        assert_eq!(unit.max_health, 110);
+        assert_eq!(unit.health, 110);
+        assert_eq!(unit.attack_power(), 12);
+    }
+
+    #[test]
+    fn test_destroyed_unit_gains_no_experience() {
+        let position = MapPosition::new(0, 0);
+        let mut unit = Unit::new(1, "テスト歩兵".to_string(), UnitType::Infantry, 1, position);
+        unit.gain_experience(90);
+        assert!(!unit.take_damage(unit.health));
+
+        // レベルアップに届く経験値でも体力0のまま
+        assert!(unit.gain_experience(50).is_empty());
+        assert_eq!(unit.experience, 90);
+        assert_eq!(unit.level, 1);
+        assert_eq!(unit.health, 0);
+    }
+
+    #[test]
+    fn test_custom_experience_curve() {
+        let position = MapPosition::new(0, 0);
+        let mut unit = Unit::new(1, "テスト騎兵".to_string(), UnitType::Cavalry, 1, position);
+        let curve = ExperienceCurve { xp_per_level: 50 };
+
+        assert_eq!(curve.required_for(1), 0);
+        assert_eq!(curve.required_for(3), 100);
+
+        let level_ups = unit.gain_experience_with_curve(250, &curve);
+        assert_eq!(level_ups.len(), 5);
+        assert_eq!(unit.level, 6);
     }
 }
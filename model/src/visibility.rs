@@ -0,0 +1,215 @@
+use crate::map::{CellType, Map, MapPosition};
+use crate::unit::Unit;
+
+/// セルの視界状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Visibility {
+    #[default]
+    Unknown, // 未踏（地形も不明）
+    Explored, // 探索済み（地形のみ判明、現在は視界外）
+    Visible,  // 視界内
+}
+
+/// 視界の判定方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SightRule {
+    #[default]
+    Radius, // 視界半径内をすべて見通す
+    LineOfSight, // 山岳に遮られたセルは見えない
+}
+
+/// 勢力ごとの視界マップ
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisibilityMap {
+    width: u32,
+    height: u32,
+    faction_id: u32,
+    cells: Vec<Visibility>,
+}
+
+impl VisibilityMap {
+    /// すべてのセルが未踏の視界マップを作成
+    pub fn new(width: u32, height: u32, faction_id: u32) -> Self {
+        Self {
+            width,
+            height,
+            faction_id,
+            cells: vec![Visibility::Unknown; (width * height) as usize],
+        }
+    }
+
+    /// この視界マップの勢力ID
+    pub fn faction_id(&self) -> u32 {
+        self.faction_id
+    }
+
+    /// 指定位置の視界状態（範囲外は未踏扱い）
+    pub fn get(&self, pos: &MapPosition) -> Visibility {
+        self.index(pos)
+            .map_or(Visibility::Unknown, |index| self.cells[index])
+    }
+
+    /// 指定位置が視界内かどうか
+    pub fn is_visible(&self, pos: &MapPosition) -> bool {
+        self.get(pos) == Visibility::Visible
+    }
+
+    /// 勢力のユニット位置から視界を再計算する
+    ///
+    /// 現在視界内のセルは探索済みに戻してから、各ユニットの視界半径内を視界内にする。
+    /// 一度探索したセルは未踏に戻らない。
+    pub fn update<'a>(
+        &mut self,
+        map: &Map,
+        units: impl IntoIterator<Item = &'a Unit>,
+        rule: SightRule,
+    ) {
+        for cell in &mut self.cells {
+            if *cell == Visibility::Visible {
+                *cell = Visibility::Explored;
+            }
+        }
+
+        for unit in units {
+            if unit.faction_id != self.faction_id {
+                continue;
+            }
+            let origin = unit.position;
+            for pos in map.positions_within(origin, unit.unit_type.sight_radius()) {
+                if rule == SightRule::LineOfSight && !has_line_of_sight(map, origin, pos) {
+                    continue;
+                }
+                if let Some(index) = self.index(&pos) {
+                    self.cells[index] = Visibility::Visible;
+                }
+            }
+        }
+    }
+
+    fn index(&self, pos: &MapPosition) -> Option<usize> {
+        if pos.x >= 0 && pos.y >= 0 && pos.x < self.width as i32 && pos.y < self.height as i32 {
+            Some((pos.y as u32 * self.width + pos.x as u32) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+/// 2点間の視線が通っているか（始点と終点を除く経路上の山岳が視線を遮る）
+fn has_line_of_sight(map: &Map, from: MapPosition, to: MapPosition) -> bool {
+    // ブレゼンハムのアルゴリズムで経路上のセルを辿る
+    let dx = (to.x - from.x).abs();
+    let dy = -(to.y - from.y).abs();
+    let sx = if from.x < to.x { 1 } else { -1 };
+    let sy = if from.y < to.y { 1 } else { -1 };
+    let mut err = dx + dy;
+    let mut current = from;
+
+    while current != to {
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            current.x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            current.y += sy;
+        }
+        if current != to
+            && map
+                .get_cell(&current)
+                .is_some_and(|cell| cell.cell_type == CellType::Mountain)
+        {
+            return false;
+        }
+    }
+    true
+}
+
+impl Map {
+    /// 勢力のユニット位置から視界マップを計算する（視界半径のみで判定）
+    pub fn compute_visibility<'a>(
+        &self,
+        units: impl IntoIterator<Item = &'a Unit>,
+        faction_id: u32,
+    ) -> VisibilityMap {
+        let mut visibility = VisibilityMap::new(self.width, self.height, faction_id);
+        visibility.update(self, units, SightRule::Radius);
+        visibility
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Cell;
+    use crate::unit::UnitType;
+
+    fn create_unit(id: u32, faction_id: u32, x: i32, y: i32) -> Unit {
+        Unit::new(
+            id,
+            format!("ユニット{}", id),
+            UnitType::Infantry,
+            faction_id,
+            MapPosition::new(x, y),
+        )
+    }
+
+    #[test]
+    fn test_visibility_radius() {
+        let map = Map::new(10, 10);
+        let units = vec![create_unit(1, 1, 5, 5), create_unit(2, 2, 0, 0)];
+        let visibility = map.compute_visibility(&units, 1);
+        let radius = UnitType::Infantry.sight_radius() as i32;
+
+        for x in 0..10 {
+            for y in 0..10 {
+                let pos = MapPosition::new(x, y);
+                let expected = (x - 5).abs() + (y - 5).abs() <= radius;
+                assert_eq!(visibility.is_visible(&pos), expected, "{:?}", pos);
+            }
+        }
+        // 他勢力のユニットの周囲は見えない
+        assert_eq!(visibility.get(&MapPosition::new(0, 0)), Visibility::Unknown);
+        assert_eq!(
+            visibility.get(&MapPosition::new(-1, 0)),
+            Visibility::Unknown
+        );
+    }
+
+    #[test]
+    fn test_visibility_keeps_explored_cells() {
+        let map = Map::new(10, 10);
+        let mut units = vec![create_unit(1, 1, 2, 2)];
+        let mut visibility = map.compute_visibility(&units, 1);
+
+        units[0].position = MapPosition::new(7, 7);
+        visibility.update(&map, &units, SightRule::Radius);
+
+        assert_eq!(
+            visibility.get(&MapPosition::new(2, 2)),
+            Visibility::Explored
+        );
+        assert_eq!(visibility.get(&MapPosition::new(7, 7)), Visibility::Visible);
+        assert_eq!(visibility.get(&MapPosition::new(9, 0)), Visibility::Unknown);
+    }
+
+    #[test]
+    fn test_line_of_sight_is_optional() {
+        let mut map = Map::new(10, 10);
+        map.set_cell(MapPosition::new(5, 4), Cell::new(CellType::Mountain));
+        let units = vec![create_unit(1, 1, 5, 5)];
+        let behind = MapPosition::new(5, 3);
+
+        // 半径のみの判定では山岳の向こうも見える
+        let visibility = map.compute_visibility(&units, 1);
+        assert!(visibility.is_visible(&behind));
+
+        // 視線判定では山岳自体は見えるが、その向こうは見えない
+        let mut visibility = VisibilityMap::new(10, 10, 1);
+        visibility.update(&map, &units, SightRule::LineOfSight);
+        assert!(visibility.is_visible(&MapPosition::new(5, 4)));
+        assert!(!visibility.is_visible(&behind));
+        assert!(visibility.is_visible(&MapPosition::new(6, 4)));
+    }
+}